@@ -0,0 +1,158 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, LitStr, Type};
+
+/// Derive `crate::schema::JsonSchema` from a type's serde attributes.
+///
+/// Structs with named fields become objects: field names follow
+/// `#[serde(rename_all = "camelCase")]` and `#[serde(rename = "…")]`, and
+/// a field is optional when it is an `Option` or carries
+/// `#[serde(default)]` or `#[serde(skip_serializing_if = "…")]`.  Enums
+/// of unit variants become string enums.  `#[serde(skip)]` fields are left
+/// out.  Any other serde attribute is a compile error, so the schema cannot
+/// silently drift from what serde reads and writes.
+#[proc_macro_derive(JsonSchema)]
+pub fn derive_json_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let container = SerdeAttrs::parse(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let Fields::Named(fields) = &data.fields else {
+                return Err(syn::Error::new_spanned(name, "expected named fields"));
+            };
+            let mut entries = Vec::new();
+            for field in &fields.named {
+                let attrs = SerdeAttrs::parse(&field.attrs)?;
+                if attrs.skip {
+                    continue;
+                }
+                let ident = field.ident.as_ref().expect("named field");
+                let key = match (attrs.rename, container.rename_all) {
+                    (Some(rename), _) => rename,
+                    (None, true) => snake_to_camel(&ident.to_string()),
+                    (None, false) => ident.to_string(),
+                };
+                let ty = &field.ty;
+                let optional = attrs.default
+                    || attrs.skip_serializing_if
+                    || container.default
+                    || is_option(ty);
+                entries.push(if optional {
+                    quote! { crate::schema::optional::<#ty>(#key) }
+                } else {
+                    quote! { crate::schema::required::<#ty>(#key) }
+                });
+            }
+            quote! { crate::schema::object(vec![#(#entries),*]) }
+        }
+        Data::Enum(data) => {
+            let mut values = Vec::new();
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(variant, "expected a unit variant"));
+                }
+                let attrs = SerdeAttrs::parse(&variant.attrs)?;
+                values.push(match (attrs.rename, container.rename_all) {
+                    (Some(rename), _) => rename,
+                    (None, true) => pascal_to_camel(&variant.ident.to_string()),
+                    (None, false) => variant.ident.to_string(),
+                });
+            }
+            quote! { crate::schema::string_enum(&[#(#values),*]) }
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(name, "unions are not supported")),
+    };
+    Ok(quote! {
+        impl crate::schema::JsonSchema for #name {
+            fn schema() -> serde_json::Value {
+                #body
+            }
+        }
+    })
+}
+
+/// The serde attributes that shape the schema.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename_all: bool,
+    rename: Option<String>,
+    default: bool,
+    skip: bool,
+    skip_serializing_if: bool,
+}
+
+impl SerdeAttrs {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = SerdeAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    let rule: LitStr = meta.value()?.parse()?;
+                    if rule.value() != "camelCase" {
+                        return Err(meta.error("only rename_all = \"camelCase\" is supported"));
+                    }
+                    out.rename_all = true;
+                } else if meta.path.is_ident("rename") {
+                    out.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("default") {
+                    if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse::<LitStr>()?;
+                    }
+                    out.default = true;
+                } else if meta.path.is_ident("skip") {
+                    out.skip = true;
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    meta.value()?.parse::<LitStr>()?;
+                    out.skip_serializing_if = true;
+                } else {
+                    return Err(meta.error("serde attribute not supported by JsonSchema"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// `semi_major_m` → `semiMajorM`, as serde's `camelCase` renames fields.
+fn snake_to_camel(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `ConvergedGradient` → `convergedGradient`, as serde's `camelCase`
+/// renames variants.
+fn pascal_to_camel(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,176 @@
+use std::any::Any;
+use std::io::{self, BufRead, Write};
+use std::panic::{self, UnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::SolveError;
+use crate::mode::{solve_request, SolverMode};
+use crate::to_json_string;
+
+/// Requests read ahead per worker thread before a chunk is solved; bounds
+/// memory while keeping all workers busy.
+const LINES_PER_WORKER: usize = 8;
+
+/// Settings of a `solve_batch` run.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BatchOptions {
+    /// Replaces the `model` of every request.
+    pub mode: Option<SolverMode>,
+    /// Worker threads; 0 uses all available cores.  Always 1 on wasm.
+    pub threads: usize,
+}
+
+/// Outcome counts of a `solve_batch` run.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BatchSummary {
+    pub solved: usize,
+    pub failed: usize,
+}
+
+// ── Batch solving ───────────────────────────────────────────────────────────
+
+/// Solve a JSON-lines stream of `SolveRequest`s.
+///
+/// Writes one line per non-blank input line, in input order:
+/// `{"line":N,"response":{…}}` or `{"line":N,"error":{code,message,details}}`
+/// with `N` the 1-based input line.  A failed request does not stop the
+/// run – neither does a line that is not UTF-8 (`invalidJson`) or a solve
+/// that panics (`internal`); only I/O errors do.  Requests are solved in
+/// parallel on native builds, a chunk at a time, so output streams as input
+/// is read.
+pub fn solve_batch<R: BufRead, W: Write>(
+    mut input: R,
+    mut output: W,
+    opts: &BatchOptions,
+) -> io::Result<BatchSummary> {
+    let workers = worker_count(opts.threads);
+    let mut summary = BatchSummary::default();
+    let mut line = 0;
+    let mut bytes = Vec::new();
+    let mut chunk: Vec<(usize, Result<String, SolveError>)> = Vec::new();
+    loop {
+        let mut eof = false;
+        while chunk.len() < workers * LINES_PER_WORKER {
+            bytes.clear();
+            if input.read_until(b'\n', &mut bytes)? == 0 {
+                eof = true;
+                break;
+            }
+            line += 1;
+            match std::str::from_utf8(&bytes) {
+                Ok(text) if text.trim().is_empty() => {}
+                Ok(text) => chunk.push((line, Ok(text.to_string()))),
+                Err(e) => chunk.push((
+                    line,
+                    Err(SolveError::InvalidJson {
+                        message: format!("line is not valid UTF-8: {e}"),
+                        line: 1,
+                        column: e.valid_up_to() + 1,
+                    }),
+                )),
+            }
+        }
+        for (line, result) in solve_chunk(&chunk, opts.mode, workers) {
+            let out = match result {
+                Ok(resp) => {
+                    summary.solved += 1;
+                    format!(r#"{{"line":{line},"response":{resp}}}"#)
+                }
+                Err(err) => {
+                    summary.failed += 1;
+                    format!(r#"{{"line":{line},"error":{err}}}"#)
+                }
+            };
+            writeln!(output, "{out}")?;
+        }
+        output.flush()?;
+        chunk.clear();
+        if eof {
+            return Ok(summary);
+        }
+    }
+}
+
+/// Solve `chunk` on up to `workers` threads; results keep the chunk order.
+/// Lines that could not be read pass their error through.  Errors are
+/// `SolveError` JSON.
+fn solve_chunk(
+    chunk: &[(usize, Result<String, SolveError>)],
+    mode: Option<SolverMode>,
+    workers: usize,
+) -> Vec<(usize, Result<String, String>)> {
+    let solve = |(line, json): &(usize, Result<String, SolveError>)| {
+        let result = match json {
+            Ok(json) => catch_panic(|| solve_request(json, mode)),
+            Err(e) => Err(e.clone()),
+        };
+        let result = result.map_err(|e| e.to_json());
+        (*line, result.and_then(|resp| to_json_string(&resp)))
+    };
+    let workers = workers.min(chunk.len());
+    if workers <= 1 {
+        return chunk.iter().map(solve).collect();
+    }
+
+    // Workers pull the next unsolved request, so one slow solve does not
+    // hold back a whole share of the chunk.
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let k = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = chunk.get(k) else {
+                            return done;
+                        };
+                        done.push((k, solve(item)));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("batch worker panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(k, _)| *k);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Run `solve`, turning a panic into an `internal` error so that one bad
+/// request cannot end the whole batch.
+pub(crate) fn catch_panic<T>(
+    solve: impl FnOnce() -> Result<T, SolveError> + UnwindSafe,
+) -> Result<T, SolveError> {
+    panic::catch_unwind(solve).unwrap_or_else(|payload| {
+        Err(SolveError::Internal {
+            message: panic_message(payload.as_ref()),
+        })
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn worker_count(requested: usize) -> usize {
+    if requested > 0 {
+        return requested;
+    }
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// wasm32-unknown-unknown has no threads.
+#[cfg(target_arch = "wasm32")]
+fn worker_count(_requested: usize) -> usize {
+    1
+}
@@ -0,0 +1,110 @@
+use solver::SolverMode;
+
+pub const USAGE: &str = "\
+Usage: posesolve [OPTIONS] [INPUT]
+
+Solve a SolveRequest JSON file (or stdin when INPUT is absent or `-`) and
+write the SolveResponse JSON to stdout.  With --batch, INPUT holds one
+request per line and every line gets a `{\"line\", \"response\"}` or
+`{\"line\", \"error\"}` output line.
+
+Options:
+  -p, --pretty         Pretty-print the response
+  -m, --mode <MODE>    Override the request's model:
+                         calibrated    focal length and distortion fixed
+                         focal         estimate the focal length
+                         full          focal, distortion and principal point
+                         uncalibrated  DLT, no focal length needed
+  -o, --output <FILE>  Write the response to FILE instead of stdout
+  -b, --batch          Solve JSON lines; failed requests become error lines
+  -j, --jobs <N>       Batch worker threads (default: all cores)
+  -h, --help           Print this help
+  -V, --version        Print the version
+
+Errors are written to stderr as SolveError JSON; the exit status is 1 when
+the solve (in batch mode: any request) failed and 2 for invalid arguments.";
+
+// ── Options ─────────────────────────────────────────────────────────────────
+
+/// Parsed command line of `posesolve`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CliOptions {
+    /// Input file; `None` or `-` reads stdin.
+    pub input: Option<String>,
+    /// Output file; `None` writes stdout.
+    pub output: Option<String>,
+    pub pretty: bool,
+    pub mode: Option<SolverMode>,
+    /// JSON-lines batch mode, see `solve_batch`.
+    pub batch: bool,
+    /// Batch worker threads; 0 uses all cores.
+    pub jobs: usize,
+    pub help: bool,
+    pub version: bool,
+}
+
+/// The solver mode named `name` on the command line.
+fn parse_mode(name: &str) -> Option<SolverMode> {
+    match name {
+        "calibrated" => Some(SolverMode::Calibrated),
+        "focal" => Some(SolverMode::Focal),
+        "full" => Some(SolverMode::Full),
+        "uncalibrated" => Some(SolverMode::Uncalibrated),
+        _ => None,
+    }
+}
+
+/// Parse the arguments after the program name.  The error is a message for
+/// stderr, to be followed by the usage.
+pub fn parse_args<I, S>(args: I) -> Result<CliOptions, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut opts = CliOptions::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        // `--mode=focal` is the same as `--mode focal`.
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = |name: &str| match inline.clone() {
+            Some(v) => Ok(v),
+            None => args
+                .next()
+                .map(|v| v.as_ref().to_string())
+                .ok_or_else(|| format!("{name} needs a value")),
+        };
+        match flag {
+            "-p" | "--pretty" => opts.pretty = true,
+            "-h" | "--help" => opts.help = true,
+            "-V" | "--version" => opts.version = true,
+            "-m" | "--mode" => {
+                let name = value(flag)?;
+                opts.mode =
+                    Some(parse_mode(&name).ok_or_else(|| format!("unknown solver mode `{name}`"))?);
+            }
+            "-o" | "--output" => opts.output = Some(value(flag)?),
+            "-b" | "--batch" => opts.batch = true,
+            "-j" | "--jobs" => {
+                let n = value(flag)?;
+                opts.jobs = n
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("{flag} needs a positive number, not `{n}`"))?;
+            }
+            _ if flag.starts_with('-') && flag != "-" => {
+                return Err(format!("unknown option `{arg}`"))
+            }
+            _ if opts.input.is_some() => return Err("only one INPUT may be given".to_string()),
+            _ => opts.input = Some(arg.to_string()),
+        }
+    }
+    if opts.batch && opts.pretty {
+        return Err("--pretty cannot be combined with --batch".to_string());
+    }
+    Ok(opts)
+}
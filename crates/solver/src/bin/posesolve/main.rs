@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+use solver::{solve_batch, solve_request, BatchOptions, SolveError};
+
+use cli::{parse_args, CliOptions, USAGE};
+
+mod cli;
+
+fn main() -> ExitCode {
+    let opts = match parse_args(std::env::args().skip(1)) {
+        Ok(opts) => opts,
+        Err(message) => {
+            eprintln!("posesolve: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    if opts.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    if opts.version {
+        println!("posesolve {}", env!("CARGO_PKG_VERSION"));
+        return ExitCode::SUCCESS;
+    }
+    if opts.batch {
+        return run_batch(&opts);
+    }
+    match run(&opts) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e.to_json());
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn run(opts: &CliOptions) -> Result<(), SolveError> {
+    let input = read_input(opts.input.as_deref()).map_err(|e| io_error("input", e))?;
+    let resp = solve_request(&input, opts.mode)?;
+    let out = if opts.pretty {
+        serde_json::to_string_pretty(&resp)
+    } else {
+        serde_json::to_string(&resp)
+    };
+    let mut out = out.map_err(|e| SolveError::Serialization {
+        message: e.to_string(),
+    })?;
+    out.push('\n');
+    match &opts.output {
+        Some(path) => std::fs::write(path, out),
+        None => io::stdout().lock().write_all(out.as_bytes()),
+    }
+    .map_err(|e| io_error("output", e))
+}
+
+fn run_batch(opts: &CliOptions) -> ExitCode {
+    let batch = BatchOptions {
+        mode: opts.mode,
+        threads: opts.jobs,
+    };
+    let result = open_input(opts.input.as_deref())
+        .map_err(|e| io_error("input", e))
+        .and_then(|input| {
+            let output: Box<dyn Write> = match &opts.output {
+                Some(path) => Box::new(File::create(path).map_err(|e| io_error("output", e))?),
+                None => Box::new(io::stdout().lock()),
+            };
+            solve_batch(input, BufWriter::new(output), &batch).map_err(|e| io_error("batch", e))
+        });
+    match result {
+        Ok(summary) => {
+            eprintln!(
+                "posesolve: solved {} of {} requests",
+                summary.solved,
+                summary.solved + summary.failed
+            );
+            if summary.failed == 0 {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::from(1)
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e.to_json());
+            ExitCode::from(1)
+        }
+    }
+}
+
+/// Buffered reader of `path`, or of stdin for `None` / `-`.
+fn open_input(path: Option<&str>) -> io::Result<Box<dyn BufRead>> {
+    Ok(match path {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(path) => Box::new(BufReader::new(File::open(path)?)),
+    })
+}
+
+/// Contents of `path`, or of stdin for `None` / `-`.
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        None | Some("-") => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            Ok(buf)
+        }
+        Some(path) => std::fs::read_to_string(path),
+    }
+}
+
+fn io_error(field: &str, e: io::Error) -> SolveError {
+    SolveError::InvalidInput {
+        field: field.to_string(),
+        message: format!("Cannot access the {field}: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests;
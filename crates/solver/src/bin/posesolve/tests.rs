@@ -0,0 +1,70 @@
+use solver::SolverMode;
+
+use crate::cli::{parse_args, CliOptions};
+
+fn args(line: &str) -> Result<CliOptions, String> {
+    parse_args(line.split_whitespace())
+}
+
+// ── Argument parsing ────────────────────────────────────────────────────────
+
+#[test]
+fn no_arguments_read_stdin_compactly() {
+    assert_eq!(args(""), Ok(CliOptions::default()));
+    assert_eq!(args("-").unwrap().input.as_deref(), Some("-"));
+}
+
+#[test]
+fn flags_and_input_are_parsed() {
+    let opts = args("--pretty -m full req.json -o out.json").unwrap();
+    assert_eq!(
+        opts,
+        CliOptions {
+            input: Some("req.json".to_string()),
+            output: Some("out.json".to_string()),
+            pretty: true,
+            mode: Some(SolverMode::Full),
+            ..CliOptions::default()
+        }
+    );
+    assert_eq!(
+        args("--mode=uncalibrated").unwrap().mode,
+        Some(SolverMode::Uncalibrated)
+    );
+    assert!(args("-h").unwrap().help);
+    assert!(args("--version").unwrap().version);
+}
+
+#[test]
+fn bad_arguments_are_reported() {
+    assert_eq!(
+        args("--mode fancy"),
+        Err("unknown solver mode `fancy`".to_string())
+    );
+    assert_eq!(args("--mode"), Err("--mode needs a value".to_string()));
+    assert_eq!(
+        args("--verbose"),
+        Err("unknown option `--verbose`".to_string())
+    );
+    assert_eq!(
+        args("a.json b.json"),
+        Err("only one INPUT may be given".to_string())
+    );
+}
+
+#[test]
+fn batch_flags_are_parsed() {
+    let opts = parse_args(["--batch", "-j", "3", "projects.jsonl"]).unwrap();
+    assert!(opts.batch);
+    assert_eq!(opts.jobs, 3);
+    assert_eq!(opts.input.as_deref(), Some("projects.jsonl"));
+
+    assert_eq!(
+        parse_args(["-b", "--jobs=0"]),
+        Err("--jobs needs a positive number, not `0`".to_string())
+    );
+    assert_eq!(
+        parse_args(["-b", "--pretty"]),
+        Err("--pretty cannot be combined with --batch".to_string())
+    );
+}
@@ -0,0 +1,158 @@
+use crate::optimizer::{
+    intrinsics_from_params, levenberg_marquardt, rotation_from_params, EnuCorrespondence,
+    OptResult, Problem, NUM_PARAMS,
+};
+use crate::pnp::minimal_sample_size;
+use crate::projection::project_point;
+use crate::rng::{SplitMix64, DEFAULT_SEED};
+use crate::types::{BootstrapCfg, BootstrapMethod};
+
+/// Number of refits when the request asks for 0.
+pub(crate) const DEFAULT_BOOTSTRAP_SAMPLES: usize = 200;
+
+/// Upper bound on the number of refits, to keep a solve interactive.
+pub(crate) const MAX_BOOTSTRAP_SAMPLES: usize = 2000;
+
+/// LM iterations per refit.  Every refit starts at the full solution and
+/// only has to absorb a small perturbation.
+const REFIT_ITERATIONS: usize = 50;
+
+/// How often case resampling redraws a sample with too few distinct
+/// correspondences before the sample is dropped.
+const MAX_REDRAWS: usize = 100;
+
+/// Outcome of `bootstrap`.
+pub(crate) struct BootstrapRun {
+    /// Refitted parameter vector of every usable sample.
+    pub samples: Vec<[f64; NUM_PARAMS]>,
+    /// Samples without a usable resample or with a diverged refit.
+    pub dropped: usize,
+}
+
+// ── Resampling ──────────────────────────────────────────────────────────────
+
+/// Bootstrap the fit `result` of `problem`: redraw the data
+/// `cfg.samples` times (see `BootstrapMethod`), refit each copy with LM
+/// from `result.params` and collect the refitted parameter vectors.
+///
+/// `problem.corrs` must be the correspondences of the final fit, with the
+/// σ that fit used (world-point and robust weights folded in).  The random
+/// stream is seeded from `cfg.seed`, so the clouds are reproducible.
+pub(crate) fn bootstrap(result: &OptResult, problem: &Problem, cfg: &BootstrapCfg) -> BootstrapRun {
+    let n_samples = match cfg.samples {
+        0 => DEFAULT_BOOTSTRAP_SAMPLES,
+        n => n.min(MAX_BOOTSTRAP_SAMPLES),
+    };
+    let mut rng = SplitMix64::new(cfg.seed.unwrap_or(DEFAULT_SEED));
+    let corrs = problem.corrs;
+    let n_free = result.free.iter().filter(|f| **f).count();
+    let min_distinct = minimal_sample_size(problem).min(corrs.len());
+    let residuals = scaled_residuals(&result.params, corrs, n_free);
+
+    let mut run = BootstrapRun {
+        samples: Vec::with_capacity(n_samples),
+        dropped: 0,
+    };
+    for _ in 0..n_samples {
+        let resampled = match cfg.method {
+            BootstrapMethod::Cases => resample_cases(corrs, min_distinct, &mut rng),
+            BootstrapMethod::Residuals => Some(resample_residuals(
+                &result.params,
+                corrs,
+                &residuals,
+                &mut rng,
+            )),
+        };
+        let refit = resampled.map(|c| {
+            levenberg_marquardt(result.params, &problem.with_corrs(&c), REFIT_ITERATIONS).params
+        });
+        match refit {
+            Some(params) if params.iter().all(|v| v.is_finite()) => run.samples.push(params),
+            _ => run.dropped += 1,
+        }
+    }
+    run
+}
+
+/// Draw `corrs.len()` correspondences with replacement, redrawing while
+/// fewer than `min_distinct` different ones were picked (the refit would
+/// be under-determined).  `None` when no such draw turned up.
+fn resample_cases(
+    corrs: &[EnuCorrespondence],
+    min_distinct: usize,
+    rng: &mut SplitMix64,
+) -> Option<Vec<EnuCorrespondence>> {
+    let n = corrs.len();
+    for _ in 0..MAX_REDRAWS {
+        let idx: Vec<usize> = (0..n).map(|_| rng.below(n)).collect();
+        let mut distinct = idx.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() >= min_distinct {
+            return Some(idx.iter().map(|&i| corrs[i].clone()).collect());
+        }
+    }
+    None
+}
+
+/// Residual of every correspondence in units of its σ, centred and
+/// inflated by `√(m / (m − p))` for `m` observations and `p` free
+/// parameters, so that the resampled residuals have the spread of the
+/// true errors rather than the (smaller) spread of the fitted ones.
+/// Points behind the camera get a zero residual.
+fn scaled_residuals(
+    params: &[f64; NUM_PARAMS],
+    corrs: &[EnuCorrespondence],
+    n_free: usize,
+) -> Vec<[f64; 2]> {
+    let cam = [params[0], params[1], params[2]];
+    let rot = rotation_from_params(params);
+    let intr = intrinsics_from_params(params);
+    let mut residuals: Vec<[f64; 2]> = corrs
+        .iter()
+        .map(|c| match project_point(c.enu, cam, &rot, &intr) {
+            Some((u, v)) => [(c.pixel[0] - u) / c.sigma, (c.pixel[1] - v) / c.sigma],
+            None => [0.0; 2],
+        })
+        .collect();
+
+    let n = residuals.len() as f64;
+    let mean = residuals
+        .iter()
+        .fold([0.0; 2], |m, r| [m[0] + r[0] / n, m[1] + r[1] / n]);
+    let n_obs = 2 * corrs.len();
+    let inflation = if n_obs > n_free {
+        (n_obs as f64 / (n_obs - n_free) as f64).sqrt()
+    } else {
+        1.0
+    };
+    for r in &mut residuals {
+        *r = [(r[0] - mean[0]) * inflation, (r[1] - mean[1]) * inflation];
+    }
+    residuals
+}
+
+/// Copy of `corrs` whose pixels are the fitted projections plus a residual
+/// drawn with replacement from `residuals` (rescaled to each point's σ).
+/// Points behind the camera keep their observed pixel.
+fn resample_residuals(
+    params: &[f64; NUM_PARAMS],
+    corrs: &[EnuCorrespondence],
+    residuals: &[[f64; 2]],
+    rng: &mut SplitMix64,
+) -> Vec<EnuCorrespondence> {
+    let cam = [params[0], params[1], params[2]];
+    let rot = rotation_from_params(params);
+    let intr = intrinsics_from_params(params);
+    corrs
+        .iter()
+        .map(|c| {
+            let mut out = c.clone();
+            if let Some((u, v)) = project_point(c.enu, cam, &rot, &intr) {
+                let r = residuals[rng.below(residuals.len())];
+                out.pixel = [u + r[0] * c.sigma, v + r[1] * c.sigma];
+            }
+            out
+        })
+        .collect()
+}
@@ -0,0 +1,800 @@
+use std::collections::HashMap;
+
+use crate::error::SolveError;
+use crate::estimator::solve_impl;
+use crate::geo::{enu_to_lla, lla_to_enu};
+use crate::linalg::{invert3, solve_dense};
+use crate::optimizer::LmControl;
+use crate::projection::{
+    dot, euler_from_rotation, mat3_mul, mat3_t_vec, mat3_vec, project_cam_point_jacobian,
+    rotation_enu_to_cam, rotation_from_vector, CameraIntrinsics, Mat3,
+};
+use crate::schema::SCHEMA_VERSION;
+use crate::triangulate::{intersect_rays, widest_angle_deg};
+use crate::types::{
+    BundleCamera, BundleDiagnostics, BundleImage, BundlePoint, BundleRequest, BundleResponse, Corr,
+    GaussianPrior, Image, Intrinsics, Pixel, Pose, Priors, SolveRequest, SolverModel,
+    TerminationReason, WorldLla,
+};
+use crate::validate::validate_bundle;
+
+/// Parameters per camera: position (3), rotation increment (3), focal
+/// length.
+const CAMERA_PARAMS: usize = 7;
+
+/// Located points (control or triangulated) an image needs to be posed.
+const MIN_LOCATED: usize = 4;
+
+/// Bundle LM iterations when the request does not say.
+const DEFAULT_MAX_ITERS: usize = 100;
+
+/// Tie points whose rays meet at less than this angle (deg) are not
+/// triangulated during initialisation: their depth is too uncertain.
+const MIN_TRIANGULATION_DEG: f64 = 1.0;
+
+// ── Problem ─────────────────────────────────────────────────────────────────
+
+#[derive(Clone)]
+struct Camera {
+    centre: [f64; 3],
+    /// ENU → camera.
+    rot: Mat3,
+    intr: CameraIntrinsics,
+    focal_free: bool,
+}
+
+enum PointKind {
+    /// Control point without σ: never moves.
+    Fixed,
+    /// Control point refined under a Gaussian prior of this σ (m).
+    Prior(f64),
+    /// Unknown tie point.
+    Tie,
+}
+
+struct Point {
+    id: String,
+    kind: PointKind,
+    /// ENU position of a control point (the prior mean for `Prior`).
+    known: Option<[f64; 3]>,
+    /// Slot among the estimated points, `None` for fixed ones.
+    slot: Option<usize>,
+}
+
+struct Obs {
+    camera: usize,
+    point: usize,
+    pixel: [f64; 2],
+    sigma: f64,
+}
+
+/// The adjustment: observations tie cameras to points.  `obs_of_point`
+/// lists, per point, the indices of its observations.
+struct Bundle {
+    points: Vec<Point>,
+    obs: Vec<Obs>,
+    obs_of_point: Vec<Vec<usize>>,
+    n_slots: usize,
+}
+
+// ── Entry point ─────────────────────────────────────────────────────────────
+
+/// Jointly adjust the poses of several images and the positions of their
+/// tie points.
+///
+/// 1. Control points fix the local ENU frame (centred on their mean).
+/// 2. Initialisation alternates between posing every image that sees at
+///    least `MIN_LOCATED` located points (through `solve_impl`) and
+///    triangulating tie points seen by two or more posed images, until
+///    nothing changes.
+/// 3. LM over all poses, free focal lengths and point positions.  Points
+///    are eliminated with the Schur complement, so each step only solves a
+///    dense `7·images` system however many tie points there are.
+#[allow(clippy::needless_range_loop)]
+pub fn bundle_adjust_impl(req: &BundleRequest) -> Result<BundleResponse, SolveError> {
+    validate_bundle(req)?;
+    let mut warnings = Vec::new();
+
+    let (ref_lat, ref_lon, ref_alt) = reference(req)?;
+    let mut bundle = build_bundle(req, ref_lat, ref_lon, ref_alt);
+    let mut positions: Vec<Option<[f64; 3]>> = bundle.points.iter().map(|p| p.known).collect();
+
+    // ── Initialisation ──────────────────────────────────────────────────
+    let mut cameras: Vec<Option<Camera>> = vec![None; req.images.len()];
+    let mut failures: Vec<Option<SolveError>> = vec![None; req.images.len()];
+    loop {
+        let mut progress = false;
+        for (c, im) in req.images.iter().enumerate() {
+            if cameras[c].is_some() {
+                continue;
+            }
+            let located: Vec<(&Obs, [f64; 3])> = bundle
+                .obs
+                .iter()
+                .filter(|o| o.camera == c)
+                .filter_map(|o| positions[o.point].map(|x| (o, x)))
+                .collect();
+            if located.len() < MIN_LOCATED {
+                continue;
+            }
+            match pose_image(im, &bundle, &located, (ref_lat, ref_lon, ref_alt)) {
+                Ok(camera) => {
+                    cameras[c] = Some(camera);
+                    failures[c] = None;
+                    progress = true;
+                }
+                Err(e) => failures[c] = Some(e),
+            }
+        }
+        for p in 0..bundle.points.len() {
+            if positions[p].is_none() {
+                positions[p] = triangulate(&bundle, p, &cameras);
+                progress |= positions[p].is_some();
+            }
+        }
+        if !progress {
+            break;
+        }
+    }
+    if let Some(c) = cameras.iter().position(Option::is_none) {
+        let id = &req.images[c].id;
+        let reason = match &failures[c] {
+            Some(e) => format!("image `{id}` could not be posed: {e}"),
+            None => format!(
+                "image `{id}` sees fewer than {MIN_LOCATED} control or triangulated tie points"
+            ),
+        };
+        return Err(SolveError::DegenerateGeometry { reason });
+    }
+    let mut cameras: Vec<Camera> = cameras.into_iter().flatten().collect();
+
+    // Tie points that could not be located take no part in the adjustment.
+    let dropped: Vec<&str> = (0..bundle.points.len())
+        .filter(|&p| positions[p].is_none())
+        .map(|p| bundle.points[p].id.as_str())
+        .collect();
+    if !dropped.is_empty() {
+        warnings.push(format!(
+            "{} tie point(s) seen in fewer than two posed images, or at too narrow an angle, were left out: {}.",
+            dropped.len(),
+            dropped.join(", ")
+        ));
+    }
+    bundle.drop_unlocated(&positions);
+    let mut points: Vec<[f64; 3]> = positions.iter().map(|x| x.unwrap_or([0.0; 3])).collect();
+
+    // ── Adjustment ──────────────────────────────────────────────────────
+    let max_iters = req.max_iters.unwrap_or(DEFAULT_MAX_ITERS);
+    let (iterations, termination) = bundle.optimise(&mut cameras, &mut points, max_iters);
+    match termination {
+        TerminationReason::MaxIterations => warnings.push(format!(
+            "Bundle adjustment did not fully converge in {iterations} iterations."
+        )),
+        TerminationReason::LambdaOverflow => warnings.push(
+            "Bundle adjustment stalled before converging; check for mismatched tie points."
+                .to_string(),
+        ),
+        _ => {}
+    }
+
+    Ok(bundle.response(
+        req,
+        &cameras,
+        &points,
+        (ref_lat, ref_lon, ref_alt),
+        BundleDiagnostics {
+            rmse_px: 0.0,
+            iterations,
+            termination,
+            warnings,
+        },
+    ))
+}
+
+/// Origin of the local ENU frame: the mean of the control points.
+fn reference(req: &BundleRequest) -> Result<(f64, f64, f64), SolveError> {
+    let n = req.control_points.len() as f64;
+    if n == 0.0 {
+        return Err(SolveError::InvalidInput {
+            field: "controlPoints".to_string(),
+            message: "Bundle adjustment needs control points to place the images".to_string(),
+        });
+    }
+    let sum = req.control_points.iter().fold([0.0; 3], |s, p| {
+        [
+            s[0] + p.world.lat,
+            s[1] + p.world.lon,
+            s[2] + p.world.alt.unwrap_or(0.0),
+        ]
+    });
+    Ok((sum[0] / n, sum[1] / n, sum[2] / n))
+}
+
+/// Points and observations of `req`: control points first, then tie
+/// points by first appearance.  Disabled observations are skipped.
+fn build_bundle(req: &BundleRequest, ref_lat: f64, ref_lon: f64, ref_alt: f64) -> Bundle {
+    let mut points = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for cp in &req.control_points {
+        let w = &cp.world;
+        let enu = lla_to_enu(
+            w.lat,
+            w.lon,
+            w.alt.unwrap_or(0.0),
+            ref_lat,
+            ref_lon,
+            ref_alt,
+        );
+        let kind = match w.sigma_m {
+            Some(s) if s > 0.0 => PointKind::Prior(s),
+            _ => PointKind::Fixed,
+        };
+        index.insert(cp.id.as_str(), points.len());
+        points.push(Point {
+            id: cp.id.clone(),
+            kind,
+            known: Some(enu),
+            slot: None,
+        });
+    }
+
+    let mut obs = Vec::new();
+    for (c, im) in req.images.iter().enumerate() {
+        for o in im.observations.iter().filter(|o| o.enabled.unwrap_or(true)) {
+            let point = *index.entry(o.id.as_str()).or_insert_with(|| {
+                points.push(Point {
+                    id: o.id.clone(),
+                    kind: PointKind::Tie,
+                    known: None,
+                    slot: None,
+                });
+                points.len() - 1
+            });
+            obs.push(Obs {
+                camera: c,
+                point,
+                pixel: [o.pixel.u, o.pixel.v],
+                sigma: o.pixel.sigma_px.unwrap_or(1.0).max(1e-6),
+            });
+        }
+    }
+
+    let mut bundle = Bundle {
+        points,
+        obs,
+        obs_of_point: Vec::new(),
+        n_slots: 0,
+    };
+    bundle.index();
+    bundle
+}
+
+/// Initial camera of `im` from its located observations, through the
+/// single-image solver (focal length fixed when known).
+fn pose_image(
+    im: &BundleImage,
+    bundle: &Bundle,
+    located: &[(&Obs, [f64; 3])],
+    (ref_lat, ref_lon, ref_alt): (f64, f64, f64),
+) -> Result<Camera, SolveError> {
+    let correspondences = located
+        .iter()
+        .map(|(o, x)| {
+            let (lat, lon, alt) = enu_to_lla(*x, ref_lat, ref_lon, ref_alt);
+            Corr {
+                id: bundle.points[o.point].id.clone(),
+                pixel: Pixel {
+                    u: o.pixel[0],
+                    v: o.pixel[1],
+                    sigma_px: Some(o.sigma),
+                },
+                world: WorldLla {
+                    lat,
+                    lon,
+                    alt: Some(alt),
+                    sigma_m: None,
+                },
+                enabled: Some(true),
+            }
+        })
+        .collect();
+    let req = SolveRequest {
+        schema_version: SCHEMA_VERSION,
+        image: Image {
+            width: im.image.width,
+            height: im.image.height,
+        },
+        correspondences,
+        priors: im.focal_px.map(|f| Priors {
+            focal_px: Some(GaussianPrior {
+                mean: f,
+                sigma: None,
+            }),
+            camera_alt: None,
+            bounds: None,
+            distortion: None,
+            position: None,
+            yaw_deg: None,
+            pitch_deg: None,
+            roll_deg: None,
+        }),
+        model: Some(SolverModel {
+            estimate_focal: Some(false),
+            estimate_distortion: false,
+            estimate_principal_point: false,
+            uncalibrated: false,
+        }),
+        ransac: None,
+        refine: None,
+        uncertainty: None,
+    };
+    let resp = solve_impl(&req)?;
+    let pose = &resp.pose;
+    Ok(Camera {
+        centre: lla_to_enu(pose.lat, pose.lon, pose.alt, ref_lat, ref_lon, ref_alt),
+        rot: rotation_enu_to_cam(pose.yaw_deg, pose.pitch_deg, pose.roll_deg),
+        intr: CameraIntrinsics {
+            focal_px: resp.intrinsics.focal_px,
+            cx: im.image.width / 2.0,
+            cy: im.image.height / 2.0,
+            k1: 0.0,
+            k2: 0.0,
+            p1: 0.0,
+            p2: 0.0,
+        },
+        focal_free: im.focal_px.is_none(),
+    })
+}
+
+/// Position of tie point `p` from the rays of the posed images that see
+/// it: the least-squares point closest to all rays.  `None` for fewer than
+/// two rays, rays closer than `MIN_TRIANGULATION_DEG` or a point behind
+/// one of the cameras.
+fn triangulate(bundle: &Bundle, p: usize, cameras: &[Option<Camera>]) -> Option<[f64; 3]> {
+    if !matches!(bundle.points[p].kind, PointKind::Tie) {
+        return None;
+    }
+    let rays: Vec<([f64; 3], [f64; 3])> = bundle.obs_of_point[p]
+        .iter()
+        .filter_map(|&k| {
+            let o = &bundle.obs[k];
+            let cam = cameras[o.camera].as_ref()?;
+            Some((cam.centre, pixel_ray(cam, o.pixel)))
+        })
+        .collect();
+    if rays.len() < 2 || widest_angle_deg(&rays) < MIN_TRIANGULATION_DEG {
+        return None;
+    }
+    intersect_rays(&rays)
+}
+
+/// Unit ENU direction of the ray through `pixel`.
+fn pixel_ray(cam: &Camera, pixel: [f64; 2]) -> [f64; 3] {
+    let xn = (pixel[0] - cam.intr.cx) / cam.intr.focal_px;
+    let yn = (pixel[1] - cam.intr.cy) / cam.intr.focal_px;
+    let d = mat3_t_vec(&cam.rot, &[xn, yn, 1.0]);
+    let n = dot(&d, &d).sqrt();
+    d.map(|v| v / n)
+}
+
+// ── Schur-complement Levenberg-Marquardt ────────────────────────────────────
+
+/// Normal equations `[U W; Wᵀ V] · [δc; δp] = −[g_c; g_p]` of the bundle,
+/// kept in their sparse block form: `U` per camera, `V` per estimated
+/// point, `W` per observation of an estimated point.
+struct Normal {
+    u: Vec<[[f64; CAMERA_PARAMS]; CAMERA_PARAMS]>,
+    g_c: Vec<[f64; CAMERA_PARAMS]>,
+    v: Vec<Mat3>,
+    g_p: Vec<[f64; 3]>,
+    w: Vec<[[f64; 3]; CAMERA_PARAMS]>,
+}
+
+impl Bundle {
+    /// Rebuild `obs_of_point` and the point slots.
+    fn index(&mut self) {
+        self.obs_of_point = vec![Vec::new(); self.points.len()];
+        for (k, o) in self.obs.iter().enumerate() {
+            self.obs_of_point[o.point].push(k);
+        }
+        // Tie points without observations are left out altogether.
+        self.n_slots = 0;
+        for (p, point) in self.points.iter_mut().enumerate() {
+            let estimated = match point.kind {
+                PointKind::Fixed => false,
+                PointKind::Prior(_) => true,
+                PointKind::Tie => !self.obs_of_point[p].is_empty(),
+            };
+            point.slot = estimated.then(|| {
+                self.n_slots += 1;
+                self.n_slots - 1
+            });
+        }
+    }
+
+    /// Remove the observations of points without a position.
+    fn drop_unlocated(&mut self, positions: &[Option<[f64; 3]>]) {
+        self.obs.retain(|o| positions[o.point].is_some());
+        self.index();
+    }
+
+    /// Whitened residual of observation `o`, `None` behind the camera.
+    fn residual(&self, o: &Obs, cam: &Camera, x: &[f64; 3]) -> Option<[f64; 2]> {
+        let p = mat3_vec(&cam.rot, &sub(x, &cam.centre));
+        if p[2] <= 0.0 {
+            return None;
+        }
+        let (xn, yn) = (p[0] / p[2], p[1] / p[2]);
+        let u = cam.intr.focal_px * xn + cam.intr.cx;
+        let v = cam.intr.focal_px * yn + cam.intr.cy;
+        Some([(u - o.pixel[0]) / o.sigma, (v - o.pixel[1]) / o.sigma])
+    }
+
+    /// Sum of squared whitened residuals and prior terms; infinite when a
+    /// point falls behind a camera that sees it.
+    fn cost(&self, cameras: &[Camera], points: &[[f64; 3]]) -> f64 {
+        let mut cost = 0.0;
+        for o in &self.obs {
+            match self.residual(o, &cameras[o.camera], &points[o.point]) {
+                Some(r) => cost += r[0] * r[0] + r[1] * r[1],
+                None => return f64::INFINITY,
+            }
+        }
+        for (p, point) in self.points.iter().enumerate() {
+            if let (PointKind::Prior(sigma), Some(mean)) = (&point.kind, point.known) {
+                let d = sub(&points[p], &mean);
+                cost += dot(&d, &d) / (sigma * sigma);
+            }
+        }
+        cost
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    fn normal_equations(&self, cameras: &[Camera], points: &[[f64; 3]]) -> Normal {
+        let mut n = Normal {
+            u: vec![[[0.0; CAMERA_PARAMS]; CAMERA_PARAMS]; cameras.len()],
+            g_c: vec![[0.0; CAMERA_PARAMS]; cameras.len()],
+            v: vec![[[0.0; 3]; 3]; self.n_slots],
+            g_p: vec![[0.0; 3]; self.n_slots],
+            w: vec![[[0.0; 3]; CAMERA_PARAMS]; self.obs.len()],
+        };
+        for (k, o) in self.obs.iter().enumerate() {
+            let cam = &cameras[o.camera];
+            let x = &points[o.point];
+            let p = mat3_vec(&cam.rot, &sub(x, &cam.centre));
+            let Some(jac) = project_cam_point_jacobian(p, &cam.intr) else {
+                continue;
+            };
+            let r = [
+                (jac.u - o.pixel[0]) / o.sigma,
+                (jac.v - o.pixel[1]) / o.sigma,
+            ];
+            // ∂(u, v)/∂p_cam, whitened.
+            let jp = jac.d_cam_point.map(|row| row.map(|v| v / o.sigma));
+            // p_cam = R (x − c):  ∂/∂c = −R,  ∂/∂x = R,  and for the local
+            // rotation update R ← exp([δ]×) R,  ∂/∂δ = −[p_cam]×.
+            let skew = [[0.0, -p[2], p[1]], [p[2], 0.0, -p[0]], [-p[1], p[0], 0.0]];
+            let mut jc = [[0.0; CAMERA_PARAMS]; 2];
+            let mut jx = [[0.0; 3]; 2];
+            for row in 0..2 {
+                for j in 0..3 {
+                    for m in 0..3 {
+                        jc[row][j] -= jp[row][m] * cam.rot[m][j];
+                        jc[row][3 + j] -= jp[row][m] * skew[m][j];
+                        jx[row][j] += jp[row][m] * cam.rot[m][j];
+                    }
+                }
+                if cam.focal_free {
+                    jc[row][6] = jac.d_focal[row] / o.sigma;
+                }
+            }
+
+            let u = &mut n.u[o.camera];
+            let g = &mut n.g_c[o.camera];
+            for a in 0..CAMERA_PARAMS {
+                g[a] += jc[0][a] * r[0] + jc[1][a] * r[1];
+                for b in 0..CAMERA_PARAMS {
+                    u[a][b] += jc[0][a] * jc[0][b] + jc[1][a] * jc[1][b];
+                }
+            }
+            if let Some(s) = self.points[o.point].slot {
+                for a in 0..3 {
+                    n.g_p[s][a] += jx[0][a] * r[0] + jx[1][a] * r[1];
+                    for b in 0..3 {
+                        n.v[s][a][b] += jx[0][a] * jx[0][b] + jx[1][a] * jx[1][b];
+                    }
+                }
+                for a in 0..CAMERA_PARAMS {
+                    for b in 0..3 {
+                        n.w[k][a][b] = jc[0][a] * jx[0][b] + jc[1][a] * jx[1][b];
+                    }
+                }
+            }
+        }
+        for (p, point) in self.points.iter().enumerate() {
+            if let (PointKind::Prior(sigma), Some(mean), Some(s)) =
+                (&point.kind, point.known, point.slot)
+            {
+                let d = sub(&points[p], &mean);
+                for a in 0..3 {
+                    n.v[s][a][a] += 1.0 / (sigma * sigma);
+                    n.g_p[s][a] += d[a] / (sigma * sigma);
+                }
+            }
+        }
+        // A fixed focal length has a zero column: a unit diagonal keeps U
+        // invertible and, with a zero gradient, its step at 0.
+        for (c, cam) in cameras.iter().enumerate() {
+            if !cam.focal_free {
+                n.u[c][6][6] += 1.0;
+            }
+        }
+        n
+    }
+
+    /// Damped step `(δc, δp)`: the reduced camera system
+    /// `S = U − Σ W V⁻¹ Wᵀ` is solved first, then every point is
+    /// back-substituted on its own.  `None` when a block is singular.
+    #[allow(clippy::needless_range_loop)]
+    fn step(&self, n: &Normal, lambda: f64) -> Option<(Vec<f64>, Vec<[f64; 3]>)> {
+        let damp = |d: f64| d + lambda * d.max(1e-12);
+        let nc = n.u.len() * CAMERA_PARAMS;
+
+        let mut v_inv = Vec::with_capacity(self.n_slots);
+        for v in &n.v {
+            let mut vd = *v;
+            for a in 0..3 {
+                vd[a][a] = damp(vd[a][a]);
+            }
+            v_inv.push(invert3(&vd)?);
+        }
+
+        let mut s = vec![0.0; nc * nc];
+        let mut rhs = vec![0.0; nc];
+        for (c, u) in n.u.iter().enumerate() {
+            for a in 0..CAMERA_PARAMS {
+                let i = c * CAMERA_PARAMS + a;
+                rhs[i] = -n.g_c[c][a];
+                for b in 0..CAMERA_PARAMS {
+                    s[i * nc + c * CAMERA_PARAMS + b] = u[a][b];
+                }
+                s[i * nc + i] = damp(u[a][a]);
+            }
+        }
+        for (p, point) in self.points.iter().enumerate() {
+            let Some(slot) = point.slot else { continue };
+            let vi = &v_inv[slot];
+            for &ka in &self.obs_of_point[p] {
+                let ca = self.obs[ka].camera;
+                // Y = W_a V⁻¹
+                let y: [[f64; 3]; CAMERA_PARAMS] = std::array::from_fn(|a| {
+                    std::array::from_fn(|b| (0..3).map(|m| n.w[ka][a][m] * vi[m][b]).sum())
+                });
+                for a in 0..CAMERA_PARAMS {
+                    rhs[ca * CAMERA_PARAMS + a] += dot(&y[a], &n.g_p[slot]);
+                }
+                for &kb in &self.obs_of_point[p] {
+                    let cb = self.obs[kb].camera;
+                    for a in 0..CAMERA_PARAMS {
+                        let row = (ca * CAMERA_PARAMS + a) * nc + cb * CAMERA_PARAMS;
+                        for b in 0..CAMERA_PARAMS {
+                            s[row + b] -= dot(&y[a], &n.w[kb][b]);
+                        }
+                    }
+                }
+            }
+        }
+        let dc = solve_dense(s, rhs)?;
+
+        let mut dp = vec![[0.0; 3]; self.n_slots];
+        for (p, point) in self.points.iter().enumerate() {
+            let Some(slot) = point.slot else { continue };
+            let mut b = n.g_p[slot].map(|g| -g);
+            for &k in &self.obs_of_point[p] {
+                let c0 = self.obs[k].camera * CAMERA_PARAMS;
+                for m in 0..3 {
+                    b[m] -= (0..CAMERA_PARAMS)
+                        .map(|a| n.w[k][a][m] * dc[c0 + a])
+                        .sum::<f64>();
+                }
+            }
+            dp[slot] = mat3_vec(&v_inv[slot], &b);
+        }
+        Some((dc, dp))
+    }
+
+    /// `cameras` and `points` moved by a step.
+    #[allow(clippy::needless_range_loop)]
+    fn apply(
+        &self,
+        cameras: &[Camera],
+        points: &[[f64; 3]],
+        dc: &[f64],
+        dp: &[[f64; 3]],
+    ) -> (Vec<Camera>, Vec<[f64; 3]>) {
+        let cameras = cameras
+            .iter()
+            .enumerate()
+            .map(|(c, cam)| {
+                let d = &dc[c * CAMERA_PARAMS..(c + 1) * CAMERA_PARAMS];
+                let mut cam = cam.clone();
+                for a in 0..3 {
+                    cam.centre[a] += d[a];
+                }
+                cam.rot = mat3_mul(&rotation_from_vector(&[d[3], d[4], d[5]]), &cam.rot);
+                if cam.focal_free {
+                    cam.intr.focal_px += d[6];
+                }
+                cam
+            })
+            .collect();
+        let points = points
+            .iter()
+            .zip(&self.points)
+            .map(|(x, point)| match point.slot {
+                Some(s) => [x[0] + dp[s][0], x[1] + dp[s][1], x[2] + dp[s][2]],
+                None => *x,
+            })
+            .collect();
+        (cameras, points)
+    }
+
+    /// LM with the stopping rules of `LmControl`.  Returns the iteration
+    /// count and why it stopped.
+    fn optimise(
+        &self,
+        cameras: &mut Vec<Camera>,
+        points: &mut Vec<[f64; 3]>,
+        max_iter: usize,
+    ) -> (usize, TerminationReason) {
+        let mut lm = LmControl::new(1e-3);
+        let mut cost = self.cost(cameras, points);
+        let mut iterations = 0;
+
+        for iter in 0..max_iter {
+            let n = self.normal_equations(cameras, points);
+            let gradient = n
+                .g_c
+                .iter()
+                .flatten()
+                .chain(n.g_p.iter().flatten())
+                .fold(0.0f64, |m, g| m.max(g.abs()));
+            if lm.gradient_converged(gradient, cost) {
+                return (iterations, TerminationReason::ConvergedGradient);
+            }
+            iterations = iter + 1;
+
+            let mut stop = Some(TerminationReason::LambdaOverflow);
+            while lm.can_damp() {
+                if let Some((dc, dp)) = self.step(&n, lm.lambda()) {
+                    let (nc, np) = self.apply(cameras, points, &dc, &dp);
+                    let new_cost = self.cost(&nc, &np);
+                    if new_cost < cost && nc.iter().all(|c| c.intr.focal_px > 0.0) {
+                        let step_norm = dc
+                            .iter()
+                            .chain(dp.iter().flatten())
+                            .map(|d| d * d)
+                            .sum::<f64>()
+                            .sqrt();
+                        let x_norm = cameras
+                            .iter()
+                            .flat_map(|c| c.centre.into_iter().chain([c.intr.focal_px]))
+                            .chain(points.iter().flatten().copied())
+                            .map(|x| x * x)
+                            .sum::<f64>()
+                            .sqrt();
+                        stop = lm.accept(cost, new_cost, step_norm, x_norm);
+                        *cameras = nc;
+                        *points = np;
+                        cost = new_cost;
+                        break;
+                    }
+                }
+                lm.reject();
+            }
+            if let Some(reason) = stop {
+                return (iterations, reason);
+            }
+        }
+        (iterations, TerminationReason::MaxIterations)
+    }
+
+    // ── Output ──────────────────────────────────────────────────────────
+
+    /// Pixel error (px) of every observation.
+    fn pixel_errors(&self, cameras: &[Camera], points: &[[f64; 3]]) -> Vec<f64> {
+        self.obs
+            .iter()
+            .map(
+                |o| match self.residual(o, &cameras[o.camera], &points[o.point]) {
+                    Some(r) => r[0].hypot(r[1]) * o.sigma,
+                    None => f64::INFINITY,
+                },
+            )
+            .collect()
+    }
+
+    fn response(
+        &self,
+        req: &BundleRequest,
+        cameras: &[Camera],
+        points: &[[f64; 3]],
+        (ref_lat, ref_lon, ref_alt): (f64, f64, f64),
+        mut diagnostics: BundleDiagnostics,
+    ) -> BundleResponse {
+        let errors = self.pixel_errors(cameras, points);
+        let rmse = |ks: &mut dyn Iterator<Item = usize>| {
+            let (n, ss) = ks.fold((0usize, 0.0), |(n, ss), k| (n + 1, ss + errors[k].powi(2)));
+            if n == 0 {
+                0.0
+            } else {
+                (ss / n as f64).sqrt()
+            }
+        };
+        diagnostics.rmse_px = rmse(&mut (0..self.obs.len()));
+
+        let cameras = cameras
+            .iter()
+            .zip(&req.images)
+            .enumerate()
+            .map(|(c, (cam, im))| {
+                let (lat, lon, alt) = enu_to_lla(cam.centre, ref_lat, ref_lon, ref_alt);
+                let (yaw_deg, pitch_deg, roll_deg) = euler_from_rotation(&cam.rot);
+                BundleCamera {
+                    id: im.id.clone(),
+                    pose: Pose {
+                        lat,
+                        lon,
+                        alt,
+                        yaw_deg,
+                        pitch_deg,
+                        roll_deg,
+                    },
+                    intrinsics: Intrinsics {
+                        focal_px: cam.intr.focal_px,
+                        cx: cam.intr.cx,
+                        cy: cam.intr.cy,
+                        k1: 0.0,
+                        k2: 0.0,
+                        p1: 0.0,
+                        p2: 0.0,
+                    },
+                    rmse_px: rmse(&mut (0..self.obs.len()).filter(|&k| self.obs[k].camera == c)),
+                    observations: self.obs.iter().filter(|o| o.camera == c).count(),
+                }
+            })
+            .collect();
+
+        let points = self
+            .points
+            .iter()
+            .enumerate()
+            .filter(|(p, _)| !self.obs_of_point[*p].is_empty())
+            .map(|(p, point)| {
+                let (lat, lon, alt) = enu_to_lla(points[p], ref_lat, ref_lon, ref_alt);
+                BundlePoint {
+                    id: point.id.clone(),
+                    lat,
+                    lon,
+                    alt,
+                    control: !matches!(point.kind, PointKind::Tie),
+                    observations: self.obs_of_point[p].len(),
+                    rmse_px: rmse(&mut self.obs_of_point[p].iter().copied()),
+                }
+            })
+            .collect();
+
+        BundleResponse {
+            schema_version: SCHEMA_VERSION,
+            cameras,
+            points,
+            diagnostics,
+        }
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn sub(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
@@ -0,0 +1,216 @@
+use crate::linalg::{principal_axes, solve_linear, symmetric_eigen};
+use crate::optimizer::{Problem, CX, FOCAL, NUM_PARAMS};
+use crate::pnp::{candidate_params, PoseCandidate};
+use crate::projection::{CameraIntrinsics, Mat3};
+
+/// Minimum number of correspondences for the DLT: the 3×4 camera matrix
+/// has 11 degrees of freedom and each point gives two equations.
+pub(crate) const DLT_MIN_POINTS: usize = 6;
+
+/// Below this ratio of smallest to largest principal spread the world
+/// points are treated as coplanar, for which the DLT is degenerate.
+pub(crate) const DLT_PLANARITY_LIMIT: f64 = 0.05;
+
+/// A 3×4 projection matrix `P ~ K · R · [I | −C]`, row-major.
+type CameraMatrix = [[f64; 4]; 3];
+
+// ── Estimation ──────────────────────────────────────────────────────────────
+
+/// Direct linear transform: the camera matrix that best maps `world` onto
+/// `pixels`, decomposed into a pose and pinhole intrinsics.
+///
+/// Both point sets are normalised first (Hartley: centroid at the origin,
+/// mean distance √2 for pixels and √3 for world points), which makes the
+/// homogeneous least-squares problem well conditioned.  The solution is
+/// the eigenvector of `AᵀA` with the smallest eigenvalue.
+///
+/// Lens distortion is ignored, and the separate x / y focal lengths of the
+/// decomposition are averaged into one.  Returns `None` with fewer than
+/// `DLT_MIN_POINTS` points or when the decomposition fails; coplanar points
+/// give a meaningless result (check `planarity` first).
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn dlt(
+    world: &[[f64; 3]],
+    pixels: &[[f64; 2]],
+) -> Option<(PoseCandidate, CameraIntrinsics)> {
+    let n = world.len();
+    if n < DLT_MIN_POINTS || pixels.len() != n {
+        return None;
+    }
+
+    // Hartley normalisation.
+    let mut pc = [0.0; 2];
+    let mut wc = [0.0; 3];
+    for (p, w) in pixels.iter().zip(world) {
+        for k in 0..2 {
+            pc[k] += p[k] / n as f64;
+        }
+        for k in 0..3 {
+            wc[k] += w[k] / n as f64;
+        }
+    }
+    let pixel_spread = pixels
+        .iter()
+        .map(|p| (p[0] - pc[0]).hypot(p[1] - pc[1]))
+        .sum::<f64>()
+        / n as f64;
+    let world_spread = world
+        .iter()
+        .map(|w| ((w[0] - wc[0]).powi(2) + (w[1] - wc[1]).powi(2) + (w[2] - wc[2]).powi(2)).sqrt())
+        .sum::<f64>()
+        / n as f64;
+    if pixel_spread <= 0.0 || world_spread <= 0.0 {
+        return None;
+    }
+    let sp = 2f64.sqrt() / pixel_spread;
+    let sw = 3f64.sqrt() / world_spread;
+
+    // AᵀA for the rows  [Xᵀ 0 −u·Xᵀ] and [0 Xᵀ −v·Xᵀ].
+    let mut ata = [[0.0; 12]; 12];
+    for (p, w) in pixels.iter().zip(world) {
+        let x = [
+            (w[0] - wc[0]) * sw,
+            (w[1] - wc[1]) * sw,
+            (w[2] - wc[2]) * sw,
+            1.0,
+        ];
+        let u = (p[0] - pc[0]) * sp;
+        let v = (p[1] - pc[1]) * sp;
+        let mut rows = [[0.0; 12]; 2];
+        for k in 0..4 {
+            rows[0][k] = x[k];
+            rows[0][8 + k] = -u * x[k];
+            rows[1][4 + k] = x[k];
+            rows[1][8 + k] = -v * x[k];
+        }
+        for row in &rows {
+            for i in 0..12 {
+                for j in 0..12 {
+                    ata[i][j] += row[i] * row[j];
+                }
+            }
+        }
+    }
+    let (_, vectors) = symmetric_eigen(&ata);
+    let h: CameraMatrix = std::array::from_fn(|r| std::array::from_fn(|c| vectors[4 * r + c][0]));
+
+    // Undo the normalisation: P = T⁻¹ · P̃ · U.
+    let mut p = [[0.0; 4]; 3];
+    for r in 0..3 {
+        // P̃ · U, with U = [sw·I | −sw·wc; 0 1]
+        let mut row = [0.0; 4];
+        for c in 0..3 {
+            row[c] = h[r][c] * sw;
+            row[3] -= h[r][c] * sw * wc[c];
+        }
+        row[3] += h[r][3];
+        p[r] = row;
+    }
+    // T⁻¹ = [1/sp 0 pc.x; 0 1/sp pc.y; 0 0 1]
+    for r in 0..2 {
+        for c in 0..4 {
+            p[r][c] = p[r][c] / sp + pc[r] * p[2][c];
+        }
+    }
+
+    decompose(&p)
+}
+
+/// Ratio of the smallest to the largest principal spread of the world
+/// points: 0 for a plane (or line), ~1 for a well-spread 3-D cloud.
+pub(crate) fn planarity(world: &[[f64; 3]]) -> f64 {
+    match principal_axes(world) {
+        Some((_, spread, _)) if spread[2] > 0.0 => spread[0] / spread[2],
+        _ => 0.0,
+    }
+}
+
+// ── Decomposition ───────────────────────────────────────────────────────────
+
+/// Split `P = K · R · [I | −C]` into pose `(R, C)` and intrinsics `K`.
+///
+/// The overall sign of `P` is chosen so that `det(M) > 0` (with `M` its left
+/// 3×3 block), which puts the scene in front of the camera; `K` and `R`
+/// then follow from an RQ decomposition of `M` by Gram-Schmidt on its rows,
+/// starting from the last.
+fn decompose(p: &CameraMatrix) -> Option<(PoseCandidate, CameraIntrinsics)> {
+    let mut m: Mat3 = std::array::from_fn(|r| [p[r][0], p[r][1], p[r][2]]);
+    let mut p4 = [p[0][3], p[1][3], p[2][3]];
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    if !det.is_finite() || det == 0.0 {
+        return None;
+    }
+    if det < 0.0 {
+        m = m.map(|row| row.map(|v| -v));
+        p4 = p4.map(|v| -v);
+    }
+
+    let dot = |a: &[f64; 3], b: &[f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let norm = |a: &[f64; 3]| dot(a, a).sqrt();
+
+    // RQ: rows of M are  m₃ = k₂₂·r₃,  m₂ = k₁₁·r₂ + k₁₂·r₃,
+    // m₁ = k₀₀·r₁ + k₀₁·r₂ + k₀₂·r₃.
+    let k22 = norm(&m[2]);
+    let r3 = m[2].map(|v| v / k22);
+    let k12 = dot(&m[1], &r3);
+    let r2_raw: [f64; 3] = std::array::from_fn(|c| m[1][c] - k12 * r3[c]);
+    let k11 = norm(&r2_raw);
+    let r2 = r2_raw.map(|v| v / k11);
+    let k02 = dot(&m[0], &r3);
+    let k01 = dot(&m[0], &r2);
+    let r1_raw: [f64; 3] = std::array::from_fn(|c| m[0][c] - k01 * r2[c] - k02 * r3[c]);
+    let k00 = norm(&r1_raw);
+    let r1 = r1_raw.map(|v| v / k00);
+    if k00 <= 0.0 || k11 <= 0.0 || k22 <= 0.0 {
+        return None;
+    }
+
+    // Camera centre: M · C = −p₄.
+    let cam = solve_linear(&m, &p4.map(|v| -v))?;
+    let intr = CameraIntrinsics {
+        focal_px: 0.5 * (k00 + k11) / k22,
+        cx: k02 / k22,
+        cy: k12 / k22,
+        k1: 0.0,
+        k2: 0.0,
+        p1: 0.0,
+        p2: 0.0,
+    };
+    let values = [intr.focal_px, intr.cx, intr.cy, cam[0], cam[1], cam[2]];
+    if values.iter().any(|v| !v.is_finite()) {
+        return None;
+    }
+    Some((
+        PoseCandidate {
+            rot: [r1, r2, r3],
+            cam,
+        },
+        intr,
+    ))
+}
+
+// ── Seeding ─────────────────────────────────────────────────────────────────
+
+/// DLT starting point for the full problem: the pose plus, for whichever of
+/// focal length and principal point are free, the DLT intrinsics.  `None`
+/// when the DLT does not apply (too few points, coplanar scene).
+pub(crate) fn dlt_seed(problem: &Problem) -> Option<[f64; NUM_PARAMS]> {
+    let world: Vec<[f64; 3]> = problem.corrs.iter().map(|c| c.enu).collect();
+    if planarity(&world) < DLT_PLANARITY_LIMIT {
+        return None;
+    }
+    let pixels: Vec<[f64; 2]> = problem.corrs.iter().map(|c| c.pixel).collect();
+    let (pose, intr) = dlt(&world, &pixels)?;
+    let free = problem.free();
+    let mut params = candidate_params(&pose, problem);
+    if free[FOCAL] {
+        params[FOCAL] = intr.focal_px;
+    }
+    if free[CX] {
+        params[CX] = intr.cx;
+        params[CX + 1] = intr.cy;
+    }
+    Some(params)
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+
+/// Why a request could not be answered.
+///
+/// Crosses the wasm boundary as `{ "code": …, "message": …, "details": … }`
+/// (see `to_json`): `code` is a stable camelCase identifier the frontend
+/// can switch on, `message` a human-readable English sentence, and
+/// `details` code-specific data (or `null`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// The request is not valid JSON or does not match the request types.
+    InvalidJson {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+    /// The request was written for a newer schema than this solver reads.
+    UnsupportedSchemaVersion { found: u64, supported: u32 },
+    /// No correspondence is enabled.
+    NoEnabledPoints,
+    /// The correspondences cannot determine a pose (coincident points, or
+    /// an optimisation that left the finite range).
+    DegenerateGeometry { reason: String },
+    /// A numeric input is NaN or infinite.
+    NonFiniteInput { field: String },
+    /// An input is out of its valid range.
+    InvalidInput { field: String, message: String },
+    /// `JᵀJ` of the fit could not be inverted and there is no fallback
+    /// estimate to report instead (some parameter is unconstrained by the
+    /// data).  A pose solve keeps a default covariance and warns.
+    SingularCovariance,
+    /// The viewing ray of a back-projected pixel misses the surface.
+    NoIntersection,
+    /// The response could not be serialised.
+    Serialization { message: String },
+    /// The solver failed unexpectedly (a bug); `message` says where.
+    Internal { message: String },
+}
+
+impl SolveError {
+    /// Machine-readable identifier of the error kind.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidJson { .. } => "invalidJson",
+            Self::UnsupportedSchemaVersion { .. } => "unsupportedSchemaVersion",
+            Self::NoEnabledPoints => "noEnabledPoints",
+            Self::DegenerateGeometry { .. } => "degenerateGeometry",
+            Self::NonFiniteInput { .. } => "nonFiniteInput",
+            Self::InvalidInput { .. } => "invalidInput",
+            Self::SingularCovariance => "singularCovariance",
+            Self::NoIntersection => "noIntersection",
+            Self::Serialization { .. } => "serialization",
+            Self::Internal { .. } => "internal",
+        }
+    }
+
+    /// Code-specific data for the frontend, `null` when there is none.
+    pub fn details(&self) -> Value {
+        match self {
+            Self::InvalidJson { line, column, .. } => json!({ "line": line, "column": column }),
+            Self::UnsupportedSchemaVersion { found, supported } => {
+                json!({ "found": found, "supported": supported })
+            }
+            Self::DegenerateGeometry { reason } => json!({ "reason": reason }),
+            Self::NonFiniteInput { field } | Self::InvalidInput { field, .. } => {
+                json!({ "field": field })
+            }
+            _ => Value::Null,
+        }
+    }
+
+    /// The `{ code, message, details }` JSON object as a string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| {
+            format!(
+                r#"{{"code":"{}","message":"","details":null}}"#,
+                self.code()
+            )
+        })
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson { message, .. } => write!(f, "Invalid request JSON: {message}"),
+            Self::UnsupportedSchemaVersion { found, supported } => write!(
+                f,
+                "Request schema version {found} is newer than the supported version {supported}"
+            ),
+            Self::NoEnabledPoints => {
+                write!(
+                    f,
+                    "Need at least one enabled correspondence to estimate pose"
+                )
+            }
+            Self::DegenerateGeometry { reason } => write!(f, "Degenerate geometry: {reason}"),
+            Self::NonFiniteInput { field } => write!(f, "{field} must be a finite number"),
+            Self::InvalidInput { message, .. } => write!(f, "{message}"),
+            Self::SingularCovariance => write!(
+                f,
+                "The fit leaves some parameter unconstrained: its covariance is singular"
+            ),
+            Self::NoIntersection => write!(
+                f,
+                "Viewing ray does not intersect the requested altitude plane"
+            ),
+            Self::Serialization { message } => write!(f, "Serialize error: {message}"),
+            Self::Internal { message } => write!(f, "Internal solver error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<serde_json::Error> for SolveError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::InvalidJson {
+            message: e.to_string(),
+            line: e.line(),
+            column: e.column(),
+        }
+    }
+}
+
+impl Serialize for SolveError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        json!({
+            "code": self.code(),
+            "message": self.to_string(),
+            "details": self.details(),
+        })
+        .serialize(serializer)
+    }
+}
@@ -1,14 +1,33 @@
-use crate::geo::{enu_to_lla, lla_to_enu};
+use crate::bootstrap::bootstrap;
+use crate::dlt::{dlt, dlt_seed, planarity, DLT_MIN_POINTS, DLT_PLANARITY_LIMIT};
+use crate::error::SolveError;
+use crate::geo::{enu_to_lla, lla_to_enu, metres_per_degree};
 use crate::optimizer::{
-    estimate_scene_distance, initialize_pose, levenberg_marquardt, EnuCorrespondence, OptResult,
-    NUM_PARAMS,
+    cost, estimate_scene_distance, euler_from_params, initialize_pose, levenberg_marquardt,
+    propagate_world_sigma, reprojection_errors, robust_refine, rotation_from_params, set_rotation,
+    EnuCorrespondence, OptResult, PosePriors, Problem, RobustKernel, CX, FOCAL, NUM_PARAMS,
 };
-use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
-use crate::types::{Corr, Covariance, Diagnostics, Intrinsics, Pose, SolveRequest, SolveResponse};
+use crate::pnp::{epnp_seed, minimal_sample_size, minimal_seeds};
+use crate::projection::{euler_local_jacobian, rotation_enu_to_cam, CameraIntrinsics};
+use crate::ransac::find_consensus;
+use crate::schema::SCHEMA_VERSION;
+use crate::types::{
+    Bootstrap, Corr, Covariance, Diagnostics, GaussianPrior, Intrinsics, Pose, PoseHypothesis,
+    RobustLoss, SolveRequest, SolveResponse, TerminationReason,
+};
+use crate::uncertainty::summarize;
+use crate::validate::validate;
+
+/// Default prior standard deviations, used when a prior omits `sigma`.
+const DEFAULT_POSITION_SIGMA_M: f64 = 10.0;
+const DEFAULT_ALT_SIGMA_M: f64 = 10.0;
+const DEFAULT_YAW_SIGMA_DEG: f64 = 20.0;
+const DEFAULT_TILT_SIGMA_DEG: f64 = 5.0;
 
 // ── Public entry point ──────────────────────────────────────────────────────
 
-pub fn solve_impl(req: &SolveRequest) -> Result<SolveResponse, String> {
+pub fn solve_impl(req: &SolveRequest) -> Result<SolveResponse, SolveError> {
+    let input_warnings = validate(req)?;
     let active: Vec<&Corr> = req
         .correspondences
         .iter()
@@ -16,15 +35,16 @@ pub fn solve_impl(req: &SolveRequest) -> Result<SolveResponse, String> {
         .collect();
 
     if active.is_empty() {
-        return Err("Need at least one enabled correspondence to estimate pose".to_string());
+        return Err(SolveError::NoEnabledPoints);
     }
 
     let focal_px = estimate_focal(req);
     let cx = req.image.width / 2.0;
     let cy = req.image.height / 2.0;
-    // Base intrinsics: focal length + principal point are fixed during
-    // optimisation.  Distortion starts from the provided prior (or 0).
-    let intr = CameraIntrinsics {
+    // Base intrinsics: focal length and image centre seed `params[FOCAL]`
+    // and `params[CX..]` (free only when the model asks for them).
+    // Distortion starts from the provided prior (or 0).
+    let mut intr = CameraIntrinsics {
         focal_px,
         cx,
         cy,
@@ -51,7 +71,7 @@ pub fn solve_impl(req: &SolveRequest) -> Result<SolveResponse, String> {
 
     // ── Single-point fallback (no geometric information) ────────────────
     if active.len() == 1 {
-        return solve_single_point(req, active[0], &intr, dist_prior);
+        return solve_single_point(req, active[0], &intr, dist_prior, input_warnings);
     }
 
     // ── Reference LLA (centroid of world points) ────────────────────────
@@ -78,43 +98,270 @@ pub fn solve_impl(req: &SolveRequest) -> Result<SolveResponse, String> {
             ),
             pixel: [c.pixel.u, c.pixel.v],
             sigma: c.pixel.sigma_px.unwrap_or(1.0).max(1e-6),
+            world_sigma: c.world.sigma_m.unwrap_or(0.0).max(0.0),
         })
         .collect();
+    check_spread(&enu_corrs)?;
 
-    // Altitude prior in ENU metres
-    let alt_prior_enu = req
-        .priors
+    let pose_priors = pose_priors(req, ref_lat, ref_lon, ref_alt);
+
+    // ── Problem definition (which intrinsics are estimated) ────────────
+    let model = req.model.clone().unwrap_or_default();
+    let mut warnings = Vec::new();
+    if model.uncalibrated {
+        match dlt_focal(&enu_corrs) {
+            Ok(f) => intr.focal_px = f,
+            Err(w) => warnings.push(w),
+        }
+    }
+    // Without `focalPx` the 0.9 × width guess is just a starting point: unless
+    // the request pins it (`estimateFocal: false`) the focal length is
+    // estimated and minimal samples go through the focal scan.
+    let focal_known = req.priors.as_ref().is_some_and(|p| p.focal_px.is_some());
+    let mut problem = Problem::new(&enu_corrs, &intr, pose_priors, dist_prior);
+    problem.estimate_distortion = model.estimate_distortion;
+    problem.unknown_focal = !focal_known;
+    if model.estimate_focal.unwrap_or(!focal_known) || model.uncalibrated {
+        problem.estimate_focal = true;
+        problem.focal_prior = Some(focal_prior(req, intr.focal_px));
+        if enu_corrs.len() < 4 {
+            warnings.push(format!(
+                "Focal length estimation needs at least 4 correspondences; keeping {:.0} px fixed.",
+                intr.focal_px
+            ));
+        }
+    }
+    if model.estimate_principal_point || model.uncalibrated {
+        problem.estimate_principal_point = true;
+        problem.principal_point_sigma = Some(principal_point_sigma(req));
+        if enu_corrs.len() < 5 {
+            warnings.push(
+                "Principal point estimation needs at least 5 correspondences; keeping the image centre."
+                    .to_string(),
+            );
+        }
+    }
+
+    // ── Outlier rejection (RANSAC) ──────────────────────────────────────
+    let mut inlier_idx: Vec<usize> = (0..enu_corrs.len()).collect();
+    let ransac_cfg = req
+        .ransac
         .as_ref()
-        .and_then(|p| p.camera_alt.as_ref())
-        .map(|a| a.mean - ref_alt);
+        .filter(|_| enu_corrs.len() > minimal_sample_size(&problem));
+    let mut consensus_found = false;
+    if let Some(cfg) = ransac_cfg {
+        match find_consensus(&problem, cfg) {
+            Some(inliers) => {
+                inlier_idx = inliers;
+                consensus_found = true;
+            }
+            None => warnings.push(
+                "RANSAC found no consensus beyond a minimal sample; using all points.".to_string(),
+            ),
+        }
+    }
+    let mut fit_corrs: Vec<EnuCorrespondence> =
+        inlier_idx.iter().map(|&i| enu_corrs[i].clone()).collect();
+
+    // ── Multi-start optimisation (consensus set only) ───────────────────
+    let mut alternatives = multi_start_optimise(&problem.with_corrs(&fit_corrs));
+    let mut result = alternatives.remove(0);
+
+    // ── Consensus re-classification ─────────────────────────────────────
+    // The minimal-sample hypotheses are rough; re-classify every point
+    // against the refined pose and refit while the consensus keeps growing
+    // (the "local optimisation" step of LO-RANSAC).
+    if let (Some(cfg), true) = (ransac_cfg, consensus_found) {
+        for _ in 0..3 {
+            let (errors, _) = reprojection_errors(&result.params, &enu_corrs);
+            let grown: Vec<usize> = (0..enu_corrs.len())
+                .filter(|&i| errors[i] <= cfg.inlier_px)
+                .collect();
+            if grown.len() <= inlier_idx.len() {
+                break;
+            }
+            inlier_idx = grown;
+            fit_corrs = inlier_idx.iter().map(|&i| enu_corrs[i].clone()).collect();
+            result = levenberg_marquardt(result.params, &problem.with_corrs(&fit_corrs), 300);
+        }
+        let rejected: Vec<&str> = (0..active.len())
+            .filter(|i| !inlier_idx.contains(i))
+            .map(|i| active[i].id.as_str())
+            .collect();
+        if !rejected.is_empty() {
+            warnings.push(format!(
+                "RANSAC rejected {} outlier(s): {}.",
+                rejected.len(),
+                rejected.join(", ")
+            ));
+        }
+    }
 
-    // ── Multi-start optimisation ────────────────────────────────────────
-    let np = active.len();
-    let result = multi_start_optimise(&enu_corrs, &intr, alt_prior_enu, dist_prior, np);
+    // ── World-point uncertainty ─────────────────────────────────────────
+    // Inflate each point's pixel sigma by its projected world sigma at the
+    // current estimate and refit; a few passes let the depths settle.
+    let mut weighted_corrs = fit_corrs.clone();
+    if fit_corrs.iter().any(|c| c.world_sigma > 0.0) {
+        for _ in 0..3 {
+            weighted_corrs = propagate_world_sigma(&result.params, &fit_corrs);
+            result = levenberg_marquardt(result.params, &problem.with_corrs(&weighted_corrs), 100);
+        }
+    }
 
-    // ── Convert result back to LLA ──────────────────────────────────────
-    let cam_enu = [result.params[0], result.params[1], result.params[2]];
-    let (mut cam_lat, mut cam_lon, _) = enu_to_lla(cam_enu, ref_lat, ref_lon, ref_alt);
-    let cam_alt = ref_alt + result.params[2];
+    // ── Robust refinement (IRLS) ────────────────────────────────────────
+    let refine = req.refine.clone().unwrap_or_default();
+    let kernel = RobustKernel::new(refine.robust_loss, refine.loss_scale);
+    let mut fit_weights = vec![1.0; fit_corrs.len()];
+    if kernel.loss != RobustLoss::None && fit_corrs.len() >= 3 {
+        let (refined, w) = robust_refine(
+            result.params,
+            &problem.with_corrs(&weighted_corrs),
+            &kernel,
+            refine.max_iters.unwrap_or(50),
+        );
+        result = refined;
+        fit_weights = w;
+    }
+    // Weight per enabled point; RANSAC outliers keep weight 0.
+    let mut weights = vec![0.0; active.len()];
+    for (k, &i) in inlier_idx.iter().enumerate() {
+        weights[i] = fit_weights[k];
+    }
+    let downweighted: Vec<&str> = inlier_idx
+        .iter()
+        .filter(|&&i| weights[i] < 0.5)
+        .map(|&i| active[i].id.as_str())
+        .collect();
+    if !downweighted.is_empty() {
+        warnings.push(format!(
+            "Robust loss down-weighted {} point(s) below 0.5: {}.",
+            downweighted.len(),
+            downweighted.join(", ")
+        ));
+    }
 
-    // Bounds clamping
-    if let Some(b) = req.priors.as_ref().and_then(|p| p.bounds.as_ref()) {
-        cam_lat = cam_lat.clamp(b.lat_min, b.lat_max);
-        cam_lon = cam_lon.clamp(b.lon_min, b.lon_max);
+    // Inliers with their world-point and robust weights folded into σ,
+    // exactly as the last LM run saw them.
+    let final_corrs: Vec<EnuCorrespondence> = weighted_corrs
+        .iter()
+        .zip(&fit_weights)
+        .map(|(c, w)| EnuCorrespondence {
+            sigma: c.sigma / w.max(1e-12).sqrt(),
+            ..c.clone()
+        })
+        .collect();
+    let final_problem = problem.with_corrs(&final_corrs);
+
+    // ── Alternative hypotheses ──────────────────────────────────────────
+    // Refit the other local minima on the final data and keep those that
+    // are still distinct from the solution (and each other) and fit
+    // reasonably well.
+    let (_, best_rmse) = reprojection_errors(&result.params, &fit_corrs);
+    let max_rmse = (HYPOTHESIS_RMSE_RATIO * best_rmse).max(SEED_ACCEPT_RMSE_PX);
+    let mut kept: Vec<(OptResult, f64)> = Vec::new();
+    for alt in alternatives {
+        let refit = levenberg_marquardt(alt.params, &final_problem, 100);
+        let (_, rmse) = reprojection_errors(&refit.params, &fit_corrs);
+        if rmse <= max_rmse
+            && !same_minimum(&refit, &result)
+            && kept.iter().all(|(k, _)| !same_minimum(k, &refit))
+        {
+            kept.push((refit, rmse));
+        }
+    }
+    kept.sort_by(|a, b| a.0.cost.total_cmp(&b.0.cost));
+    let ambiguous = kept
+        .iter()
+        .filter(|(_, rmse)| *rmse <= AMBIGUOUS_RMSE_RATIO * best_rmse + 1.0)
+        .count();
+    if ambiguous > 0 {
+        warnings.push(format!(
+            "Ambiguous geometry: {} alternative pose(s) fit almost as well; see hypotheses.",
+            ambiguous
+        ));
     }
 
-    let yaw_deg = ((result.params[3] % 360.0) + 360.0) % 360.0;
+    if result.params.iter().any(|v| !v.is_finite()) {
+        return Err(SolveError::DegenerateGeometry {
+            reason: "the optimisation diverged".to_string(),
+        });
+    }
 
-    // ── Diagnostics ─────────────────────────────────────────────────────
-    let (residuals_px, rmse_px) = reprojection_errors(&result.params, &enu_corrs, &intr);
-    let covariance = build_covariance(&result, &enu_corrs, ref_lat);
+    // ── Bootstrap ───────────────────────────────────────────────────────
+    // Resample the final fit.
+    let bootstrap_cfg = req
+        .uncertainty
+        .as_ref()
+        .and_then(|u| u.bootstrap.as_ref())
+        .filter(|b| b.enabled);
+    let bootstrap_run = bootstrap_cfg.map(|cfg| bootstrap(&result, &final_problem, cfg));
+    if let Some(run) = &bootstrap_run {
+        if run.dropped > 0 {
+            warnings.push(format!(
+                "Bootstrap dropped {} of {} samples (too few distinct points or a failed refit).",
+                run.dropped,
+                run.dropped + run.samples.len()
+            ));
+        }
+        let n_free = result.free.iter().filter(|f| **f).count();
+        if 2 * fit_corrs.len() <= n_free {
+            warnings.push(
+                "Bootstrap: no more observations than unknowns, so the samples show no spread."
+                    .to_string(),
+            );
+        }
+    }
 
-    let mut warnings = Vec::new();
-    if !result.converged {
-        warnings.push(format!(
+    // ── Convert result back to LLA ──────────────────────────────────────
+    let reference = (ref_lat, ref_lon, ref_alt);
+    let pose = pose_from_params(&result.params, reference, req);
+    let yaw_deg = pose.yaw_deg;
+
+    // ── Diagnostics ─────────────────────────────────────────────────────
+    // Residuals are reported for every enabled point (outliers included, so
+    // the UI can show how far off they are); RMSE covers the inliers only.
+    let (residuals_px, _) = reprojection_errors(&result.params, &enu_corrs);
+    let (_, rmse_px) = reprojection_errors(&result.params, &fit_corrs);
+    let covariance =
+        build_covariance(&result, &fit_corrs, pose.lat, pose.alt).unwrap_or_else(|| {
+            warnings.push(
+                "The fit leaves some parameter unconstrained (JᵀJ is singular): the covariance is a rough default, not an estimate."
+                    .to_string(),
+            );
+            fallback_covariance()
+        });
+    let bootstrap = bootstrap_run.map(|run| Bootstrap {
+        position_samples: run
+            .samples
+            .iter()
+            .map(|p| {
+                let (lat, lon, _) = enu_to_lla([p[0], p[1], p[2]], ref_lat, ref_lon, ref_alt);
+                [lat, lon, ref_alt + p[2]]
+            })
+            .collect(),
+        orientation_samples: run
+            .samples
+            .iter()
+            .map(|p| {
+                let (yaw, pitch, roll) = euler_from_params(p);
+                // Unwrapped around the reported yaw, so 359° and 1° stay 2° apart.
+                let dyaw = ((yaw - yaw_deg) % 360.0 + 540.0) % 360.0 - 180.0;
+                [yaw_deg + dyaw, pitch, roll]
+            })
+            .collect(),
+        focal_samples: result.free[FOCAL].then(|| run.samples.iter().map(|p| p[FOCAL]).collect()),
+    });
+
+    match result.termination {
+        TerminationReason::MaxIterations => warnings.push(format!(
             "Optimizer did not fully converge after {} iterations.",
             result.iterations
-        ));
+        )),
+        TerminationReason::LambdaOverflow => warnings.push(format!(
+            "Optimizer stalled after {} iterations: no damped step lowered the cost.",
+            result.iterations
+        )),
+        _ => {}
     }
     if rmse_px > 50.0 {
         warnings.push(format!(
@@ -122,39 +369,135 @@ pub fn solve_impl(req: &SolveRequest) -> Result<SolveResponse, String> {
             rmse_px
         ));
     }
-    if active.len() < 3 {
+    if fit_corrs.len() < 3 {
         warnings.push("Fewer than 3 correspondences: solution is underdetermined.".to_string());
     }
+    warnings.extend(input_warnings);
+
+    let uncertainty = summarize(&covariance, &pose);
+    let intrinsics = intrinsics_from_result(&result.params);
+
+    let mut hypotheses = vec![PoseHypothesis {
+        pose: pose.clone(),
+        intrinsics: intrinsics.clone(),
+        cost: cost(&result.params, &final_problem),
+        rmse_px,
+        covariance: covariance.clone(),
+    }];
+    for (alt, alt_rmse) in &kept {
+        let alt_pose = pose_from_params(&alt.params, reference, req);
+        let alt_covariance = build_covariance(alt, &fit_corrs, alt_pose.lat, alt_pose.alt)
+            .unwrap_or_else(|| {
+                warnings.push(format!(
+                    "hypotheses[{}] leaves some parameter unconstrained: its covariance is a rough default.",
+                    hypotheses.len()
+                ));
+                fallback_covariance()
+            });
+        hypotheses.push(PoseHypothesis {
+            covariance: alt_covariance,
+            pose: alt_pose,
+            intrinsics: intrinsics_from_result(&alt.params),
+            cost: alt.cost,
+            rmse_px: *alt_rmse,
+        });
+    }
 
     Ok(SolveResponse {
-        pose: Pose {
-            lat: cam_lat,
-            lon: cam_lon,
-            alt: cam_alt,
-            yaw_deg,
-            pitch_deg: result.params[4],
-            roll_deg: result.params[5],
-        },
-        intrinsics: Intrinsics {
-            focal_px,
-            cx,
-            cy,
-            k1: result.params[6],
-            k2: result.params[7],
-            p1: result.params[8],
-            p2: result.params[9],
-        },
+        schema_version: SCHEMA_VERSION,
+        pose,
+        intrinsics,
         covariance,
+        uncertainty,
+        bootstrap,
+        hypotheses,
         diagnostics: Diagnostics {
             rmse_px,
-            inlier_ratio: 1.0,
+            inlier_ratio: inlier_idx.len() as f64 / active.len() as f64,
             residuals_px,
-            inlier_ids: active.iter().map(|c| c.id.clone()).collect(),
+            inlier_ids: inlier_idx.iter().map(|&i| active[i].id.clone()).collect(),
+            weights,
+            termination: Some(result.termination),
+            iterations: result.iterations,
             warnings,
         },
     })
 }
 
+// ── Input checks ────────────────────────────────────────────────────────────
+
+/// Two or more correspondences that all share one world point, or one
+/// pixel, carry no geometric information.
+fn check_spread(corrs: &[EnuCorrespondence]) -> Result<(), SolveError> {
+    let first = &corrs[0];
+    let world_spread = corrs
+        .iter()
+        .map(|c| {
+            (0..3)
+                .map(|k| (c.enu[k] - first.enu[k]).powi(2))
+                .sum::<f64>()
+        })
+        .fold(0.0f64, f64::max)
+        .sqrt();
+    let pixel_spread = corrs
+        .iter()
+        .map(|c| {
+            (0..2)
+                .map(|k| (c.pixel[k] - first.pixel[k]).powi(2))
+                .sum::<f64>()
+        })
+        .fold(0.0f64, f64::max)
+        .sqrt();
+    let reason = if world_spread < 1e-3 {
+        "all enabled correspondences share one world point"
+    } else if pixel_spread < 1e-6 {
+        "all enabled correspondences share one pixel"
+    } else {
+        return Ok(());
+    };
+    Err(SolveError::DegenerateGeometry {
+        reason: reason.to_string(),
+    })
+}
+
+/// Camera pose in LLA for the parameter vector `params`, relative to the ENU
+/// origin `reference` = (lat, lon, alt): clamped to the request's bounds,
+/// yaw wrapped to `[0, 360)`.
+fn pose_from_params(
+    params: &[f64; NUM_PARAMS],
+    reference: (f64, f64, f64),
+    req: &SolveRequest,
+) -> Pose {
+    let (ref_lat, ref_lon, ref_alt) = reference;
+    let (mut lat, mut lon, _) =
+        enu_to_lla([params[0], params[1], params[2]], ref_lat, ref_lon, ref_alt);
+    if let Some(b) = req.priors.as_ref().and_then(|p| p.bounds.as_ref()) {
+        lat = lat.clamp(b.lat_min, b.lat_max);
+        lon = lon.clamp(b.lon_min, b.lon_max);
+    }
+    let (yaw, pitch_deg, roll_deg) = euler_from_params(params);
+    Pose {
+        lat,
+        lon,
+        alt: ref_alt + params[2],
+        yaw_deg: ((yaw % 360.0) + 360.0) % 360.0,
+        pitch_deg,
+        roll_deg,
+    }
+}
+
+fn intrinsics_from_result(params: &[f64; NUM_PARAMS]) -> Intrinsics {
+    Intrinsics {
+        focal_px: params[FOCAL],
+        cx: params[CX],
+        cy: params[CX + 1],
+        k1: params[6],
+        k2: params[7],
+        p1: params[8],
+        p2: params[9],
+    }
+}
+
 // ── Single-point fallback ───────────────────────────────────────────────────
 
 fn solve_single_point(
@@ -162,56 +505,189 @@ fn solve_single_point(
     corr: &Corr,
     intr: &CameraIntrinsics,
     dist_prior: [f64; 4],
-) -> Result<SolveResponse, String> {
-    let lat = corr.world.lat;
-    let lon = corr.world.lon;
-    let alt = req
-        .priors
-        .as_ref()
+    input_warnings: Vec<String>,
+) -> Result<SolveResponse, SolveError> {
+    let priors = req.priors.as_ref();
+    let position = priors.and_then(|p| p.position.as_ref());
+    let has_position_prior = position.is_some();
+    let (lat, lon) = position.map_or((corr.world.lat, corr.world.lon), |p| (p.lat, p.lon));
+    let alt = priors
         .and_then(|p| p.camera_alt.as_ref().map(|c| c.mean))
         .unwrap_or_else(|| corr.world.alt.map(|g| (g - 2.0).max(0.0)).unwrap_or(2.0));
+    let angle = |g: Option<&GaussianPrior>| g.map_or(0.0, |g| g.mean);
+
+    let pose = Pose {
+        lat,
+        lon,
+        alt,
+        yaw_deg: (angle(priors.and_then(|p| p.yaw_deg.as_ref())) % 360.0 + 360.0) % 360.0,
+        pitch_deg: angle(priors.and_then(|p| p.pitch_deg.as_ref())),
+        roll_deg: angle(priors.and_then(|p| p.roll_deg.as_ref())),
+    };
+    let covariance = fallback_covariance();
+    let uncertainty = summarize(&covariance, &pose);
+    let mut warnings = vec![if has_position_prior {
+        "Only one correspondence: camera placed at the position prior.".to_string()
+    } else {
+        "Only one correspondence: camera placed at world point location.".to_string()
+    }];
+    warnings.extend(input_warnings);
+    let intrinsics = Intrinsics {
+        focal_px: intr.focal_px,
+        cx: intr.cx,
+        cy: intr.cy,
+        k1: dist_prior[0],
+        k2: dist_prior[1],
+        p1: dist_prior[2],
+        p2: dist_prior[3],
+    };
 
     Ok(SolveResponse {
-        pose: Pose {
-            lat,
-            lon,
-            alt,
-            yaw_deg: 0.0,
-            pitch_deg: 0.0,
-            roll_deg: 0.0,
-        },
-        intrinsics: Intrinsics {
-            focal_px: intr.focal_px,
-            cx: intr.cx,
-            cy: intr.cy,
-            k1: dist_prior[0],
-            k2: dist_prior[1],
-            p1: dist_prior[2],
-            p2: dist_prior[3],
-        },
-        covariance: fallback_covariance(),
+        schema_version: SCHEMA_VERSION,
+        hypotheses: vec![PoseHypothesis {
+            pose: pose.clone(),
+            intrinsics: intrinsics.clone(),
+            cost: 0.0,
+            rmse_px: 0.0,
+            covariance: covariance.clone(),
+        }],
+        pose,
+        intrinsics,
+        covariance,
+        uncertainty,
+        bootstrap: None,
         diagnostics: Diagnostics {
             rmse_px: 0.0,
             inlier_ratio: 1.0,
             residuals_px: vec![0.0],
             inlier_ids: vec![corr.id.clone()],
-            warnings: vec![
-                "Only one correspondence: camera placed at world point location.".to_string(),
-            ],
+            weights: vec![1.0],
+            termination: None,
+            iterations: 0,
+            warnings,
         },
     })
 }
 
 // ── Multi-start wrapper ─────────────────────────────────────────────────────
 
-fn multi_start_optimise(
-    corrs: &[EnuCorrespondence],
-    intr: &CameraIntrinsics,
-    alt_prior: Option<f64>,
-    dist_prior: [f64; 4],
-    np: usize,
-) -> OptResult {
-    let base = initialize_pose(corrs, intr, alt_prior, dist_prior);
+/// At most this many minimal samples are solved with P3P / the focal scan.
+const MAX_MINIMAL_SAMPLES: usize = 60;
+/// Number of best-ranked minimal-solver candidates refined with LM.
+const MINIMAL_REFINED: usize = 8;
+/// A closed-form-seeded solution with an RMSE below this (px) is accepted
+/// without trying further starting points.
+const SEED_ACCEPT_RMSE_PX: f64 = 10.0;
+/// At most this many distinct local minima are kept as pose hypotheses.
+const MAX_HYPOTHESES: usize = 4;
+/// Two solutions closer than this in camera position (m) and attitude (°)
+/// are the same local minimum.
+const HYPOTHESIS_SEPARATION_M: f64 = 1.0;
+const HYPOTHESIS_SEPARATION_DEG: f64 = 1.0;
+/// Alternative hypotheses are reported up to this multiple of the best
+/// RMSE (or `SEED_ACCEPT_RMSE_PX`, whichever is larger).
+const HYPOTHESIS_RMSE_RATIO: f64 = 2.0;
+/// Alternatives within this multiple of the best RMSE (plus 1 px) make the
+/// solution ambiguous and raise a warning.
+const AMBIGUOUS_RMSE_RATIO: f64 = 1.5;
+
+/// Find the global optimum of `problem`, together with the other distinct
+/// local minima met on the way (best first, at most `MAX_HYPOTHESES`).
+///
+/// Starting points are tried from cheapest to most expensive, stopping as
+/// soon as LM reaches an acceptable fit:
+///
+/// 1. the linear DLT camera (n ≥ 6, non-planar) when the focal length is
+///    free, else the linear EPnP pose (n ≥ 4, non-planar);
+/// 2. minimal-solver poses – P3P from correspondence triples, or the focal
+///    scan over quadruples when the focal length is unknown – ranked by
+///    their full cost, the best few refined;
+/// 3. the brute-force grid of initial guesses.
+///
+/// Later stages only run when the earlier ones fail (too few points,
+/// degenerate geometry, or a poor fit – e.g. outliers without RANSAC).
+/// Every distinct minimal-solver minimum is refined in full, so mirror
+/// ambiguities (three points, nearly collinear points) come back as
+/// separate hypotheses.  The result is never empty.
+fn multi_start_optimise(problem: &Problem) -> Vec<OptResult> {
+    let acceptable =
+        |r: &OptResult| reprojection_errors(&r.params, problem.corrs).1 <= SEED_ACCEPT_RMSE_PX;
+    let mut found: Vec<OptResult> = Vec::new();
+
+    let linear_seed = if problem.estimate_focal {
+        dlt_seed(problem).or_else(|| epnp_seed(problem))
+    } else {
+        epnp_seed(problem)
+    };
+    if let Some(seed) = linear_seed {
+        let refined = levenberg_marquardt(seed, problem, 300);
+        if acceptable(&refined) {
+            return vec![refined];
+        }
+        found.push(refined);
+    }
+
+    let seeds = minimal_seeds(problem, MAX_MINIMAL_SAMPLES);
+    if !seeds.is_empty() {
+        let mut ranked: Vec<(f64, [f64; NUM_PARAMS])> =
+            seeds.into_iter().map(|s| (cost(&s, problem), s)).collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let rough: Vec<OptResult> = ranked
+            .iter()
+            .take(MINIMAL_REFINED)
+            .map(|(_, s)| levenberg_marquardt(*s, problem, 30))
+            .collect();
+        for r in distinct_minima(rough) {
+            found.push(levenberg_marquardt(r.params, problem, 300));
+        }
+        if found.iter().any(acceptable) {
+            return distinct_minima(found);
+        }
+    }
+
+    found.push(grid_optimise(problem));
+    distinct_minima(found)
+}
+
+/// `results` sorted by cost with near-duplicates (see `same_minimum`)
+/// removed, keeping the cheapest of each and at most `MAX_HYPOTHESES`.
+fn distinct_minima(mut results: Vec<OptResult>) -> Vec<OptResult> {
+    results.sort_by(|a, b| a.cost.total_cmp(&b.cost));
+    let mut kept: Vec<OptResult> = Vec::new();
+    for r in results {
+        if kept.len() < MAX_HYPOTHESES && kept.iter().all(|k| !same_minimum(k, &r)) {
+            kept.push(r);
+        }
+    }
+    kept
+}
+
+/// Whether two solutions are the same local minimum: camera positions
+/// within `HYPOTHESIS_SEPARATION_M` and attitudes within
+/// `HYPOTHESIS_SEPARATION_DEG` of each other.
+fn same_minimum(a: &OptResult, b: &OptResult) -> bool {
+    let distance = (0..3)
+        .map(|k| (a.params[k] - b.params[k]).powi(2))
+        .sum::<f64>()
+        .sqrt();
+    let (ra, rb) = (
+        rotation_from_params(&a.params),
+        rotation_from_params(&b.params),
+    );
+    // Angle of Ra·Rbᵀ from its trace
+    let trace: f64 = (0..3)
+        .map(|i| (0..3).map(|k| ra[i][k] * rb[i][k]).sum::<f64>())
+        .sum();
+    let angle = (0.5 * (trace - 1.0)).clamp(-1.0, 1.0).acos().to_degrees();
+    distance <= HYPOTHESIS_SEPARATION_M && angle <= HYPOTHESIS_SEPARATION_DEG
+}
+
+/// Brute-force fallback: LM from a grid of headings, distances, altitudes
+/// and pitches around `initialize_pose`.
+fn grid_optimise(problem: &Problem) -> OptResult {
+    let corrs = problem.corrs;
+    let intr = &problem.intr;
+    let base = initialize_pose(corrs, intr, &problem.priors, problem.dist_prior);
     let dist = estimate_scene_distance(corrs, intr);
 
     let me: f64 = corrs.iter().map(|c| c.enu[0]).sum::<f64>() / corrs.len() as f64;
@@ -240,21 +716,31 @@ fn multi_start_optimise(
     ];
     let dist_scales = [0.2, 0.5, 1.0, 3.0, 8.0];
     let mut best: Option<OptResult> = None;
+    let (base_yaw, base_pitch, base_roll) = euler_from_params(&base);
 
     for &off in &yaw_offsets {
-        for &ds in &dist_scales {
+        let yaw = ((base_yaw + off) % 360.0 + 360.0) % 360.0;
+        let yr = yaw.to_radians();
+        // Positions along the viewing direction, plus the position prior
+        // itself when there is one.
+        let mut positions: Vec<[f64; 2]> = dist_scales
+            .iter()
+            .map(|ds| [me - dist * ds * yr.sin(), mn - dist * ds * yr.cos()])
+            .collect();
+        if let Some((en, _)) = problem.priors.position {
+            positions.push(en);
+        }
+        for &[e, n] in &positions {
             for &alt in &alt_candidates {
                 for &poff in &pitch_offsets {
                     let mut init = base;
-                    init[3] = ((init[3] + off) % 360.0 + 360.0) % 360.0;
-                    init[4] = (init[4] + poff).clamp(-80.0, 80.0);
-                    let yr = init[3].to_radians();
-                    let d = dist * ds;
-                    init[0] = me - d * yr.sin();
-                    init[1] = mn - d * yr.cos();
+                    let pitch = (base_pitch + poff).clamp(-90.0, 90.0);
+                    set_rotation(&mut init, &rotation_enu_to_cam(yaw, pitch, base_roll));
+                    init[0] = e;
+                    init[1] = n;
                     init[2] = alt;
 
-                    let r = levenberg_marquardt(init, corrs, intr, alt_prior, dist_prior, np, 30);
+                    let r = levenberg_marquardt(init, problem, 30);
                     if best.is_none() || r.cost < best.as_ref().unwrap().cost {
                         best = Some(r);
                     }
@@ -265,7 +751,7 @@ fn multi_start_optimise(
 
     // Refine the best candidate with many iterations
     let b = best.unwrap();
-    levenberg_marquardt(b.params, corrs, intr, alt_prior, dist_prior, np, 300)
+    levenberg_marquardt(b.params, problem, 300)
 }
 
 // ── Focal-length estimation ─────────────────────────────────────────────────
@@ -277,95 +763,172 @@ fn estimate_focal(req: &SolveRequest) -> f64 {
         .unwrap_or(req.image.width * 0.9)
 }
 
-// ── Reprojection-error diagnostics ──────────────────────────────────────────
+/// Soft constraint `(mean, sigma)` on the focal length while it is free.
+///
+/// A user prior keeps its mean and sigma (default 10 %); without one
+/// the initial `guess` (0.9 × width, or the DLT focal length) only acts as
+/// a weak regulariser (σ = image width) that keeps near-degenerate
+/// geometry from running off.
+fn focal_prior(req: &SolveRequest, guess: f64) -> (f64, f64) {
+    match req.priors.as_ref().and_then(|p| p.focal_px.as_ref()) {
+        Some(f) => mean_sigma(f, 0.1 * f.mean.abs()),
+        None => (guess, req.image.width),
+    }
+}
 
-fn reprojection_errors(
-    params: &[f64; NUM_PARAMS],
-    corrs: &[EnuCorrespondence],
-    base_intr: &CameraIntrinsics,
-) -> (Vec<f64>, f64) {
-    let cam = [params[0], params[1], params[2]];
-    let rot = rotation_enu_to_cam(params[3], params[4], params[5]);
-    // Build full intrinsics including optimised distortion coefficients.
-    let intr = CameraIntrinsics {
-        focal_px: base_intr.focal_px,
-        cx: base_intr.cx,
-        cy: base_intr.cy,
-        k1: params[6],
-        k2: params[7],
-        p1: params[8],
-        p2: params[9],
+/// Focal length of the DLT camera matrix for the uncalibrated mode, or the
+/// warning explaining why it is unavailable (the default guess is kept).
+fn dlt_focal(corrs: &[EnuCorrespondence]) -> Result<f64, String> {
+    if corrs.len() < DLT_MIN_POINTS {
+        return Err(format!(
+            "Uncalibrated solve needs at least {} correspondences; using the default focal length.",
+            DLT_MIN_POINTS
+        ));
+    }
+    let world: Vec<[f64; 3]> = corrs.iter().map(|c| c.enu).collect();
+    if planarity(&world) < DLT_PLANARITY_LIMIT {
+        return Err(
+            "World points are nearly coplanar: the DLT is degenerate, so focal length and principal point are poorly constrained."
+                .to_string(),
+        );
+    }
+    let pixels: Vec<[f64; 2]> = corrs.iter().map(|c| c.pixel).collect();
+    match dlt(&world, &pixels) {
+        Some((_, intr)) if intr.focal_px > 0.0 => Ok(intr.focal_px),
+        _ => Err(
+            "DLT failed to recover a camera matrix; using the default focal length.".to_string(),
+        ),
+    }
+}
+
+/// Standard deviation (px) of the principal-point prior around the image
+/// centre: 10 % of the longer image side, wide enough for typical crops
+/// while keeping cx/cy from trading off freely against yaw and pitch.
+fn principal_point_sigma(req: &SolveRequest) -> f64 {
+    0.1 * req.image.width.max(req.image.height).max(1.0)
+}
+
+// ── Pose priors ─────────────────────────────────────────────────────────────
+
+/// `(mean, sigma)` of a prior, substituting `default_sigma` when the sigma
+/// is missing or not positive.
+fn mean_sigma(prior: &GaussianPrior, default_sigma: f64) -> (f64, f64) {
+    let sigma = prior.sigma.filter(|s| *s > 0.0).unwrap_or(default_sigma);
+    (prior.mean, sigma)
+}
+
+/// Convert the request's pose priors into the optimiser's ENU frame
+/// centred on `(ref_lat, ref_lon, ref_alt)`.
+fn pose_priors(req: &SolveRequest, ref_lat: f64, ref_lon: f64, ref_alt: f64) -> PosePriors {
+    let Some(p) = req.priors.as_ref() else {
+        return PosePriors::default();
     };
-    let mut res = Vec::with_capacity(corrs.len());
-    let mut ss = 0.0;
-    for c in corrs {
-        let r = match project_point(c.enu, cam, &rot, &intr) {
-            Some((u, v)) => (u - c.pixel[0]).hypot(v - c.pixel[1]),
-            None => 1000.0,
-        };
-        ss += r * r;
-        res.push(r);
-    }
-    let rmse = (ss / corrs.len() as f64).sqrt();
-    (res, rmse)
+    PosePriors {
+        position: p.position.as_ref().map(|pos| {
+            let enu = lla_to_enu(pos.lat, pos.lon, ref_alt, ref_lat, ref_lon, ref_alt);
+            let sigma = pos
+                .sigma_m
+                .filter(|s| *s > 0.0)
+                .unwrap_or(DEFAULT_POSITION_SIGMA_M);
+            ([enu[0], enu[1]], sigma)
+        }),
+        alt: p.camera_alt.as_ref().map(|a| {
+            let (mean, sigma) = mean_sigma(a, DEFAULT_ALT_SIGMA_M);
+            (mean - ref_alt, sigma)
+        }),
+        yaw: p
+            .yaw_deg
+            .as_ref()
+            .map(|g| mean_sigma(g, DEFAULT_YAW_SIGMA_DEG)),
+        pitch: p
+            .pitch_deg
+            .as_ref()
+            .map(|g| mean_sigma(g, DEFAULT_TILT_SIGMA_DEG)),
+        roll: p
+            .roll_deg
+            .as_ref()
+            .map(|g| mean_sigma(g, DEFAULT_TILT_SIGMA_DEG)),
+    }
 }
 
 // ── Covariance ──────────────────────────────────────────────────────────────
 
-fn build_covariance(result: &OptResult, corrs: &[EnuCorrespondence], ref_lat: f64) -> Covariance {
+/// Output order of the covariance matrix.
+const COVARIANCE_LABELS: [&str; NUM_PARAMS] = [
+    "lat", "lon", "alt", "yawDeg", "pitchDeg", "rollDeg", "k1", "k2", "p1", "p2", "focalPx", "cx",
+    "cy",
+];
+
+/// Linearised covariance `σ² (JᵀJ)⁻¹` of the fit, in the output order and
+/// units of `COVARIANCE_LABELS` (degrees of latitude / longitude, converted
+/// with the WGS-84 radii at the camera).  `None` when `JᵀJ` is singular;
+/// callers then report `fallback_covariance` with a warning.
+pub(crate) fn build_covariance(
+    result: &OptResult,
+    corrs: &[EnuCorrespondence],
+    cam_lat: f64,
+    cam_alt: f64,
+) -> Option<Covariance> {
     let n_obs = corrs.len() * 2;
-    let sigma2 = if n_obs > NUM_PARAMS {
-        result.cost / (n_obs - NUM_PARAMS) as f64
+    let n_free = result.free.iter().filter(|f| **f).count();
+    let sigma2 = if n_obs > n_free {
+        result.cost / (n_obs - n_free) as f64
     } else {
         result.cost.max(1.0)
     };
 
-    let cov_raw = invert_nxn(&result.jtj, sigma2);
-
-    // Re-order [e,n,u,yaw,pitch,roll,k1,k2,p1,p2]
-    //       → [lat(n),lon(e),alt(u),yaw,pitch,roll,k1,k2,p1,p2]
-    let m_lat = 111_320.0;
-    let m_lon = 111_320.0 * ref_lat.to_radians().cos();
-    // map[i] = which optimizer param corresponds to output param i
-    let map: [usize; NUM_PARAMS] = [1, 0, 2, 3, 4, 5, 6, 7, 8, 9];
-    let scl: [f64; NUM_PARAMS] = [
-        1.0 / m_lat,
-        1.0 / m_lon,
-        1.0,
-        1.0,
-        1.0,
-        1.0,
-        1.0,
-        1.0,
-        1.0,
-        1.0,
-    ];
+    let cov_raw = invert_nxn(&result.jtj, sigma2)?;
+
+    // Linear map [e,n,u,δ,k1,k2,p1,p2,f,cx,cy]
+    //         → [lat(n),lon(e),alt(u),yaw,pitch,roll,k1,k2,p1,p2,f,cx,cy],
+    // with δ the local rotation the optimiser works in; the covariance
+    // transforms as  A · Σ · Aᵀ.
+    let (m_lat, m_lon) = metres_per_degree(cam_lat, cam_alt);
+    let mut a = [[0.0f64; NUM_PARAMS]; NUM_PARAMS];
+    a[0][1] = 1.0 / m_lat;
+    a[1][0] = 1.0 / m_lon;
+    a[2][2] = 1.0;
+    let euler_jac = euler_local_jacobian(&rotation_from_params(&result.params));
+    for (row, jac_row) in a[3..6].iter_mut().zip(euler_jac) {
+        row[3..6].copy_from_slice(&jac_row);
+    }
+    for (i, row) in a.iter_mut().enumerate().skip(6) {
+        row[i] = 1.0;
+    }
 
+    // Fixed parameters are known exactly: leave them out instead of
+    // propagating the unit placeholder the optimiser used.
+    let cov = |i: usize, j: usize| {
+        if result.free[i] && result.free[j] {
+            cov_raw[i * NUM_PARAMS + j]
+        } else {
+            0.0
+        }
+    };
     let mut out = [0.0f64; NUM_PARAMS * NUM_PARAMS];
     for i in 0..NUM_PARAMS {
         for j in 0..NUM_PARAMS {
-            out[i * NUM_PARAMS + j] = cov_raw[map[i] * NUM_PARAMS + map[j]] * scl[i] * scl[j];
+            let mut sum = 0.0;
+            for k in (0..NUM_PARAMS).filter(|&k| a[i][k] != 0.0) {
+                for l in (0..NUM_PARAMS).filter(|&l| a[j][l] != 0.0) {
+                    sum += a[i][k] * cov(k, l) * a[j][l];
+                }
+            }
+            out[i * NUM_PARAMS + j] = sum;
         }
     }
 
-    Covariance {
-        labels: vec![
-            "lat".into(),
-            "lon".into(),
-            "alt".into(),
-            "yawDeg".into(),
-            "pitchDeg".into(),
-            "rollDeg".into(),
-            "k1".into(),
-            "k2".into(),
-            "p1".into(),
-            "p2".into(),
-        ],
+    Some(Covariance {
+        labels: COVARIANCE_LABELS.iter().map(|l| l.to_string()).collect(),
         matrix: out.to_vec(),
-    }
+    })
 }
 
-fn invert_nxn(mat: &[f64; NUM_PARAMS * NUM_PARAMS], scale: f64) -> [f64; NUM_PARAMS * NUM_PARAMS] {
+/// `scale · mat⁻¹`, or `None` when `mat` is (numerically) singular.
+fn invert_nxn(
+    mat: &[f64; NUM_PARAMS * NUM_PARAMS],
+    scale: f64,
+) -> Option<[f64; NUM_PARAMS * NUM_PARAMS]> {
     const N: usize = NUM_PARAMS;
     // Augmented matrix [A | I], stored as N rows × 2N cols.
     // Use a flat Vec to avoid issues with non-literal const expressions in
@@ -389,7 +952,7 @@ fn invert_nxn(mat: &[f64; NUM_PARAMS * NUM_PARAMS], scale: f64) -> [f64; NUM_PAR
             }
         }
         if mx < 1e-30 {
-            return fallback_cov_matrix();
+            return None;
         }
         if mr != col {
             // Swap rows col and mr
@@ -418,39 +981,36 @@ fn invert_nxn(mat: &[f64; NUM_PARAMS * NUM_PARAMS], scale: f64) -> [f64; NUM_PAR
             r[i * N + j] = a[i * 2 * N + (N + j)] * scale;
         }
     }
-    r
+    r.iter().all(|v| v.is_finite()).then_some(r)
 }
 
 fn fallback_cov_matrix() -> [f64; NUM_PARAMS * NUM_PARAMS] {
     let mut m = [0.0f64; NUM_PARAMS * NUM_PARAMS];
-    // Diagonal heuristics: [lat, lon, alt, yaw, pitch, roll, k1, k2, p1, p2]
-    m[0 * NUM_PARAMS + 0] = 1e-8; // lat  variance
-    m[1 * NUM_PARAMS + 1] = 1e-8; // lon  variance
-    m[2 * NUM_PARAMS + 2] = 4.0; // alt  (m²)
-    m[3 * NUM_PARAMS + 3] = 25.0; // yaw  (deg²)
-    m[4 * NUM_PARAMS + 4] = 9.0; // pitch(deg²)
-    m[5 * NUM_PARAMS + 5] = 9.0; // roll (deg²)
-    m[6 * NUM_PARAMS + 6] = 0.25; // k1
-    m[7 * NUM_PARAMS + 7] = 0.0625; // k2
-    m[8 * NUM_PARAMS + 8] = 2.5e-3; // p1
-    m[9 * NUM_PARAMS + 9] = 2.5e-3; // p2
+    // Diagonal heuristics: [lat, lon, alt, yaw, pitch, roll, k1, k2, p1, p2, f, cx, cy]
+    let diag: [f64; NUM_PARAMS] = [
+        1e-8,   // lat  variance
+        1e-8,   // lon  variance
+        4.0,    // alt  (m²)
+        25.0,   // yaw  (deg²)
+        9.0,    // pitch(deg²)
+        9.0,    // roll (deg²)
+        0.25,   // k1
+        0.0625, // k2
+        2.5e-3, // p1
+        2.5e-3, // p2
+        1e4,    // f    (px²)
+        1e4,    // cx   (px²)
+        1e4,    // cy   (px²)
+    ];
+    for (i, d) in diag.iter().enumerate() {
+        m[i * NUM_PARAMS + i] = *d;
+    }
     m
 }
 
 fn fallback_covariance() -> Covariance {
     Covariance {
-        labels: vec![
-            "lat".into(),
-            "lon".into(),
-            "alt".into(),
-            "yawDeg".into(),
-            "pitchDeg".into(),
-            "rollDeg".into(),
-            "k1".into(),
-            "k2".into(),
-            "p1".into(),
-            "p2".into(),
-        ],
+        labels: COVARIANCE_LABELS.iter().map(|l| l.to_string()).collect(),
         matrix: fallback_cov_matrix().to_vec(),
     }
 }
@@ -8,6 +8,23 @@ fn prime_vertical_radius(sin_lat: f64) -> f64 {
     WGS84_A / (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt()
 }
 
+/// Radius of curvature in the meridian.
+fn meridian_radius(sin_lat: f64) -> f64 {
+    WGS84_A * (1.0 - WGS84_E2) / (1.0 - WGS84_E2 * sin_lat * sin_lat).powf(1.5)
+}
+
+/// Metres per degree of latitude and of longitude at `lat_deg` and `alt`
+/// metres above the ellipsoid, from the WGS-84 radii of curvature.
+pub(crate) fn metres_per_degree(lat_deg: f64, alt: f64) -> (f64, f64) {
+    let lat = lat_deg.to_radians();
+    let sin_lat = lat.sin();
+    let deg = 1f64.to_radians();
+    (
+        (meridian_radius(sin_lat) + alt) * deg,
+        (prime_vertical_radius(sin_lat) + alt) * lat.cos() * deg,
+    )
+}
+
 // ── Coordinate conversions ──────────────────────────────────────────────────
 
 /// Convert geodetic LLA (lat/lon degrees, alt metres above ellipsoid) → ECEF.
@@ -1,35 +1,108 @@
+mod batch;
+mod bootstrap;
+mod bundle;
+mod dlt;
+mod error;
 mod estimator;
 mod geo;
+mod linalg;
+mod mode;
 mod optimizer;
+mod pnp;
 mod projection;
+mod ransac;
+mod reproject;
+mod rng;
+mod schema;
+mod triangulate;
 pub mod types;
+mod uncertainty;
+mod validate;
 
-use types::SolveRequest;
+use serde::{de::DeserializeOwned, Serialize};
 use wasm_bindgen::prelude::*;
 
+pub use batch::{solve_batch, BatchOptions, BatchSummary};
+pub use bundle::bundle_adjust_impl;
+pub use error::SolveError;
 pub use estimator::solve_impl;
+pub use mode::{solve_request, SolverMode};
+pub use reproject::{back_project_impl, reproject_impl};
+pub use schema::{parse_solve_request, request_schema, response_schema, SCHEMA_VERSION};
+pub use triangulate::triangulate_impl;
 
+/// Solve a JSON `SolveRequest`.  Errors are thrown as the JSON of a
+/// `SolveError` (`{ code, message, details }`).
 #[wasm_bindgen]
 pub fn solve(req_json: String) -> Result<String, JsValue> {
-    let req: SolveRequest = serde_json::from_str(&req_json)
-        .map_err(|e| to_js_error(format!("Invalid request JSON: {e}")))?;
-    let resp = solve_impl(&req).map_err(to_js_error)?;
-    serde_json::to_string(&resp).map_err(|e| to_js_error(format!("Serialize error: {e}")))
+    solve_json(&req_json).map_err(to_js_error)
 }
 
-#[cfg(target_arch = "wasm32")]
-fn to_js_error(message: String) -> JsValue {
-    JsValue::from_str(&message)
+/// JSON in / JSON out core of `solve`, usable from native code: the error
+/// is the serialised `SolveError`.  The request is migrated to the current
+/// `SCHEMA_VERSION` first; ignored unknown fields are reported as warnings.
+pub fn solve_json(req_json: &str) -> Result<String, String> {
+    let (req, unknown) = parse_solve_request(req_json).map_err(|e| e.to_json())?;
+    let mut resp = solve_impl(&req).map_err(|e| e.to_json())?;
+    resp.diagnostics.warnings.extend(unknown);
+    to_json_string(&resp)
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn to_js_error(_message: String) -> JsValue {
-    JsValue::NULL
+/// Back-project a pixel of a solved image onto a horizontal surface at a
+/// given ellipsoidal altitude and return the lat/lon/alt seen there.
+#[wasm_bindgen]
+pub fn back_project(req_json: String) -> Result<String, JsValue> {
+    call_json(&req_json, back_project_impl).map_err(to_js_error)
 }
 
+/// Jointly adjust several images that share tie points; see
+/// `BundleRequest`.
 #[wasm_bindgen]
-pub fn reproject_points(_req_json: String) -> Result<String, JsValue> {
-    Ok("{\"pixels\": [], \"warnings\": [\"Not implemented yet\"]}".to_string())
+pub fn bundle_adjust(req_json: String) -> Result<String, JsValue> {
+    call_json(&req_json, bundle_adjust_impl).map_err(to_js_error)
+}
+
+/// Locate a feature clicked in two or more solved images; see
+/// `TriangulateRequest`.
+#[wasm_bindgen]
+pub fn triangulate(req_json: String) -> Result<String, JsValue> {
+    call_json(&req_json, triangulate_impl).map_err(to_js_error)
+}
+
+#[wasm_bindgen]
+pub fn reproject_points(req_json: String) -> Result<String, JsValue> {
+    call_json(&req_json, reproject_impl).map_err(to_js_error)
+}
+
+/// Parse `req_json`, run `f` and serialise its response; every failure
+/// becomes the JSON of a `SolveError`.
+fn call_json<Req: DeserializeOwned, Resp: Serialize>(
+    req_json: &str,
+    f: impl Fn(&Req) -> Result<Resp, SolveError>,
+) -> Result<String, String> {
+    let req: Req = serde_json::from_str(req_json).map_err(|e| SolveError::from(e).to_json())?;
+    let resp = f(&req).map_err(|e| e.to_json())?;
+    to_json_string(&resp)
+}
+
+fn to_json_string<Resp: Serialize>(resp: &Resp) -> Result<String, String> {
+    serde_json::to_string(resp).map_err(|e| {
+        SolveError::Serialization {
+            message: e.to_string(),
+        }
+        .to_json()
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+fn to_js_error(error_json: String) -> JsValue {
+    JsValue::from_str(&error_json)
+}
+
+// JsValues cannot be created outside wasm; native callers use `solve_json`.
+#[cfg(not(target_arch = "wasm32"))]
+fn to_js_error(_error_json: String) -> JsValue {
+    JsValue::NULL
 }
 
 #[cfg(test)]
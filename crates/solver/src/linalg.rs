@@ -0,0 +1,325 @@
+// Small dense linear-algebra helpers for the closed-form pose solvers.
+//
+// Everything is fixed-size and allocation-light: the matrices involved are
+// at most 12×12, so simple and robust algorithms (cyclic Jacobi, interval
+// bisection) are preferred over fast ones.
+
+// ── Polynomial roots ────────────────────────────────────────────────────────
+
+/// Real roots of `coeffs[0]·xⁿ + coeffs[1]·xⁿ⁻¹ + … + coeffs[n]`, ascending.
+///
+/// Works recursively: the real roots of the derivative split the real line
+/// into monotonic intervals, each of which holds at most one root that is
+/// found by bisection.  A root of even multiplicity (the polynomial touches
+/// zero without crossing) is reported when the value at the critical point
+/// is zero to working precision.  Leading coefficients that are negligible
+/// relative to the rest are dropped, so a degenerate quartic is solved as
+/// the cubic it really is.
+pub(crate) fn polynomial_real_roots(coeffs: &[f64]) -> Vec<f64> {
+    let scale = coeffs.iter().fold(0.0f64, |m, c| m.max(c.abs()));
+    if scale == 0.0 || !scale.is_finite() {
+        return Vec::new();
+    }
+    let first = coeffs
+        .iter()
+        .position(|c| c.abs() > 1e-14 * scale)
+        .unwrap_or(coeffs.len());
+    let c: Vec<f64> = coeffs[first..].iter().map(|v| v / coeffs[first]).collect();
+
+    match c.len() {
+        0 | 1 => Vec::new(),
+        2 => vec![-c[1]],
+        3 => quadratic_roots(c[1], c[2]),
+        n => {
+            let degree = n - 1;
+            let derivative: Vec<f64> = c[..degree]
+                .iter()
+                .enumerate()
+                .map(|(i, v)| v * (degree - i) as f64)
+                .collect();
+            let critical = polynomial_real_roots(&derivative);
+
+            // Cauchy bound: every root lies in [-bound, bound].
+            let bound = 1.0 + c[1..].iter().fold(0.0f64, |m, v| m.max(v.abs()));
+            let mut edges = vec![-bound];
+            edges.extend(critical.iter().copied().filter(|x| x.abs() < bound));
+            edges.push(bound);
+
+            let eval = |x: f64| c.iter().fold(0.0, |acc, v| acc * x + v);
+            let mut roots: Vec<f64> = Vec::new();
+            for w in edges.windows(2) {
+                let (mut lo, mut hi) = (w[0], w[1]);
+                let (flo, fhi) = (eval(lo), eval(hi));
+                if flo == 0.0 {
+                    roots.push(lo);
+                    continue;
+                }
+                if flo.signum() == fhi.signum() {
+                    continue;
+                }
+                for _ in 0..200 {
+                    let mid = 0.5 * (lo + hi);
+                    if mid <= lo || mid >= hi {
+                        break;
+                    }
+                    if eval(mid).signum() == flo.signum() {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                roots.push(0.5 * (lo + hi));
+            }
+            // Touching (even-multiplicity) roots at critical points.
+            let magnitude = |x: f64| c.iter().fold(0.0, |acc, v| acc * x.abs() + v.abs());
+            for &x in &critical {
+                if eval(x).abs() <= 1e-12 * magnitude(x)
+                    && roots.iter().all(|r| (r - x).abs() > 1e-9 * (1.0 + x.abs()))
+                {
+                    roots.push(x);
+                }
+            }
+            roots.sort_by(|a, b| a.total_cmp(b));
+            roots.dedup_by(|a, b| (*a - *b).abs() <= 1e-12 * (1.0 + a.abs()));
+            roots
+        }
+    }
+}
+
+/// Real roots of the monic quadratic `x² + b·x + c`, ascending.
+fn quadratic_roots(b: f64, c: f64) -> Vec<f64> {
+    let disc = b * b - 4.0 * c;
+    if disc < 0.0 {
+        return Vec::new();
+    }
+    if disc == 0.0 {
+        return vec![-0.5 * b];
+    }
+    // Numerically stable form (no cancellation between -b and √disc).
+    let q = -0.5 * (b + b.signum() * disc.sqrt());
+    let (r1, r2) = if q == 0.0 { (0.0, 0.0) } else { (q, c / q) };
+    if r1 < r2 {
+        vec![r1, r2]
+    } else {
+        vec![r2, r1]
+    }
+}
+
+// ── Symmetric eigen-decomposition ───────────────────────────────────────────
+
+/// Eigen-decomposition of a symmetric `N×N` matrix by cyclic Jacobi
+/// rotations.
+///
+/// Returns the eigenvalues in ascending order and the matching unit
+/// eigenvectors as the **columns** of the second result.  Only the upper
+/// triangle of `a` is read.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn symmetric_eigen<const N: usize>(a: &[[f64; N]; N]) -> ([f64; N], [[f64; N]; N]) {
+    let mut m = *a;
+    for i in 0..N {
+        for j in 0..i {
+            m[i][j] = m[j][i];
+        }
+    }
+    let mut v = [[0.0; N]; N];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+
+    for _sweep in 0..100 {
+        let off: f64 = (0..N)
+            .flat_map(|i| ((i + 1)..N).map(move |j| (i, j)))
+            .map(|(i, j)| m[i][j] * m[i][j])
+            .sum();
+        let total: f64 = m.iter().flatten().map(|x| x * x).sum();
+        if off <= 1e-30 * total.max(f64::MIN_POSITIVE) {
+            break;
+        }
+        for p in 0..N {
+            for q in (p + 1)..N {
+                if m[p][q].abs() < f64::MIN_POSITIVE {
+                    continue;
+                }
+                // Rotation angle that zeroes m[p][q]
+                let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let t = if theta == 0.0 { 1.0 } else { t };
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..N {
+                    let (mkp, mkq) = (m[k][p], m[k][q]);
+                    m[k][p] = c * mkp - s * mkq;
+                    m[k][q] = s * mkp + c * mkq;
+                }
+                for k in 0..N {
+                    let (mpk, mqk) = (m[p][k], m[q][k]);
+                    m[p][k] = c * mpk - s * mqk;
+                    m[q][k] = s * mpk + c * mqk;
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut order: [usize; N] = [0; N];
+    for (i, o) in order.iter_mut().enumerate() {
+        *o = i;
+    }
+    order.sort_by(|&a, &b| m[a][a].total_cmp(&m[b][b]));
+    let mut values = [0.0; N];
+    let mut vectors = [[0.0; N]; N];
+    for (dst, &src) in order.iter().enumerate() {
+        values[dst] = m[src][src];
+        for k in 0..N {
+            vectors[k][dst] = v[k][src];
+        }
+    }
+    (values, vectors)
+}
+
+// ── Point-cloud shape ───────────────────────────────────────────────────────
+
+/// `(centroid, spread, axes)` of a point cloud, see `principal_axes`.
+pub(crate) type PrincipalAxes = ([f64; 3], [f64; 3], [[f64; 3]; 3]);
+
+/// Centroid, principal standard deviations (ascending) and principal axes
+/// (as the columns of the matrix) of a 3-D point cloud.
+///
+/// The smallest spread relative to the largest measures how far the cloud
+/// is from planar; `None` for an empty slice.
+pub(crate) fn principal_axes(points: &[[f64; 3]]) -> Option<PrincipalAxes> {
+    let n = points.len() as f64;
+    if points.is_empty() {
+        return None;
+    }
+    let mut centroid = [0.0; 3];
+    for p in points {
+        for k in 0..3 {
+            centroid[k] += p[k] / n;
+        }
+    }
+    let mut cov = [[0.0; 3]; 3];
+    for p in points {
+        for i in 0..3 {
+            for j in 0..3 {
+                cov[i][j] += (p[i] - centroid[i]) * (p[j] - centroid[j]) / n;
+            }
+        }
+    }
+    let (variances, axes) = symmetric_eigen(&cov);
+    Some((centroid, variances.map(|v| v.max(0.0).sqrt()), axes))
+}
+
+// ── Linear systems ──────────────────────────────────────────────────────────
+
+/// Solve the square system `A·x = b` by Gaussian elimination with partial
+/// pivoting.  Returns `None` when `A` is singular to working precision.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn solve_linear<const N: usize>(a: &[[f64; N]; N], b: &[f64; N]) -> Option<[f64; N]> {
+    let mut a = *a;
+    let mut b = *b;
+    let scale = a.iter().flatten().fold(0.0f64, |m, v| m.max(v.abs()));
+    for col in 0..N {
+        let (mut mx, mut mr) = (a[col][col].abs(), col);
+        for (row, r) in a.iter().enumerate().skip(col + 1) {
+            if r[col].abs() > mx {
+                mx = r[col].abs();
+                mr = row;
+            }
+        }
+        if mx <= 1e-14 * scale || mx == 0.0 {
+            return None;
+        }
+        a.swap(col, mr);
+        b.swap(col, mr);
+        for row in (col + 1)..N {
+            let f = a[row][col] / a[col][col];
+            for k in col..N {
+                a[row][k] -= f * a[col][k];
+            }
+            b[row] -= f * b[col];
+        }
+    }
+    let mut x = [0.0; N];
+    for i in (0..N).rev() {
+        let s: f64 = ((i + 1)..N).map(|j| a[i][j] * x[j]).sum();
+        x[i] = (b[i] - s) / a[i][i];
+    }
+    Some(x)
+}
+
+/// `solve_linear` for a system whose size is only known at run time: `a`
+/// is the row-major `n × n` matrix for `n = b.len()`.
+pub(crate) fn solve_dense(mut a: Vec<f64>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    let scale = a.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    for col in 0..n {
+        let (mut mx, mut mr) = (a[col * n + col].abs(), col);
+        for row in (col + 1)..n {
+            if a[row * n + col].abs() > mx {
+                mx = a[row * n + col].abs();
+                mr = row;
+            }
+        }
+        if mx <= 1e-14 * scale || mx == 0.0 {
+            return None;
+        }
+        if mr != col {
+            for k in 0..n {
+                a.swap(col * n + k, mr * n + k);
+            }
+            b.swap(col, mr);
+        }
+        for row in (col + 1)..n {
+            let f = a[row * n + col] / a[col * n + col];
+            if f == 0.0 {
+                continue;
+            }
+            for k in col..n {
+                a[row * n + k] -= f * a[col * n + k];
+            }
+            b[row] -= f * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let s: f64 = ((i + 1)..n).map(|j| a[i * n + j] * x[j]).sum();
+        x[i] = (b[i] - s) / a[i * n + i];
+    }
+    Some(x)
+}
+
+/// Inverse of a 3×3 matrix, column by column; `None` when singular.
+pub(crate) fn invert3(m: &[[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let mut inv = [[0.0; 3]; 3];
+    for j in 0..3 {
+        let mut e = [0.0; 3];
+        e[j] = 1.0;
+        let col = solve_linear(m, &e)?;
+        for i in 0..3 {
+            inv[i][j] = col[i];
+        }
+    }
+    Some(inv)
+}
+
+/// Least-squares solution of the over-determined system `rows · x ≈ rhs`
+/// via the normal equations.  Adequate for the small, well-scaled systems
+/// of the pose solvers; `None` when the columns are linearly dependent.
+pub(crate) fn least_squares<const N: usize>(rows: &[[f64; N]], rhs: &[f64]) -> Option<[f64; N]> {
+    let mut ata = [[0.0; N]; N];
+    let mut atb = [0.0; N];
+    for (row, &r) in rows.iter().zip(rhs) {
+        for i in 0..N {
+            atb[i] += row[i] * r;
+            for j in 0..N {
+                ata[i][j] += row[i] * row[j];
+            }
+        }
+    }
+    solve_linear(&ata, &atb)
+}
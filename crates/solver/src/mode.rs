@@ -0,0 +1,54 @@
+use crate::error::SolveError;
+use crate::estimator::solve_impl;
+use crate::schema::parse_solve_request;
+use crate::types::{SolveResponse, SolverModel};
+
+// ── Solver modes ────────────────────────────────────────────────────────────
+
+/// Which camera parameters to estimate; replaces the request's `model`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolverMode {
+    /// Pose only: focal length from the prior, no distortion.
+    Calibrated,
+    /// Pose and focal length.
+    Focal,
+    /// Pose, focal length, distortion and principal point.
+    Full,
+    /// DLT initialisation of focal length and principal point.
+    Uncalibrated,
+}
+
+impl SolverMode {
+    /// The request `model` this mode stands for.
+    pub fn model(self) -> SolverModel {
+        let (focal, distortion, principal_point, uncalibrated) = match self {
+            Self::Calibrated => (false, false, false, false),
+            Self::Focal => (true, false, false, false),
+            Self::Full => (true, true, true, false),
+            Self::Uncalibrated => (true, false, true, true),
+        };
+        SolverModel {
+            estimate_focal: Some(focal),
+            estimate_distortion: distortion,
+            estimate_principal_point: principal_point,
+            uncalibrated,
+        }
+    }
+}
+
+// ── Solving ─────────────────────────────────────────────────────────────────
+
+/// Solve one request JSON the way `solve_json` does, with `mode` (if any)
+/// replacing the request's model.
+pub fn solve_request(
+    req_json: &str,
+    mode: Option<SolverMode>,
+) -> Result<SolveResponse, SolveError> {
+    let (mut req, unknown) = parse_solve_request(req_json)?;
+    if let Some(mode) = mode {
+        req.model = Some(mode.model());
+    }
+    let mut resp = solve_impl(&req)?;
+    resp.diagnostics.warnings.extend(unknown);
+    Ok(resp)
+}
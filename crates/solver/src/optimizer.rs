@@ -1,27 +1,230 @@
-use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
+use crate::projection::{
+    cross, euler_from_rotation, euler_local_jacobian, mat3_mul, mat3_vec,
+    project_cam_point_jacobian, project_point, rotation_enu_to_cam, rotation_from_vector,
+    rotation_to_vector, CameraIntrinsics, Mat3,
+};
+use crate::types::{RobustLoss, TerminationReason};
 
 /// Parameter vector layout:
 /// ```text
-/// [0] e       – camera East  (m, ENU)
-/// [1] n       – camera North (m, ENU)
-/// [2] u       – camera Up    (m, ENU)
-/// [3] yaw°    – heading (0 = North, clockwise)
-/// [4] pitch°  – elevation (-90 = straight down)
-/// [5] roll°   – bank (0 = level)
-/// [6] k1      – radial distortion, 1st order
-/// [7] k2      – radial distortion, 2nd order
-/// [8] p1      – tangential distortion 1
-/// [9] p2      – tangential distortion 2
+/// [0]  e       – camera East  (m, ENU)
+/// [1]  n       – camera North (m, ENU)
+/// [2]  u       – camera Up    (m, ENU)
+/// [3]  ω₁      – rotation vector ENU → camera (rad)
+/// [4]  ω₂
+/// [5]  ω₃
+/// [6]  k1      – radial distortion, 1st order
+/// [7]  k2      – radial distortion, 2nd order
+/// [8]  p1      – tangential distortion 1
+/// [9]  p2      – tangential distortion 2
+/// [10] f       – focal length (px)
+/// [11] cx      – principal point, x (px)
+/// [12] cy      – principal point, y (px)
 /// ```
-pub(crate) const NUM_PARAMS: usize = 10;
+///
+/// The attitude is stored as a rotation vector rather than yaw / pitch /
+/// roll, which have a singularity at pitch ±90°.  LM updates it locally
+/// (`apply_step`); `euler_from_params` recovers the Euler angles that the
+/// priors act on and that the API reports.
+pub(crate) const NUM_PARAMS: usize = 13;
+
+/// Index of the focal length in the parameter vector.
+pub(crate) const FOCAL: usize = 10;
+
+/// Index of the principal point x coordinate (y follows at `CX + 1`).
+pub(crate) const CX: usize = 11;
+
+// ── Parameter vector ────────────────────────────────────────────────────────
+
+/// Rotation ENU → camera stored in a parameter vector.
+pub(crate) fn rotation_from_params(params: &[f64; NUM_PARAMS]) -> Mat3 {
+    rotation_from_vector(&[params[3], params[4], params[5]])
+}
+
+/// `(yaw°, pitch°, roll°)` of the rotation stored in a parameter vector.
+pub(crate) fn euler_from_params(params: &[f64; NUM_PARAMS]) -> (f64, f64, f64) {
+    euler_from_rotation(&rotation_from_params(params))
+}
+
+/// Store the rotation ENU → camera `rot` in a parameter vector.
+pub(crate) fn set_rotation(params: &mut [f64; NUM_PARAMS], rot: &Mat3) {
+    params[3..6].copy_from_slice(&rotation_to_vector(rot));
+}
+
+/// Parameter vector for a camera at `cam` (ENU) with the given Euler
+/// angles and distortion, and the focal length and principal point of
+/// `intr`.
+pub(crate) fn params_from_pose(
+    cam: [f64; 3],
+    (yaw_deg, pitch_deg, roll_deg): (f64, f64, f64),
+    dist: [f64; 4],
+    intr: &CameraIntrinsics,
+) -> [f64; NUM_PARAMS] {
+    let mut params = [
+        cam[0],
+        cam[1],
+        cam[2],
+        0.0,
+        0.0,
+        0.0,
+        dist[0],
+        dist[1],
+        dist[2],
+        dist[3],
+        intr.focal_px,
+        intr.cx,
+        intr.cy,
+    ];
+    set_rotation(
+        &mut params,
+        &rotation_enu_to_cam(yaw_deg, pitch_deg, roll_deg),
+    );
+    params
+}
+
+/// `params` moved by an LM step.  Every free entry is updated additively
+/// except the rotation: `step[3..6]` is a small rotation `δ` in the camera
+/// frame, applied as `R ← exp([δ]×) · R`, so the update is equally well
+/// behaved at every attitude.
+pub(crate) fn apply_step(
+    params: &[f64; NUM_PARAMS],
+    step: &[f64; NUM_PARAMS],
+    free: &[bool; NUM_PARAMS],
+) -> [f64; NUM_PARAMS] {
+    let mut out = *params;
+    for i in (0..NUM_PARAMS).filter(|&i| free[i] && !(3..6).contains(&i)) {
+        out[i] += step[i];
+    }
+    if free[3] {
+        let delta = rotation_from_vector(&[step[3], step[4], step[5]]);
+        set_rotation(&mut out, &mat3_mul(&delta, &rotation_from_params(params)));
+    }
+    out
+}
 
 // ── Data types ──────────────────────────────────────────────────────────────
 
 /// A single pixel ↔ world correspondence expressed in ENU.
+///
+/// `sigma` is the pixel standard deviation used to normalise the residual;
+/// `world_sigma` is the world point's position uncertainty (m), folded
+/// into `sigma` by `propagate_world_sigma`.
+#[derive(Clone)]
 pub(crate) struct EnuCorrespondence {
     pub enu: [f64; 3],
     pub pixel: [f64; 2],
     pub sigma: f64,
+    pub world_sigma: f64,
+}
+
+/// Gaussian priors on the camera pose, each `(mean, sigma)` in optimiser
+/// units (ENU metres, degrees).  Absent priors add no residual.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct PosePriors {
+    /// Horizontal position `([east, north], sigma_m)`, isotropic.
+    pub position: Option<([f64; 2], f64)>,
+    /// Camera up coordinate (m).
+    pub alt: Option<(f64, f64)>,
+    /// Heading (°); the residual is wrapped to ±180°.
+    pub yaw: Option<(f64, f64)>,
+    pub pitch: Option<(f64, f64)>,
+    pub roll: Option<(f64, f64)>,
+}
+
+/// Fixed inputs of one optimisation: the correspondences plus every prior
+/// and switch that shapes the residual vector.
+///
+/// Frozen parameters (see `free`) keep the value they have in the initial
+/// parameter vector – callers seed them with the prior / fixed value – and
+/// contribute neither residuals nor Jacobian columns.
+#[derive(Clone)]
+pub(crate) struct Problem<'a> {
+    pub corrs: &'a [EnuCorrespondence],
+    /// Initial intrinsics; `params[FOCAL]` and `params[CX..]` are seeded
+    /// from these and the principal-point prior is centred on them.
+    pub intr: CameraIntrinsics,
+    /// Gaussian priors on the camera pose.
+    pub priors: PosePriors,
+    /// Distortion prior / fixed value `[k1, k2, p1, p2]`.
+    pub dist_prior: [f64; 4],
+    /// Soft focal-length constraint `(mean, sigma)` in px, applied while the
+    /// focal length is free.
+    pub focal_prior: Option<(f64, f64)>,
+    /// Standard deviation (px) of the principal-point prior around
+    /// `intr.cx, intr.cy`, applied while the principal point is free.
+    pub principal_point_sigma: Option<f64>,
+    /// Estimate distortion when there are enough correspondences.
+    pub estimate_distortion: bool,
+    /// Estimate the focal length when there are enough correspondences.
+    pub estimate_focal: bool,
+    /// Estimate the principal point when there are enough correspondences.
+    pub estimate_principal_point: bool,
+    /// No focal length was supplied: `intr.focal_px` is only a guess, so
+    /// minimal samples use the focal scan (pose + focal) instead of P3P.
+    pub unknown_focal: bool,
+}
+
+impl<'a> Problem<'a> {
+    /// Default problem: pose + distortion estimated, focal length fixed.
+    pub(crate) fn new(
+        corrs: &'a [EnuCorrespondence],
+        intr: &CameraIntrinsics,
+        priors: PosePriors,
+        dist_prior: [f64; 4],
+    ) -> Self {
+        Problem {
+            corrs,
+            intr: *intr,
+            priors,
+            dist_prior,
+            focal_prior: None,
+            principal_point_sigma: None,
+            estimate_distortion: true,
+            estimate_focal: false,
+            estimate_principal_point: false,
+            unknown_focal: false,
+        }
+    }
+
+    /// Same priors and switches applied to a different set of
+    /// correspondences (e.g. a RANSAC consensus set or reweighted copy).
+    pub(crate) fn with_corrs<'b>(&self, corrs: &'b [EnuCorrespondence]) -> Problem<'b> {
+        Problem {
+            corrs,
+            intr: self.intr,
+            priors: self.priors,
+            dist_prior: self.dist_prior,
+            focal_prior: self.focal_prior,
+            principal_point_sigma: self.principal_point_sigma,
+            estimate_distortion: self.estimate_distortion,
+            estimate_focal: self.estimate_focal,
+            estimate_principal_point: self.estimate_principal_point,
+            unknown_focal: self.unknown_focal,
+        }
+    }
+
+    /// Which parameters are estimated for the current correspondences.
+    ///
+    /// The pose is always free.  With fewer than five correspondences
+    /// distortion cannot be separated from the camera pose, so it is frozen
+    /// at `dist_prior` and the model is a pure pinhole (or a pinhole with
+    /// the user-supplied priors as fixed offsets) – five correspondences
+    /// give 10 equations for the 10 pose + distortion unknowns.  The focal
+    /// length needs at least four (8 equations for 7 unknowns), the
+    /// principal point at least five.
+    pub(crate) fn free(&self) -> [bool; NUM_PARAMS] {
+        let n = self.corrs.len();
+        let mut free = [false; NUM_PARAMS];
+        for (i, f) in free.iter_mut().enumerate() {
+            *f = match i {
+                0..=5 => true,
+                6..=9 => self.estimate_distortion && n >= 5,
+                FOCAL => self.estimate_focal && n >= 4,
+                _ => self.estimate_principal_point && n >= 5,
+            };
+        }
+        free
+    }
 }
 
 /// Result returned by the Levenberg-Marquardt optimiser.
@@ -29,149 +232,262 @@ pub(crate) struct OptResult {
     pub params: [f64; NUM_PARAMS],
     pub cost: f64,
     pub iterations: usize,
-    pub converged: bool,
-    /// Flattened 6×6 J^T·J (row-major) at the final iterate – used for
-    /// covariance estimation.
+    /// Why LM stopped.
+    pub termination: TerminationReason,
+    /// Flattened NUM_PARAMS×NUM_PARAMS J^T·J (row-major) at the final
+    /// iterate – used for covariance estimation.  Frozen parameters carry a
+    /// unit diagonal.
     pub jtj: [f64; NUM_PARAMS * NUM_PARAMS],
+    /// Which parameters were estimated (`Problem::free`).
+    pub free: [bool; NUM_PARAMS],
 }
 
-// ── Residuals ───────────────────────────────────────────────────────────────
+// ── Residuals and Jacobian ──────────────────────────────────────────────────
 
 /// Compute the full residual vector (reprojection + regularisation terms).
 ///
 /// Layout: `[du₁/σ₁, dv₁/σ₁, …, duₙ/σₙ, dvₙ/σₙ, <prior terms>]`
 ///
-/// `base_intr` carries the fixed focal length and principal point.
-/// Distortion coefficients (k1, k2, p1, p2) are taken from `params[6..10]`
-/// and regularised toward `dist_prior`.
-fn residuals(
+/// Intrinsics come from `params` (frozen entries simply never move).  Free
+/// distortion coefficients are regularised toward `dist_prior`, a free
+/// focal length toward `focal_prior` and a free principal point toward
+/// the initial one (`principal_point_sigma`).
+fn residuals(params: &[f64; NUM_PARAMS], problem: &Problem) -> Vec<f64> {
+    evaluate(params, problem, false).0
+}
+
+/// Sum of squared residuals – the quantity LM minimises.
+pub(crate) fn cost(params: &[f64; NUM_PARAMS], problem: &Problem) -> f64 {
+    residuals(params, problem).iter().map(|r| r * r).sum()
+}
+
+/// Residual vector together with its closed-form Jacobian (one row per
+/// residual, see `residuals` for the layout).  Columns of frozen
+/// parameters are zero.
+pub(crate) fn residuals_and_jacobian(
     params: &[f64; NUM_PARAMS],
-    corrs: &[EnuCorrespondence],
-    base_intr: &CameraIntrinsics,
-    alt_prior_enu: Option<f64>,
-    dist_prior: [f64; 4],
-    n_points: usize,
-) -> Vec<f64> {
+    problem: &Problem,
+) -> (Vec<f64>, Vec<[f64; NUM_PARAMS]>) {
+    evaluate(params, problem, true)
+}
+
+/// Shared implementation of `residuals` and `residuals_and_jacobian`.
+///
+/// Every residual is pushed together with a closure that fills its
+/// Jacobian row, so the two can never disagree on the layout; the closures
+/// only run when `with_jacobian` is set.
+fn evaluate(
+    params: &[f64; NUM_PARAMS],
+    problem: &Problem,
+    with_jacobian: bool,
+) -> (Vec<f64>, Vec<[f64; NUM_PARAMS]>) {
+    let free = problem.free();
     let cam = [params[0], params[1], params[2]];
-    let rot = rotation_enu_to_cam(params[3], params[4], params[5]);
-
-    // When there are too few correspondences to reliably separate distortion
-    // from camera pose, freeze the distortion at the prior values so that the
-    // effective model is a pure pinhole (or a pinhole with the user-supplied
-    // priors as fixed offsets).  Five correspondences gives 10 equations for
-    // 10 unknowns – the minimum for a well-determined system.
-    let enough_for_distortion = n_points >= 5;
-    // Build full intrinsics: fixed focal/principal point + optimised/frozen distortion.
-    let intr = CameraIntrinsics {
-        focal_px: base_intr.focal_px,
-        cx: base_intr.cx,
-        cy: base_intr.cy,
-        k1: if enough_for_distortion {
-            params[6]
-        } else {
-            dist_prior[0]
-        },
-        k2: if enough_for_distortion {
-            params[7]
-        } else {
-            dist_prior[1]
-        },
-        p1: if enough_for_distortion {
-            params[8]
-        } else {
-            dist_prior[2]
-        },
-        p2: if enough_for_distortion {
-            params[9]
-        } else {
-            dist_prior[3]
-        },
+    let rot = rotation_from_params(params);
+    let intr = intrinsics_from_params(params);
+    let n_points = problem.corrs.len();
+
+    let mut res = Vec::with_capacity(n_points * 2 + 12);
+    let mut jac = Vec::with_capacity(if with_jacobian { n_points * 2 + 12 } else { 0 });
+    let mut push = |r: f64, grad: &dyn Fn(&mut [f64; NUM_PARAMS])| {
+        res.push(r);
+        if with_jacobian {
+            let mut row = [0.0; NUM_PARAMS];
+            grad(&mut row);
+            jac.push(row);
+        }
     };
 
-    let mut res = Vec::with_capacity(corrs.len() * 2 + 8);
-    for c in corrs {
-        match project_point(c.enu, cam, &rot, &intr) {
-            Some((u, v)) => {
-                res.push((u - c.pixel[0]) / c.sigma);
-                res.push((v - c.pixel[1]) / c.sigma);
+    for c in problem.corrs {
+        let dp = [c.enu[0] - cam[0], c.enu[1] - cam[1], c.enu[2] - cam[2]];
+        let p = mat3_vec(&rot, &dp);
+        // Local rotation δ: p' = exp([δ]×) · p, so ∂p/∂δₐ = eₐ × p
+        let dp_dangle = |a: usize| {
+            let mut e = [0.0; 3];
+            e[a] = 1.0;
+            cross(&e, &p)
+        };
+
+        match project_cam_point_jacobian(p, &intr) {
+            Some(pj) => {
+                let inv_s = 1.0 / c.sigma;
+                for (axis, value) in [pj.u, pj.v].into_iter().enumerate() {
+                    push((value - c.pixel[axis]) * inv_s, &|row| {
+                        let d = &pj.d_cam_point[axis];
+                        // Camera position: ∂p/∂cam = −R
+                        for k in 0..3 {
+                            row[k] =
+                                -(d[0] * rot[0][k] + d[1] * rot[1][k] + d[2] * rot[2][k]) * inv_s;
+                        }
+                        for a in 0..3 {
+                            let dpa = dp_dangle(a);
+                            row[3 + a] = (d[0] * dpa[0] + d[1] * dpa[1] + d[2] * dpa[2]) * inv_s;
+                        }
+                        for k in 0..4 {
+                            row[6 + k] = pj.d_distortion[axis][k] * inv_s;
+                        }
+                        row[FOCAL] = pj.d_focal[axis] * inv_s;
+                        row[CX + axis] = inv_s;
+                    });
+                }
             }
             None => {
                 // Behind camera → large smooth penalty that grows with negative Z
-                let dp = [c.enu[0] - cam[0], c.enu[1] - cam[1], c.enu[2] - cam[2]];
-                let rm = rotation_enu_to_cam(params[3], params[4], params[5]);
-                let z_cam = rm[2][0] * dp[0] + rm[2][1] * dp[1] + rm[2][2] * dp[2];
+                let z_cam = p[2];
                 let penalty = 1000.0 + (-z_cam).max(0.0) * 10.0;
-                res.push(penalty);
-                res.push(penalty);
+                for _ in 0..2 {
+                    push(penalty, &|row| {
+                        if z_cam < 0.0 {
+                            // ∂penalty/∂z = −10, ∂z/∂cam = −R[2]
+                            for k in 0..3 {
+                                row[k] = 10.0 * rot[2][k];
+                            }
+                            for a in 0..3 {
+                                row[3 + a] = -10.0 * dp_dangle(a)[2];
+                            }
+                        }
+                    });
+                }
             }
         }
     }
 
-    // Altitude prior (soft constraint)
-    if let Some(ap) = alt_prior_enu {
-        res.push((params[2] - ap) / 10.0); // σ_alt = 10 m
+    // Attitude priors act on the Euler angles of R; their gradient with
+    // respect to the local rotation comes from `euler_local_jacobian`.
+    let (yaw, pitch, roll) = euler_from_rotation(&rot);
+    let euler_jac = euler_local_jacobian(&rot);
+    // Jacobian row of `scale × angle` (0 = yaw, 1 = pitch, 2 = roll).
+    let angle_grad = |angle: usize, scale: f64| {
+        let mut g = [0.0; NUM_PARAMS];
+        for k in 0..3 {
+            g[3 + k] = euler_jac[angle][k] * scale;
+        }
+        g
+    };
+    // Jacobian row of `params[i] / sigma`.
+    let param_grad = |i: usize, sigma: f64| {
+        let mut g = [0.0; NUM_PARAMS];
+        g[i] = 1.0 / sigma;
+        g
+    };
+
+    // A prior residual with its (constant) Jacobian row.
+    let mut push_prior = |r: f64, grad: [f64; NUM_PARAMS]| {
+        push(r, &|row| *row = grad);
+    };
+
+    // Pose priors (soft constraints)
+    let priors = &problem.priors;
+    if let Some(([e, n], sigma)) = priors.position {
+        push_prior((params[0] - e) / sigma, param_grad(0, sigma));
+        push_prior((params[1] - n) / sigma, param_grad(1, sigma));
+    }
+    if let Some((mean, sigma)) = priors.alt {
+        push_prior((params[2] - mean) / sigma, param_grad(2, sigma));
+    }
+    if let Some((mean, sigma)) = priors.yaw {
+        let d = ((yaw - mean) % 360.0 + 540.0) % 360.0 - 180.0;
+        push_prior(d / sigma, angle_grad(0, 1.0 / sigma));
     }
 
-    // Roll regularisation (prefer level horizon)
-    let roll_sigma = if n_points < 3 { 5.0 } else { 45.0 };
-    res.push(params[5] / roll_sigma);
+    // Roll: the user prior, else a regulariser preferring a level horizon.
+    // The regulariser is weighted by cos(pitch): towards nadir / zenith
+    // roll and yaw become one rotation and a level horizon means nothing.
+    match priors.roll {
+        Some((mean, sigma)) => push_prior((roll - mean) / sigma, angle_grad(2, 1.0 / sigma)),
+        None => {
+            let roll_sigma = if n_points < 3 { 5.0 } else { 45.0 };
+            let (sin_p, cos_p) = pitch.to_radians().sin_cos();
+            let mut grad = angle_grad(2, cos_p / roll_sigma);
+            let d_pitch = angle_grad(1, -roll * sin_p * 1f64.to_radians() / roll_sigma);
+            for (g, d) in grad.iter_mut().zip(d_pitch) {
+                *g += d;
+            }
+            push_prior(roll * cos_p / roll_sigma, grad);
+        }
+    }
 
-    // Pitch regularisation for under-determined cases
-    if n_points < 3 {
-        res.push(params[4] / 30.0);
+    // Pitch: the user prior, else a regulariser for under-determined cases
+    match priors.pitch {
+        Some((mean, sigma)) => push_prior((pitch - mean) / sigma, angle_grad(1, 1.0 / sigma)),
+        None if n_points < 3 => push_prior(pitch / 30.0, angle_grad(1, 1.0 / 30.0)),
+        None => {}
     }
 
-    // Distortion regularisation (or identity constraints when frozen).
+    // Distortion regularisation.
     //
-    // When distortion is estimated (n_points ≥ 5), add Gaussian soft
-    // constraints toward the prior.
-    //
-    // When distortion is frozen (n_points < 5) we do NOT add distortion
-    // residuals here at all.  The Levenberg-Marquardt loop adds a unit
-    // identity block directly to the J^T J diagonal for those parameters,
-    // keeping the normal-equations matrix non-singular while leaving the
-    // pose sub-block bit-identical to the old 6-DOF solver.  This avoids
-    // any floating-point accumulation differences that could shift the
-    // optimizer into a different basin of attraction.
-    if enough_for_distortion {
+    // When distortion is estimated, add Gaussian soft constraints toward
+    // the prior.  When it is frozen no residuals are added at all: the LM
+    // loop puts a unit identity block on the J^T J diagonal for frozen
+    // parameters instead, keeping the normal equations non-singular while
+    // leaving the pose sub-block untouched.
+    if free[6] {
         // Σ chosen to allow realistic smartphone distortion.
         //   k1: 0.5  – strong barrel common on wide-angle lenses
         //   k2: 0.25 – second-order term, smaller in practice
         //   p1: 0.05 – tangential distortion is small on most lenses
         //   p2: 0.05
-        res.push((params[6] - dist_prior[0]) / 0.5);
-        res.push((params[7] - dist_prior[1]) / 0.25);
-        res.push((params[8] - dist_prior[2]) / 0.05);
-        res.push((params[9] - dist_prior[3]) / 0.05);
+        let dp = problem.dist_prior;
+        for (k, sigma) in [0.5, 0.25, 0.05, 0.05].into_iter().enumerate() {
+            push_prior((params[6 + k] - dp[k]) / sigma, param_grad(6 + k, sigma));
+        }
     }
-    // (frozen case handled by the LM caller)
 
-    res
-}
+    // Focal-length prior (soft constraint, only when the focal is free)
+    if let (true, Some((mean, sigma))) = (free[FOCAL], problem.focal_prior) {
+        push_prior((params[FOCAL] - mean) / sigma, param_grad(FOCAL, sigma));
+    }
 
-// ── Numerical Jacobian ──────────────────────────────────────────────────────
+    // Principal-point prior (soft constraint toward the initial centre)
+    if let (true, Some(sigma)) = (free[CX], problem.principal_point_sigma) {
+        push_prior(
+            (params[CX] - problem.intr.cx) / sigma,
+            param_grad(CX, sigma),
+        );
+        push_prior(
+            (params[CX + 1] - problem.intr.cy) / sigma,
+            param_grad(CX + 1, sigma),
+        );
+    }
 
-fn jacobian(
+    for row in &mut jac {
+        for (j, v) in row.iter_mut().enumerate() {
+            if !free[j] {
+                *v = 0.0;
+            }
+        }
+    }
+    (res, jac)
+}
+
+/// Central-difference Jacobian of `residuals`, kept as a cross-check for
+/// `residuals_and_jacobian`; columns of frozen parameters are left at zero.
+///
+/// Steps go through `apply_step`, so the rotation columns are derivatives
+/// with respect to the local rotation, like the closed-form ones.
+#[cfg(test)]
+pub(crate) fn numerical_jacobian(
     params: &[f64; NUM_PARAMS],
-    corrs: &[EnuCorrespondence],
-    base_intr: &CameraIntrinsics,
-    alt_prior: Option<f64>,
-    dist_prior: [f64; 4],
-    n_points: usize,
+    problem: &Problem,
 ) -> Vec<[f64; NUM_PARAMS]> {
-    // Step sizes: position in metres, angles in degrees, distortion dimensionless
-    const H: [f64; NUM_PARAMS] = [1e-3, 1e-3, 1e-3, 1e-4, 1e-4, 1e-4, 1e-6, 1e-7, 1e-7, 1e-7];
+    // Step sizes: position in metres, rotation in radians, distortion
+    // dimensionless, focal length and principal point in pixels
+    const H: [f64; NUM_PARAMS] = [
+        1e-3, 1e-3, 1e-3, 2e-6, 2e-6, 2e-6, 1e-6, 1e-7, 1e-7, 1e-7, 1e-3, 1e-3, 1e-3,
+    ];
 
-    let n_res = residuals(params, corrs, base_intr, alt_prior, dist_prior, n_points).len();
+    let free = problem.free();
+    let n_res = residuals(params, problem).len();
     let mut jac = vec![[0.0; NUM_PARAMS]; n_res];
 
-    for j in 0..NUM_PARAMS {
-        let mut pp = *params;
-        let mut pm = *params;
-        pp[j] += H[j];
-        pm[j] -= H[j];
-        let rp = residuals(&pp, corrs, base_intr, alt_prior, dist_prior, n_points);
-        let rm = residuals(&pm, corrs, base_intr, alt_prior, dist_prior, n_points);
+    for j in (0..NUM_PARAMS).filter(|&j| free[j]) {
+        let mut step = [0.0; NUM_PARAMS];
+        step[j] = H[j];
+        let pp = apply_step(params, &step, &free);
+        step[j] = -H[j];
+        let pm = apply_step(params, &step, &free);
+        let rp = residuals(&pp, problem);
+        let rm = residuals(&pm, problem);
         let inv2h = 1.0 / (2.0 * H[j]);
         for i in 0..n_res {
             jac[i][j] = (rp[i] - rm[i]) * inv2h;
@@ -182,30 +498,111 @@ fn jacobian(
 
 // ── Levenberg-Marquardt ─────────────────────────────────────────────────────
 
+/// Gradient tolerance: stop once `‖Jᵀr‖∞` has shrunk below this fraction
+/// of its value at the starting point.
+const GRADIENT_TOLERANCE: f64 = 1e-10;
+/// Relative cost-change tolerance: stop once an accepted step lowers the
+/// cost by less than this fraction.
+const COST_TOLERANCE: f64 = 1e-12;
+/// Step-size tolerance: stop once an accepted step has
+/// `‖δ‖ ≤ tol·(‖x‖ + tol)`.
+const STEP_TOLERANCE: f64 = 1e-10;
+/// Damping beyond which no further decrease is expected.
+const MAX_LAMBDA: f64 = 1e16;
+
+/// Damping schedule and stopping tests of Levenberg-Marquardt, shared by
+/// the pose, bundle and triangulation solvers so they all stop for the
+/// same reasons (see `TerminationReason`).
+///
+/// Each outer iteration first asks `gradient_converged`; the inner loop
+/// then tries steps while `can_damp`, and calls `accept` on a cost decrease
+/// or `reject` otherwise.  Only accepted steps are tested for convergence:
+/// a rejected step, however small, just raises λ.
+pub(crate) struct LmControl {
+    lambda: f64,
+    initial_gradient: Option<f64>,
+}
+
+impl LmControl {
+    /// Controller starting from damping `lambda`.
+    pub(crate) fn new(lambda: f64) -> Self {
+        LmControl {
+            lambda,
+            initial_gradient: None,
+        }
+    }
+
+    /// Current damping λ.
+    pub(crate) fn lambda(&self) -> f64 {
+        self.lambda
+    }
+
+    /// Whether `gradient` (`‖Jᵀr‖∞`) has shrunk below `GRADIENT_TOLERANCE`
+    /// of the first gradient seen, or `cost` is an exact fit.
+    pub(crate) fn gradient_converged(&mut self, gradient: f64, cost: f64) -> bool {
+        let g0 = *self.initial_gradient.get_or_insert(gradient);
+        gradient <= GRADIENT_TOLERANCE * g0 || cost < 1e-20
+    }
+
+    /// Whether λ can still be raised; once it cannot, the solve stops with
+    /// `LambdaOverflow`.
+    pub(crate) fn can_damp(&self) -> bool {
+        self.lambda < MAX_LAMBDA
+    }
+
+    /// Record a step of norm `step_norm`, taken from parameters of norm
+    /// `x_norm`, that lowered the cost from `cost` to `new_cost`: relaxes λ
+    /// and returns `ConvergedCost` when the decrease is negligible, or
+    /// `ConvergedStep` when the step was too small to change the fit any
+    /// more.
+    pub(crate) fn accept(
+        &mut self,
+        cost: f64,
+        new_cost: f64,
+        step_norm: f64,
+        x_norm: f64,
+    ) -> Option<TerminationReason> {
+        self.lambda = (self.lambda * 0.3).max(1e-12);
+        if (cost - new_cost) / cost <= COST_TOLERANCE {
+            Some(TerminationReason::ConvergedCost)
+        } else if step_norm <= STEP_TOLERANCE * (x_norm + STEP_TOLERANCE) {
+            Some(TerminationReason::ConvergedStep)
+        } else {
+            None
+        }
+    }
+
+    /// Record a failed step: raises λ.
+    pub(crate) fn reject(&mut self) {
+        self.lambda *= 3.0;
+    }
+}
+
+/// Minimise the squared residuals of `problem` starting from `initial`.
+///
+/// Runs at most `max_iter` outer iterations; each raises the damping until
+/// the cost decreases.  Stops early when the gradient, the relative cost
+/// change or the step size falls below its tolerance (see
+/// `TerminationReason`).  Frozen parameters never move.
+#[allow(clippy::needless_range_loop)]
 pub(crate) fn levenberg_marquardt(
     initial: [f64; NUM_PARAMS],
-    corrs: &[EnuCorrespondence],
-    base_intr: &CameraIntrinsics,
-    alt_prior: Option<f64>,
-    dist_prior: [f64; 4],
-    n_points: usize,
+    problem: &Problem,
     max_iter: usize,
 ) -> OptResult {
+    let free = problem.free();
     let mut params = initial;
-    let mut lambda: f64 = 1.0;
+    let mut lm = LmControl::new(1.0);
 
-    let r0 = residuals(&params, corrs, base_intr, alt_prior, dist_prior, n_points);
+    let r0 = residuals(&params, problem);
     let mut cost: f64 = r0.iter().map(|v| v * v).sum();
 
-    let mut converged = false;
+    let mut termination = TerminationReason::MaxIterations;
     let mut iterations: usize = 0;
     let mut last_jtj = [[0.0f64; NUM_PARAMS]; NUM_PARAMS];
 
-    for _iter in 0..max_iter {
-        iterations = _iter + 1;
-
-        let r = residuals(&params, corrs, base_intr, alt_prior, dist_prior, n_points);
-        let jac = jacobian(&params, corrs, base_intr, alt_prior, dist_prior, n_points);
+    for iter in 0..max_iter {
+        let (r, jac) = residuals_and_jacobian(&params, problem);
         let n_res = r.len();
 
         // Normal equations: J^T J and J^T r
@@ -226,23 +623,30 @@ pub(crate) fn levenberg_marquardt(
             }
         }
 
-        // When distortion is frozen, add a unit identity block to the
-        // distortion sub-diagonal so the normal-equations matrix remains
-        // non-singular.  The corresponding jtr entries are already 0.
-        let enough_for_distortion_norm = n_points >= 5;
-        if !enough_for_distortion_norm {
-            for k in 6..NUM_PARAMS {
+        // Frozen parameters have all-zero Jacobian columns: a unit diagonal
+        // keeps the normal-equations matrix non-singular, and with
+        // jtr = 0 their step is exactly 0.
+        for k in 0..NUM_PARAMS {
+            if !free[k] {
                 jtj[k][k] += 1.0;
             }
         }
         last_jtj = jtj;
 
-        // Try damped steps (inner loop adjusts λ)
-        let mut step_accepted = false;
-        for _ in 0..30 {
+        // Gradient check (also catches an exact fit)
+        let gradient = jtr.iter().fold(0.0f64, |m, g| m.max(g.abs()));
+        if lm.gradient_converged(gradient, cost) {
+            termination = TerminationReason::ConvergedGradient;
+            break;
+        }
+        iterations = iter + 1;
+
+        // Try damped steps (inner loop raises λ until the cost decreases)
+        let mut stop = Some(TerminationReason::LambdaOverflow);
+        while lm.can_damp() {
             let mut a = jtj;
             for i in 0..NUM_PARAMS {
-                a[i][i] += lambda * a[i][i].max(1e-12);
+                a[i][i] += lm.lambda() * a[i][i].max(1e-12);
             }
             let mut b = [0.0; NUM_PARAMS];
             for i in 0..NUM_PARAMS {
@@ -250,34 +654,25 @@ pub(crate) fn levenberg_marquardt(
             }
 
             if let Some(delta) = solve_nxn(&mut a, &mut b) {
-                let mut np = params;
-                for i in 0..NUM_PARAMS {
-                    np[i] += delta[i];
-                }
-                // Normalise yaw to [0, 360)
-                np[3] = ((np[3] % 360.0) + 360.0) % 360.0;
+                let np = apply_step(&params, &delta, &free);
 
-                let nr = residuals(&np, corrs, base_intr, alt_prior, dist_prior, n_points);
+                let nr = residuals(&np, problem);
                 let nc: f64 = nr.iter().map(|v| v * v).sum();
 
-                if nc < cost {
+                if nc < cost && np[FOCAL] > 0.0 {
+                    let step_norm = delta.iter().map(|d| d * d).sum::<f64>().sqrt();
+                    let x_norm = params.iter().map(|x| x * x).sum::<f64>().sqrt();
+                    stop = lm.accept(cost, nc, step_norm, x_norm);
                     params = np;
                     cost = nc;
-                    lambda = (lambda * 0.3).max(1e-12);
-                    step_accepted = true;
                     break;
                 }
             }
-            lambda = (lambda * 3.0).min(1e16);
+            lm.reject();
         }
 
-        if !step_accepted {
-            break;
-        }
-
-        // Convergence checks
-        if cost < 1e-20 {
-            converged = true;
+        if let Some(reason) = stop {
+            termination = reason;
             break;
         }
     }
@@ -294,22 +689,208 @@ pub(crate) fn levenberg_marquardt(
         params,
         cost,
         iterations,
-        converged,
+        termination,
         jtj: jtj_flat,
+        free,
+    }
+}
+
+// ── Robust refinement (IRLS) ────────────────────────────────────────────────
+
+/// A robust kernel together with its threshold, expressed in units of the
+/// per-point σ (so `scale = 2` means "residuals beyond 2σ are suspicious").
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RobustKernel {
+    pub loss: RobustLoss,
+    pub scale: f64,
+}
+
+impl RobustKernel {
+    /// Kernel with the conventional 95 %-efficiency threshold for `loss`
+    /// unless `scale` overrides it.
+    pub(crate) fn new(loss: RobustLoss, scale: Option<f64>) -> Self {
+        let default_scale = match loss {
+            RobustLoss::None => 1.0,
+            RobustLoss::Huber => 1.345,
+            RobustLoss::Cauchy => 2.385,
+            RobustLoss::Tukey => 4.685,
+        };
+        RobustKernel {
+            loss,
+            scale: scale.filter(|c| *c > 0.0).unwrap_or(default_scale),
+        }
+    }
+
+    /// IRLS weight `w(s) = ρ'(s) / s` for a σ-normalised residual norm `s`.
+    pub(crate) fn weight(&self, s: f64) -> f64 {
+        let c = self.scale;
+        match self.loss {
+            RobustLoss::None => 1.0,
+            RobustLoss::Huber => {
+                if s <= c {
+                    1.0
+                } else {
+                    c / s
+                }
+            }
+            RobustLoss::Cauchy => 1.0 / (1.0 + (s / c).powi(2)),
+            RobustLoss::Tukey => {
+                if s < c {
+                    (1.0 - (s / c).powi(2)).powi(2)
+                } else {
+                    0.0
+                }
+            }
+        }
     }
 }
 
+/// Iteratively-reweighted least squares around `levenberg_marquardt`.
+///
+/// Each round computes a weight per correspondence from its σ-normalised
+/// reprojection error at the current estimate, folds it into the
+/// correspondence σ (`σ' = σ / √w`, so the squared residual is scaled by
+/// `w`) and runs LM with those weights held fixed.  Stops once the weights
+/// settle (max change < 1e-3) or after 10 rounds.
+///
+/// Redescending kernels (Tukey) can zero out points; a round that would
+/// leave fewer than 3 points with non-zero weight is discarded.
+///
+/// Returns the final LM result and the final weight of every correspondence
+/// (in input order).
+pub(crate) fn robust_refine(
+    initial: [f64; NUM_PARAMS],
+    problem: &Problem,
+    kernel: &RobustKernel,
+    max_iter: usize,
+) -> (OptResult, Vec<f64>) {
+    let corrs = problem.corrs;
+    let mut weights = vec![1.0; corrs.len()];
+    let mut weighted: Vec<EnuCorrespondence> = corrs.to_vec();
+    let mut result = levenberg_marquardt(initial, problem, max_iter);
+
+    if kernel.loss == RobustLoss::None {
+        return (result, weights);
+    }
+
+    for _ in 0..10 {
+        let (errors, _) = reprojection_errors(&result.params, corrs);
+        let new_weights: Vec<f64> = errors
+            .iter()
+            .zip(corrs)
+            .map(|(e, c)| kernel.weight(e / c.sigma))
+            .collect();
+        if new_weights.iter().filter(|w| **w > 0.0).count() < 3 {
+            break;
+        }
+        let change = new_weights
+            .iter()
+            .zip(&weights)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        weights = new_weights;
+
+        for (w, (dst, src)) in weights.iter().zip(weighted.iter_mut().zip(corrs)) {
+            dst.sigma = src.sigma / w.max(1e-12).sqrt();
+        }
+        result = levenberg_marquardt(result.params, &problem.with_corrs(&weighted), max_iter);
+        if change < 1e-3 {
+            break;
+        }
+    }
+    (result, weights)
+}
+
+// ── World-point uncertainty ─────────────────────────────────────────────────
+
+/// Copy of `corrs` whose pixel sigma also accounts for the world point's
+/// position uncertainty, projected through the camera in `params`:
+///
+/// `σ_eff² = σ_px² + (f · σ_world / depth)²`
+///
+/// This is the effective-variance linearisation of an errors-in-variables
+/// model.  The result is meant to be held fixed during an LM run and
+/// recomputed between runs: letting σ_eff vary inside the cost would
+/// reward moving the camera toward uncertain points.  Points behind the
+/// camera keep their pixel sigma.
+pub(crate) fn propagate_world_sigma(
+    params: &[f64; NUM_PARAMS],
+    corrs: &[EnuCorrespondence],
+) -> Vec<EnuCorrespondence> {
+    let rot = rotation_from_params(params);
+    corrs
+        .iter()
+        .map(|c| {
+            let mut out = c.clone();
+            if c.world_sigma > 0.0 {
+                let dp = [
+                    c.enu[0] - params[0],
+                    c.enu[1] - params[1],
+                    c.enu[2] - params[2],
+                ];
+                let depth = rot[2][0] * dp[0] + rot[2][1] * dp[1] + rot[2][2] * dp[2];
+                if depth > 1e-6 {
+                    let px = params[FOCAL] * c.world_sigma / depth;
+                    out.sigma = c.sigma.hypot(px);
+                }
+            }
+            out
+        })
+        .collect()
+}
+
+// ── Reprojection-error diagnostics ──────────────────────────────────────────
+
+/// Camera intrinsics (focal length, principal point, distortion) stored in
+/// a parameter vector.
+pub(crate) fn intrinsics_from_params(params: &[f64; NUM_PARAMS]) -> CameraIntrinsics {
+    CameraIntrinsics {
+        focal_px: params[FOCAL],
+        cx: params[CX],
+        cy: params[CX + 1],
+        k1: params[6],
+        k2: params[7],
+        p1: params[8],
+        p2: params[9],
+    }
+}
+
+/// Per-point reprojection error (px, Euclidean) and their RMSE for the
+/// camera described by `params`.
+pub(crate) fn reprojection_errors(
+    params: &[f64; NUM_PARAMS],
+    corrs: &[EnuCorrespondence],
+) -> (Vec<f64>, f64) {
+    let cam = [params[0], params[1], params[2]];
+    let rot = rotation_from_params(params);
+    let intr = intrinsics_from_params(params);
+    let mut res = Vec::with_capacity(corrs.len());
+    let mut ss = 0.0;
+    for c in corrs {
+        let r = match project_point(c.enu, cam, &rot, &intr) {
+            Some((u, v)) => (u - c.pixel[0]).hypot(v - c.pixel[1]),
+            None => 1000.0,
+        };
+        ss += r * r;
+        res.push(r);
+    }
+    let rmse = (ss / corrs.len() as f64).sqrt();
+    (res, rmse)
+}
+
 // ── Initialisation heuristic ────────────────────────────────────────────────
 
-/// Produce a starting guess for the 10-DOF camera parameter vector
-/// `[e, n, u, yaw°, pitch°, roll°, k1, k2, p1, p2]`.
+/// Produce a starting guess for the camera parameter vector
+/// `[e, n, u, ω, k1, k2, p1, p2, f, cx, cy]`.
 ///
+/// Pose priors, when given, replace the corresponding heuristic.
 /// `dist_prior` seeds the distortion coefficients; pass `[0.0; 4]` when no
-/// prior is available.
+/// prior is available.  The focal length and principal point are seeded
+/// from `intr`.
 pub(crate) fn initialize_pose(
     corrs: &[EnuCorrespondence],
     intr: &CameraIntrinsics,
-    alt_prior_enu: Option<f64>,
+    priors: &PosePriors,
     dist_prior: [f64; 4],
 ) -> [f64; NUM_PARAMS] {
     let n = corrs.len() as f64;
@@ -334,7 +915,9 @@ pub(crate) fn initialize_pose(
         var_e += de * de;
         var_n += dn * dn;
     }
-    let yaw_deg = if var_e > 1e-10 || var_n > 1e-10 {
+    let yaw_deg = if let Some((mean, _)) = priors.yaw {
+        ((mean % 360.0) + 360.0) % 360.0
+    } else if var_e > 1e-10 || var_n > 1e-10 {
         let r = (-cov_un / var_n.max(1e-10)).atan2(cov_ue / var_e.max(1e-10));
         ((r.to_degrees() % 360.0) + 360.0) % 360.0
     } else {
@@ -345,27 +928,30 @@ pub(crate) fn initialize_pose(
     let dist = estimate_scene_distance(corrs, intr);
 
     let yaw_rad = yaw_deg.to_radians();
-    let cam_e = me - dist * yaw_rad.sin();
-    let cam_n = mn - dist * yaw_rad.cos();
+    let [cam_e, cam_n] = match priors.position {
+        Some((en, _)) => en,
+        None => [me - dist * yaw_rad.sin(), mn - dist * yaw_rad.cos()],
+    };
 
     // ── Altitude ────────────────────────────────────────────────────────
-    let cam_u = alt_prior_enu.unwrap_or_else(|| (min_u - 10.0).min(mu - 5.0));
+    let cam_u = match priors.alt {
+        Some((mean, _)) => mean,
+        None => (min_u - 10.0).min(mu - 5.0),
+    };
 
     // ── Pitch from vertical pixel offset ────────────────────────────────
-    let pitch_deg = -((mpv - intr.cy) / intr.focal_px).atan().to_degrees();
-
-    [
-        cam_e,
-        cam_n,
-        cam_u,
-        yaw_deg,
-        pitch_deg,
-        0.0,
-        dist_prior[0],
-        dist_prior[1],
-        dist_prior[2],
-        dist_prior[3],
-    ]
+    let pitch_deg = match priors.pitch {
+        Some((mean, _)) => mean,
+        None => -((mpv - intr.cy) / intr.focal_px).atan().to_degrees(),
+    };
+    let roll_deg = priors.roll.map_or(0.0, |(mean, _)| mean);
+
+    params_from_pose(
+        [cam_e, cam_n, cam_u],
+        (yaw_deg, pitch_deg, roll_deg),
+        dist_prior,
+        intr,
+    )
 }
 
 /// Median of pair-wise  `geo_distance_3d × focal / pixel_distance`.
@@ -396,6 +982,7 @@ pub(crate) fn estimate_scene_distance(corrs: &[EnuCorrespondence], intr: &Camera
 
 /// Solve A·x = b (NUM_PARAMS × NUM_PARAMS) with Gaussian elimination +
 /// partial pivoting.  Returns `None` when the matrix is singular.
+#[allow(clippy::needless_range_loop)]
 fn solve_nxn(
     a: &mut [[f64; NUM_PARAMS]; NUM_PARAMS],
     b: &mut [f64; NUM_PARAMS],
@@ -0,0 +1,670 @@
+use crate::linalg::{
+    least_squares, polynomial_real_roots, principal_axes, solve_linear, symmetric_eigen,
+};
+use crate::optimizer::{set_rotation, EnuCorrespondence, Problem, CX, FOCAL, NUM_PARAMS};
+use crate::projection::{mat3_vec, project_point, undistort_pixel, CameraIntrinsics, Mat3};
+use crate::rng::{SplitMix64, DEFAULT_SEED};
+
+/// A camera pose recovered by a closed-form solver: `p_cam = rot · (p − cam)`
+/// with `cam` the camera centre in ENU metres.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PoseCandidate {
+    pub rot: Mat3,
+    pub cam: [f64; 3],
+}
+
+// ── Bearings ────────────────────────────────────────────────────────────────
+
+/// Unit viewing ray (camera frame) through pixel `(u, v)`, with the lens
+/// distortion of `intr` removed.  `None` when undistortion fails.
+pub(crate) fn bearing(pixel: [f64; 2], intr: &CameraIntrinsics) -> Option<[f64; 3]> {
+    let (xn, yn) = undistort_pixel(pixel[0], pixel[1], intr)?;
+    let n = (xn * xn + yn * yn + 1.0).sqrt();
+    Some([xn / n, yn / n, 1.0 / n])
+}
+
+// ── P3P ─────────────────────────────────────────────────────────────────────
+
+/// Closed-form perspective-three-point solver (Grunert's formulation, as
+/// reviewed by Haralick et al. 1994).
+///
+/// With `s₁, s₂, s₃` the unknown distances along the three unit `bearings`,
+/// the law of cosines on each pair of rays gives three quadratic equations
+/// in the `s` that reduce – substituting `s₂ = u·s₁`, `s₃ = v·s₁` – to a
+/// quartic in `v`.  Each positive real root yields the three camera-frame
+/// points, and the camera pose is the rigid motion aligning them with
+/// `world` (`absolute_orientation`).
+///
+/// Returns up to four candidate poses.  Degenerate configurations
+/// (collinear or coincident world points) yield none.
+pub(crate) fn p3p(world: &[[f64; 3]; 3], bearings: &[[f64; 3]; 3]) -> Vec<PoseCandidate> {
+    let dist2 = |a: &[f64; 3], b: &[f64; 3]| {
+        (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+    };
+    let dot = |a: &[f64; 3], b: &[f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+
+    // Side lengths opposite each ray pair and the angles between the rays.
+    let a2 = dist2(&world[1], &world[2]);
+    let b2 = dist2(&world[0], &world[2]);
+    let c2 = dist2(&world[0], &world[1]);
+    if a2 < 1e-12 || b2 < 1e-12 || c2 < 1e-12 {
+        return Vec::new();
+    }
+    let cos_a = dot(&bearings[1], &bearings[2]);
+    let cos_b = dot(&bearings[0], &bearings[2]);
+    let cos_g = dot(&bearings[0], &bearings[1]);
+
+    let amc = (a2 - c2) / b2;
+    let apc = (a2 + c2) / b2;
+    let bmc = (b2 - c2) / b2;
+    let bma = (b2 - a2) / b2;
+
+    let a4 = (amc - 1.0).powi(2) - 4.0 * c2 / b2 * cos_a * cos_a;
+    let a3 = 4.0
+        * (amc * (1.0 - amc) * cos_b - (1.0 - apc) * cos_a * cos_g
+            + 2.0 * c2 / b2 * cos_a * cos_a * cos_b);
+    let a2c = 2.0
+        * (amc * amc - 1.0 + 2.0 * amc * amc * cos_b * cos_b + 2.0 * bmc * cos_a * cos_a
+            - 4.0 * apc * cos_a * cos_b * cos_g
+            + 2.0 * bma * cos_g * cos_g);
+    let a1 = 4.0
+        * (-amc * (1.0 + amc) * cos_b + 2.0 * a2 / b2 * cos_g * cos_g * cos_b
+            - (1.0 - apc) * cos_a * cos_g);
+    let a0 = (1.0 + amc).powi(2) - 4.0 * a2 / b2 * cos_g * cos_g;
+
+    let mut out = Vec::new();
+    for v in polynomial_real_roots(&[a4, a3, a2c, a1, a0]) {
+        if v <= 0.0 {
+            continue;
+        }
+        let denom = 2.0 * (cos_g - v * cos_a);
+        if denom.abs() < 1e-12 {
+            continue;
+        }
+        let u = ((amc - 1.0) * v * v - 2.0 * amc * cos_b * v + 1.0 + amc) / denom;
+        if u <= 0.0 {
+            continue;
+        }
+        let s1_sq = c2 / (1.0 + u * u - 2.0 * u * cos_g);
+        if s1_sq.is_nan() || s1_sq <= 0.0 {
+            continue;
+        }
+        let s1 = s1_sq.sqrt();
+        let s = [s1, u * s1, v * s1];
+        let cam_pts: [[f64; 3]; 3] = std::array::from_fn(|i| bearings[i].map(|b| b * s[i]));
+        if let Some(pose) = absolute_orientation(world, &cam_pts) {
+            out.push(pose);
+        }
+    }
+    out
+}
+
+// ── Focal scan over P3P ─────────────────────────────────────────────────────
+
+/// Focal lengths searched by the focal scan, as multiples of the larger
+/// image side (from a long telephoto crop down to a fisheye-like wide angle).
+const FOCAL_SCAN_RANGE: (f64, f64) = (0.2, 6.0);
+/// Log-spaced focal lengths in the initial scan.
+const FOCAL_SCAN_STEPS: usize = 40;
+/// Golden-section steps refining each local minimum of the scan.
+const FOCAL_SCAN_REFINE: usize = 24;
+/// At most this many focal-length minima are refined and returned.
+const FOCAL_SCAN_MAX_SOLUTIONS: usize = 2;
+
+/// Pose and focal length from four correspondences by a focal scan over P3P
+/// (a stand-in for a minimal P4Pf solver).
+///
+/// For a trial focal length the first three points fix up to four poses by
+/// P3P, and the fourth point's reprojection error says how well that focal
+/// length explains the sample.  That error is a one-dimensional function of
+/// the focal length: it is scanned on `FOCAL_SCAN_STEPS` log-spaced values
+/// over `FOCAL_SCAN_RANGE` (scaled by `image_size`, the larger image side in
+/// px), and the best `FOCAL_SCAN_MAX_SOLUTIONS` local minima of the scan are
+/// polished by golden-section search.  This is a numerical search, not a
+/// closed-form solver: focal lengths outside `FOCAL_SCAN_RANGE` are never
+/// found, and two minima closer than one grid step (about 9 % in focal
+/// length) merge, so the right one can be missed.  With noise-free data and
+/// a well-separated minimum it converges to the true focal length up to the
+/// search tolerance.
+///
+/// Each call costs at most `FOCAL_SCAN_STEPS + FOCAL_SCAN_MAX_SOLUTIONS ·
+/// (FOCAL_SCAN_REFINE + 3)` = 94 P3P solves, so a RANSAC iteration without
+/// `focalPx` costs about a hundred P3P iterations; `FOCAL_SCAN_MAX_ITERS`
+/// and `MAX_MINIMAL_SAMPLES` bound the total.
+///
+/// `base` supplies the principal point and distortion.  Returns up to
+/// `FOCAL_SCAN_MAX_SOLUTIONS` `(pose, focal_px)` pairs, best first.
+pub(crate) fn focal_scan_p3p(
+    world: &[[f64; 3]; 4],
+    pixels: &[[f64; 2]; 4],
+    base: &CameraIntrinsics,
+    image_size: f64,
+) -> Vec<(PoseCandidate, f64)> {
+    // Best P3P pose for `f` and its fourth-point error (px).
+    let solve = |log_f: f64| -> Option<(f64, PoseCandidate)> {
+        let intr = CameraIntrinsics {
+            focal_px: log_f.exp(),
+            ..*base
+        };
+        let mut bearings = [[0.0; 3]; 3];
+        for (b, p) in bearings.iter_mut().zip(pixels) {
+            *b = bearing(*p, &intr)?;
+        }
+        let tri = [world[0], world[1], world[2]];
+        p3p(&tri, &bearings)
+            .into_iter()
+            .filter_map(|pose| {
+                let (u, v) = project_point(world[3], pose.cam, &pose.rot, &intr)?;
+                Some(((u - pixels[3][0]).hypot(v - pixels[3][1]), pose))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    };
+    let error = |log_f: f64| solve(log_f).map_or(f64::INFINITY, |(e, _)| e);
+
+    let lo = (FOCAL_SCAN_RANGE.0 * image_size).ln();
+    let hi = (FOCAL_SCAN_RANGE.1 * image_size).ln();
+    let step = (hi - lo) / (FOCAL_SCAN_STEPS - 1) as f64;
+    let grid: Vec<f64> = (0..FOCAL_SCAN_STEPS)
+        .map(|i| lo + step * i as f64)
+        .collect();
+    let errs: Vec<f64> = grid.iter().map(|&g| error(g)).collect();
+
+    // Only the best few scan minima are refined, which bounds the cost.
+    let mut starts: Vec<usize> = (0..FOCAL_SCAN_STEPS)
+        .filter(|&i| {
+            let left = if i == 0 { f64::INFINITY } else { errs[i - 1] };
+            let right = errs.get(i + 1).copied().unwrap_or(f64::INFINITY);
+            errs[i].is_finite() && errs[i] <= left && errs[i] <= right
+        })
+        .collect();
+    starts.sort_by(|&a, &b| errs[a].total_cmp(&errs[b]));
+    starts.truncate(FOCAL_SCAN_MAX_SOLUTIONS);
+
+    let mut minima: Vec<(f64, f64)> = Vec::new();
+    for i in starts {
+        // Golden-section search on the bracketing interval.
+        let phi = 0.5 * (5f64.sqrt() - 1.0);
+        let (mut a, mut b) = (grid[i] - step, grid[i] + step);
+        let mut x1 = b - phi * (b - a);
+        let mut x2 = a + phi * (b - a);
+        let (mut e1, mut e2) = (error(x1), error(x2));
+        for _ in 0..FOCAL_SCAN_REFINE {
+            if e1 <= e2 {
+                b = x2;
+                x2 = x1;
+                e2 = e1;
+                x1 = b - phi * (b - a);
+                e1 = error(x1);
+            } else {
+                a = x1;
+                x1 = x2;
+                e1 = e2;
+                x2 = a + phi * (b - a);
+                e2 = error(x2);
+            }
+        }
+        let (x, e) = if e1 <= e2 { (x1, e1) } else { (x2, e2) };
+        let best = if e <= errs[i] {
+            (e, x)
+        } else {
+            (errs[i], grid[i])
+        };
+        minima.push(best);
+    }
+    minima.sort_by(|a, b| a.0.total_cmp(&b.0));
+    minima
+        .into_iter()
+        .filter_map(|(_, log_f)| solve(log_f).map(|(_, pose)| (pose, log_f.exp())))
+        .collect()
+}
+
+// ── Absolute orientation ────────────────────────────────────────────────────
+
+/// Rigid motion that best maps `world` points onto the matching camera-frame
+/// points `cam_pts` (least squares, Horn's closed-form quaternion method).
+///
+/// Returns the pose as rotation + camera centre, or `None` when the points
+/// do not determine a rotation (fewer than three non-collinear points).
+pub(crate) fn absolute_orientation(
+    world: &[[f64; 3]],
+    cam_pts: &[[f64; 3]],
+) -> Option<PoseCandidate> {
+    let n = world.len();
+    if n < 3 || cam_pts.len() != n {
+        return None;
+    }
+    let centroid = |pts: &[[f64; 3]]| {
+        let mut c = [0.0; 3];
+        for p in pts {
+            for k in 0..3 {
+                c[k] += p[k] / n as f64;
+            }
+        }
+        c
+    };
+    let pw = centroid(world);
+    let pc = centroid(cam_pts);
+
+    // Cross-covariance S[i][j] = Σ (world − pw)ᵢ · (cam − pc)ⱼ
+    let mut s = [[0.0; 3]; 3];
+    for (w, c) in world.iter().zip(cam_pts) {
+        for i in 0..3 {
+            for j in 0..3 {
+                s[i][j] += (w[i] - pw[i]) * (c[j] - pc[j]);
+            }
+        }
+    }
+    let [[sxx, sxy, sxz], [syx, syy, syz], [szx, szy, szz]] = s;
+    let nmat = [
+        [sxx + syy + szz, syz - szy, szx - sxz, sxy - syx],
+        [syz - szy, sxx - syy - szz, sxy + syx, szx + sxz],
+        [szx - sxz, sxy + syx, -sxx + syy - szz, syz + szy],
+        [sxy - syx, szx + sxz, syz + szy, -sxx - syy + szz],
+    ];
+    let (values, vectors) = symmetric_eigen(&nmat);
+    // A unique rotation needs a dominant eigenvalue.
+    if values[3] - values[2] < 1e-12 * values[3].abs().max(1e-300) {
+        return None;
+    }
+    let [w, x, y, z] = [vectors[0][3], vectors[1][3], vectors[2][3], vectors[3][3]];
+    let rot = [
+        [
+            w * w + x * x - y * y - z * z,
+            2.0 * (x * y - w * z),
+            2.0 * (x * z + w * y),
+        ],
+        [
+            2.0 * (x * y + w * z),
+            w * w - x * x + y * y - z * z,
+            2.0 * (y * z - w * x),
+        ],
+        [
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            w * w - x * x - y * y + z * z,
+        ],
+    ];
+    // cam_pts = rot · (world − cam)  ⇒  cam = pw − rotᵀ · pc
+    let mut cam = pw;
+    for k in 0..3 {
+        cam[k] -= rot[0][k] * pc[0] + rot[1][k] * pc[1] + rot[2][k] * pc[2];
+    }
+    if cam.iter().any(|v| !v.is_finite()) {
+        return None;
+    }
+    Some(PoseCandidate { rot, cam })
+}
+
+// ── EPnP ────────────────────────────────────────────────────────────────────
+
+/// Pairs of control points whose distances fix the EPnP null-space mix.
+const CONTROL_PAIRS: [(usize, usize); 6] = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+
+/// Efficient PnP (Lepetit, Moreno-Noguer & Fua 2009) for four or more
+/// correspondences.
+///
+/// Every world point is written as a barycentric combination of four
+/// control points – the centroid plus one point along each principal axis
+/// of the point cloud.  The projection equations are linear in the
+/// camera-frame control points, so these lie in the (near) null space of a
+/// `2n × 12` system.  The mix of null-space vectors is fixed by requiring
+/// the control points to keep their world distances: the paper's N = 1, 2
+/// and 3 linearisations each give a start that is polished by Gauss-Newton,
+/// and the start with the smallest reprojection error wins.  The pose
+/// finally comes from aligning all points with `absolute_orientation`.
+///
+/// `normalized` holds the undistorted image coordinates `(x/z, y/z)`.
+/// Returns `None` for fewer than four points and for (near-)planar point
+/// clouds, which leave the fourth control point undetermined – P3P still
+/// handles those.
+pub(crate) fn epnp(world: &[[f64; 3]], normalized: &[[f64; 2]]) -> Option<PoseCandidate> {
+    let n = world.len();
+    if n < 4 || normalized.len() != n {
+        return None;
+    }
+
+    // Control points from the principal axes of the world points.
+    let (centroid, spread, axes) = principal_axes(world)?;
+    if spread[0] <= 1e-3 * spread[2] {
+        return None;
+    }
+    let mut ctrl = [centroid; 4];
+    for k in 0..3 {
+        for i in 0..3 {
+            ctrl[k + 1][i] += spread[k] * axes[i][k];
+        }
+    }
+
+    // Barycentric coordinates: the axes are orthogonal, so each one is a
+    // projection onto its axis.
+    let alphas: Vec<[f64; 4]> = world
+        .iter()
+        .map(|p| {
+            let a: [f64; 3] = std::array::from_fn(|k| {
+                (0..3)
+                    .map(|i| (p[i] - centroid[i]) * axes[i][k])
+                    .sum::<f64>()
+                    / spread[k]
+            });
+            [1.0 - a[0] - a[1] - a[2], a[0], a[1], a[2]]
+        })
+        .collect();
+
+    // MᵀM for the projection equations  Σⱼ αⱼ (cⱼₓ − x·cⱼ_z) = 0  (and y).
+    let mut mtm = [[0.0; 12]; 12];
+    for (a, x) in alphas.iter().zip(normalized) {
+        let mut rows = [[0.0; 12]; 2];
+        for j in 0..4 {
+            rows[0][3 * j] = a[j];
+            rows[0][3 * j + 2] = -a[j] * x[0];
+            rows[1][3 * j + 1] = a[j];
+            rows[1][3 * j + 2] = -a[j] * x[1];
+        }
+        for row in &rows {
+            for i in 0..12 {
+                for j in 0..12 {
+                    mtm[i][j] += row[i] * row[j];
+                }
+            }
+        }
+    }
+    let (_, vectors) = symmetric_eigen(&mtm);
+    let null: [[f64; 12]; 4] = std::array::from_fn(|k| std::array::from_fn(|i| vectors[i][k]));
+
+    // Squared control-point distances as quadratic forms in the betas:
+    // ‖cᵢ − cⱼ‖² = Σₖₗ βₖ βₗ quad[p][k][l].
+    let mut quad = [[[0.0; 4]; 4]; 6];
+    let mut rho = [0.0; 6];
+    for (p, &(i, j)) in CONTROL_PAIRS.iter().enumerate() {
+        rho[p] = (0..3).map(|c| (ctrl[i][c] - ctrl[j][c]).powi(2)).sum();
+        let diff: [[f64; 3]; 4] = std::array::from_fn(|k| {
+            std::array::from_fn(|c| null[k][3 * i + c] - null[k][3 * j + c])
+        });
+        for k in 0..4 {
+            for l in 0..4 {
+                quad[p][k][l] = (0..3).map(|c| diff[k][c] * diff[l][c]).sum();
+            }
+        }
+    }
+
+    let mut starts: Vec<[f64; 4]> = Vec::new();
+    // N = 1: a single null-space vector, scaled to the world distances.
+    let num: f64 = (0..6).map(|p| (quad[p][0][0] * rho[p]).sqrt()).sum();
+    let den: f64 = (0..6).map(|p| quad[p][0][0]).sum();
+    if den > 0.0 {
+        starts.push([num / den, 0.0, 0.0, 0.0]);
+    }
+    // N = 2: linear in (β₁², β₁β₂, β₂²).
+    let rows2: Vec<[f64; 3]> = quad
+        .iter()
+        .map(|q| [q[0][0], 2.0 * q[0][1], q[1][1]])
+        .collect();
+    if let Some([b11, b12, b22]) = least_squares(&rows2, &rho) {
+        let b1 = b11.abs().sqrt();
+        starts.push([b1, b22.abs().sqrt() * b12.signum(), 0.0, 0.0]);
+    }
+    // N = 3: linear in the six products of β₁, β₂, β₃.
+    let rows3: [[f64; 6]; 6] = std::array::from_fn(|p| {
+        let q = &quad[p];
+        [
+            q[0][0],
+            2.0 * q[0][1],
+            q[1][1],
+            2.0 * q[0][2],
+            2.0 * q[1][2],
+            q[2][2],
+        ]
+    });
+    if let Some(b) = solve_linear(&rows3, &rho) {
+        let b1 = b[0].abs().sqrt();
+        if b1 > 0.0 {
+            starts.push([b1, b[1] / b1, b[3] / b1, 0.0]);
+        }
+    }
+
+    starts
+        .into_iter()
+        .filter_map(|beta| {
+            let beta = refine_betas(beta, &quad, &rho);
+            let pose = pose_from_betas(&beta, &null, &alphas, world)?;
+            Some((normalized_error(&pose, world, normalized), pose))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, pose)| pose)
+}
+
+/// Gauss-Newton on all four betas, minimising the control-point distance
+/// errors `Σₖₗ βₖ βₗ quad[p][k][l] − rho[p]`.
+fn refine_betas(mut beta: [f64; 4], quad: &[[[f64; 4]; 4]; 6], rho: &[f64; 6]) -> [f64; 4] {
+    for _ in 0..10 {
+        let mut jac = [[0.0; 4]; 6];
+        let mut neg_res = [0.0; 6];
+        for p in 0..6 {
+            let mut value = 0.0;
+            for k in 0..4 {
+                let row: f64 = (0..4).map(|l| quad[p][k][l] * beta[l]).sum();
+                jac[p][k] = 2.0 * row;
+                value += beta[k] * row;
+            }
+            neg_res[p] = rho[p] - value;
+        }
+        match least_squares(&jac, &neg_res) {
+            Some(delta) if delta.iter().all(|d| d.is_finite()) => {
+                for k in 0..4 {
+                    beta[k] += delta[k];
+                }
+            }
+            _ => break,
+        }
+    }
+    beta
+}
+
+/// Camera-frame points for a null-space mix `beta`, aligned with `world`.
+fn pose_from_betas(
+    beta: &[f64; 4],
+    null: &[[f64; 12]; 4],
+    alphas: &[[f64; 4]],
+    world: &[[f64; 3]],
+) -> Option<PoseCandidate> {
+    let ctrl: [[f64; 3]; 4] = std::array::from_fn(|j| {
+        std::array::from_fn(|c| (0..4).map(|k| beta[k] * null[k][3 * j + c]).sum())
+    });
+    let mut cam_pts: Vec<[f64; 3]> = alphas
+        .iter()
+        .map(|a| std::array::from_fn(|c| (0..4).map(|j| a[j] * ctrl[j][c]).sum()))
+        .collect();
+    // The null space is only defined up to sign: put the points in front.
+    if cam_pts.iter().map(|p| p[2]).sum::<f64>() < 0.0 {
+        for p in &mut cam_pts {
+            *p = p.map(|v| -v);
+        }
+    }
+    absolute_orientation(world, &cam_pts)
+}
+
+/// Sum of squared errors in normalised image coordinates; infinite when a
+/// point lands behind the camera.
+fn normalized_error(pose: &PoseCandidate, world: &[[f64; 3]], normalized: &[[f64; 2]]) -> f64 {
+    let mut err = 0.0;
+    for (p, x) in world.iter().zip(normalized) {
+        let d = [p[0] - pose.cam[0], p[1] - pose.cam[1], p[2] - pose.cam[2]];
+        let pc = mat3_vec(&pose.rot, &d);
+        if pc[2] <= 0.0 {
+            return f64::INFINITY;
+        }
+        err += (pc[0] / pc[2] - x[0]).powi(2) + (pc[1] / pc[2] - x[1]).powi(2);
+    }
+    err
+}
+
+// ── Seeding ─────────────────────────────────────────────────────────────────
+
+/// A triple of correspondences is degenerate when its pixels are (nearly)
+/// collinear or its world points (nearly) coincide: the pose is then not
+/// determined.
+pub(crate) fn is_degenerate(sample: &[EnuCorrespondence]) -> bool {
+    let [a, b, c] = [&sample[0], &sample[1], &sample[2]];
+    let cross = (b.pixel[0] - a.pixel[0]) * (c.pixel[1] - a.pixel[1])
+        - (b.pixel[1] - a.pixel[1]) * (c.pixel[0] - a.pixel[0]);
+    // Twice the triangle area in px²; < 1 px² is numerically a line.
+    if cross.abs() < 2.0 {
+        return true;
+    }
+    let d = |p: &[f64; 3], q: &[f64; 3]| {
+        ((p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2)).sqrt()
+    };
+    d(&a.enu, &b.enu) < 0.1 || d(&a.enu, &c.enu) < 0.1 || d(&b.enu, &c.enu) < 0.1
+}
+
+/// Intrinsics used to turn pixels into bearings: the problem's initial
+/// focal length and principal point with the distortion prior applied.
+pub(crate) fn seed_intrinsics(problem: &Problem) -> CameraIntrinsics {
+    let [k1, k2, p1, p2] = problem.dist_prior;
+    CameraIntrinsics {
+        k1,
+        k2,
+        p1,
+        p2,
+        ..problem.intr
+    }
+}
+
+/// Parameter vector for a closed-form pose, with the intrinsics seeded from
+/// `problem` exactly as `initialize_pose` does.
+pub(crate) fn candidate_params(pose: &PoseCandidate, problem: &Problem) -> [f64; NUM_PARAMS] {
+    let mut p = [0.0; NUM_PARAMS];
+    p[..3].copy_from_slice(&pose.cam);
+    set_rotation(&mut p, &pose.rot);
+    p[6..10].copy_from_slice(&problem.dist_prior);
+    p[FOCAL] = problem.intr.focal_px;
+    p[CX] = problem.intr.cx;
+    p[CX + 1] = problem.intr.cy;
+    p
+}
+
+/// All P3P solutions for the correspondences at `idx`, as parameter vectors.
+pub(crate) fn p3p_candidates(idx: [usize; 3], problem: &Problem) -> Vec<[f64; NUM_PARAMS]> {
+    let sample: Vec<EnuCorrespondence> = idx.iter().map(|&i| problem.corrs[i].clone()).collect();
+    if is_degenerate(&sample) {
+        return Vec::new();
+    }
+    let intr = seed_intrinsics(problem);
+    let mut bearings = [[0.0; 3]; 3];
+    for (b, c) in bearings.iter_mut().zip(&sample) {
+        match bearing(c.pixel, &intr) {
+            Some(v) => *b = v,
+            None => return Vec::new(),
+        }
+    }
+    let world = [sample[0].enu, sample[1].enu, sample[2].enu];
+    p3p(&world, &bearings)
+        .iter()
+        .map(|pose| candidate_params(pose, problem))
+        .collect()
+}
+
+/// All focal-scan solutions for the correspondences at `idx`, as parameter
+/// vectors carrying their own focal length.
+pub(crate) fn focal_scan_candidates(idx: [usize; 4], problem: &Problem) -> Vec<[f64; NUM_PARAMS]> {
+    let sample: Vec<EnuCorrespondence> = idx.iter().map(|&i| problem.corrs[i].clone()).collect();
+    if is_degenerate(&sample[..3]) {
+        return Vec::new();
+    }
+    let intr = seed_intrinsics(problem);
+    let image_size = 2.0 * intr.cx.max(intr.cy);
+    let world = [sample[0].enu, sample[1].enu, sample[2].enu, sample[3].enu];
+    let pixels = [
+        sample[0].pixel,
+        sample[1].pixel,
+        sample[2].pixel,
+        sample[3].pixel,
+    ];
+    focal_scan_p3p(&world, &pixels, &intr, image_size)
+        .iter()
+        .map(|(pose, focal_px)| {
+            let mut params = candidate_params(pose, problem);
+            params[FOCAL] = *focal_px;
+            params
+        })
+        .collect()
+}
+
+/// Size of the minimal sample for `problem`: four points (focal scan) when the
+/// focal length is free and unknown, three (P3P) otherwise.
+pub(crate) fn minimal_sample_size(problem: &Problem) -> usize {
+    if problem.unknown_focal && problem.free()[FOCAL] {
+        4
+    } else {
+        3
+    }
+}
+
+/// Minimal-solver solutions for a sample of `minimal_sample_size`
+/// correspondences (focal scan for four indices, P3P for three).
+pub(crate) fn minimal_candidates(idx: &[usize], problem: &Problem) -> Vec<[f64; NUM_PARAMS]> {
+    match *idx {
+        [a, b, c, d] => focal_scan_candidates([a, b, c, d], problem),
+        [a, b, c] => p3p_candidates([a, b, c], problem),
+        _ => Vec::new(),
+    }
+}
+
+/// `n` choose `k`, or `None` when it does not fit in a `u64`.
+pub(crate) fn binomial(n: usize, k: usize) -> Option<u64> {
+    (0..k.min(n) as u64).try_fold(1u64, |acc, i| {
+        acc.checked_mul(n as u64 - i).map(|p| p / (i + 1))
+    })
+}
+
+/// Minimal-solver starting points for the full problem (P3P, or the focal
+/// scan when the focal length is unknown).
+///
+/// Uses every minimal subset when there are at most `max_samples` of them,
+/// and otherwise a deterministic random selection (fixed seed) of that many.
+pub(crate) fn minimal_seeds(problem: &Problem, max_samples: usize) -> Vec<[f64; NUM_PARAMS]> {
+    let n = problem.corrs.len();
+    let k = minimal_sample_size(problem);
+    if n < k {
+        return Vec::new();
+    }
+    let total = binomial(n, k).filter(|&t| t <= max_samples as u64);
+    let samples: Vec<Vec<usize>> = if let Some(total) = total {
+        let mut all = Vec::with_capacity(total as usize);
+        let mut idx: Vec<usize> = (0..k).collect();
+        loop {
+            all.push(idx.clone());
+            // Next k-combination in lexicographic order.
+            let Some(i) = (0..k).rev().find(|&i| idx[i] < n - k + i) else {
+                break;
+            };
+            idx[i] += 1;
+            for j in (i + 1)..k {
+                idx[j] = idx[j - 1] + 1;
+            }
+        }
+        all
+    } else {
+        let mut rng = SplitMix64::new(DEFAULT_SEED);
+        (0..max_samples)
+            .map(|_| rng.sample_distinct(n, k))
+            .collect()
+    };
+    samples
+        .iter()
+        .flat_map(|s| minimal_candidates(s, problem))
+        .collect()
+}
+
+/// EPnP starting point from all correspondences, or `None` when EPnP does
+/// not apply (fewer than four points, planar scene, failed undistortion).
+pub(crate) fn epnp_seed(problem: &Problem) -> Option<[f64; NUM_PARAMS]> {
+    let intr = seed_intrinsics(problem);
+    let normalized = problem
+        .corrs
+        .iter()
+        .map(|c| undistort_pixel(c.pixel[0], c.pixel[1], &intr).map(|(x, y)| [x, y]))
+        .collect::<Option<Vec<[f64; 2]>>>()?;
+    let world: Vec<[f64; 3]> = problem.corrs.iter().map(|c| c.enu).collect();
+    epnp(&world, &normalized).map(|pose| candidate_params(&pose, problem))
+}
@@ -14,7 +14,7 @@ pub(crate) type Mat3 = [[f64; 3]; 3];
 /// ```
 ///
 /// Set k1 = k2 = p1 = p2 = 0 for a pure pinhole model.
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub(crate) struct CameraIntrinsics {
     pub focal_px: f64,
     pub cx: f64,
@@ -44,9 +44,9 @@ pub(crate) struct CameraIntrinsics {
 /// The full rotation is  `R = R_roll · R_pitch · R_base · R_yaw` where:
 ///
 /// * `R_yaw`   – rotates ENU around the Up axis to align North with the
-///               camera heading.
+///   camera heading.
 /// * `R_base`  – swaps axes from heading-aligned ENU to camera frame
-///               (cam-X = East, cam-Y = −Up, cam-Z = North).
+///   (cam-X = East, cam-Y = −Up, cam-Z = North).
 /// * `R_pitch` – tilts the camera frame around its X (right) axis.
 /// * `R_roll`  – banks the camera frame around its Z (forward) axis.
 pub(crate) fn rotation_enu_to_cam(yaw_deg: f64, pitch_deg: f64, roll_deg: f64) -> Mat3 {
@@ -80,6 +80,127 @@ pub(crate) fn rotation_enu_to_cam(yaw_deg: f64, pitch_deg: f64, roll_deg: f64) -
     mat3_mul(&r_roll, &t2)
 }
 
+/// Inverse of `rotation_enu_to_cam`: the `(yaw°, pitch°, roll°)` of a
+/// rotation matrix, with yaw in `[0, 360)` and pitch in `[-90, 90]`.
+///
+/// The camera's forward axis is the third row of `R`, i.e.
+/// `(cos θ·sin ψ, cos θ·cos ψ, sin θ)`; roll follows from the Up components
+/// of the right and down axes.  At pitch ±90° yaw and roll are not
+/// separable and yaw absorbs the whole rotation.
+pub(crate) fn euler_from_rotation(r: &Mat3) -> (f64, f64, f64) {
+    let horizontal = r[2][0].hypot(r[2][1]);
+    let pitch = r[2][2].atan2(horizontal);
+    let (yaw, roll) = if horizontal > 1e-9 {
+        (r[2][0].atan2(r[2][1]), r[0][2].atan2(-r[1][2]))
+    } else {
+        // Looking straight up/down: forward is ±Up; take roll = 0 and
+        // read yaw from the right axis (cos ψ, −sin ψ, 0).
+        ((-r[0][1]).atan2(r[0][0]), 0.0)
+    };
+    let yaw_deg = (yaw.to_degrees() % 360.0 + 360.0) % 360.0;
+    (yaw_deg, pitch.to_degrees(), roll.to_degrees())
+}
+
+/// Derivatives of `euler_from_rotation` (degrees) with respect to a small
+/// rotation `δ` (radians) applied in the camera frame, `R' = exp([δ]×) · R`.
+///
+/// Row `i` holds `∂(yaw, pitch, roll)ᵢ / ∂δ`.  The yaw and roll rows grow
+/// like `1 / cos(pitch)` and are zero at pitch ±90°, where those angles are
+/// not defined.
+pub(crate) fn euler_local_jacobian(r: &Mat3) -> [[f64; 3]; 3] {
+    let h2 = r[2][0] * r[2][0] + r[2][1] * r[2][1];
+    let q2 = r[0][2] * r[0][2] + r[1][2] * r[1][2];
+    let h = h2.sqrt();
+    let deg = 1f64.to_degrees();
+    let mut jac = [[0.0; 3]; 3];
+    for k in 0..3 {
+        // dR = [e_k]× · R: every column c becomes e_k × c.
+        let mut e = [0.0; 3];
+        e[k] = 1.0;
+        let dr: Mat3 = std::array::from_fn(|i| {
+            std::array::from_fn(|j| cross(&e, &[r[0][j], r[1][j], r[2][j]])[i])
+        });
+        if h > 1e-9 {
+            // yaw = atan2(R20, R21), pitch = atan2(R22, hypot(R20, R21))
+            jac[0][k] = (r[2][1] * dr[2][0] - r[2][0] * dr[2][1]) / h2 * deg;
+            let dh = (r[2][0] * dr[2][0] + r[2][1] * dr[2][1]) / h;
+            jac[1][k] = (h * dr[2][2] - r[2][2] * dh) * deg;
+        }
+        if h > 1e-9 && q2 > 1e-18 {
+            // roll = atan2(R02, −R12)
+            jac[2][k] = (r[0][2] * dr[1][2] - r[1][2] * dr[0][2]) / q2 * deg;
+        }
+    }
+    jac
+}
+
+/// Rotation matrix of the rotation vector `w` (axis × angle, radians), by
+/// Rodrigues' formula.
+pub(crate) fn rotation_from_vector(w: &[f64; 3]) -> Mat3 {
+    let theta2 = w[0] * w[0] + w[1] * w[1] + w[2] * w[2];
+    let theta = theta2.sqrt();
+    // sin θ / θ and (1 − cos θ) / θ², with their Taylor series near 0
+    let (a, b) = if theta < 1e-6 {
+        (1.0 - theta2 / 6.0, 0.5 - theta2 / 24.0)
+    } else {
+        (theta.sin() / theta, (1.0 - theta.cos()) / theta2)
+    };
+    let k: Mat3 = [[0.0, -w[2], w[1]], [w[2], 0.0, -w[0]], [-w[1], w[0], 0.0]];
+    let k2 = mat3_mul(&k, &k);
+    std::array::from_fn(|i| {
+        std::array::from_fn(|j| if i == j { 1.0 } else { 0.0 } + a * k[i][j] + b * k2[i][j])
+    })
+}
+
+/// Inverse of `rotation_from_vector`, with the angle in `[0, π]`.
+///
+/// Goes through the unit quaternion (Shepperd's method), which stays
+/// accurate near both 0 and π where the trace-based formula does not.
+pub(crate) fn rotation_to_vector(r: &Mat3) -> [f64; 3] {
+    let trace = r[0][0] + r[1][1] + r[2][2];
+    let (w, x, y, z) = if trace > 0.0 {
+        let s = 2.0 * (trace + 1.0).sqrt();
+        (
+            0.25 * s,
+            (r[2][1] - r[1][2]) / s,
+            (r[0][2] - r[2][0]) / s,
+            (r[1][0] - r[0][1]) / s,
+        )
+    } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+        let s = 2.0 * (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt();
+        (
+            (r[2][1] - r[1][2]) / s,
+            0.25 * s,
+            (r[0][1] + r[1][0]) / s,
+            (r[0][2] + r[2][0]) / s,
+        )
+    } else if r[1][1] > r[2][2] {
+        let s = 2.0 * (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt();
+        (
+            (r[0][2] - r[2][0]) / s,
+            (r[0][1] + r[1][0]) / s,
+            0.25 * s,
+            (r[1][2] + r[2][1]) / s,
+        )
+    } else {
+        let s = 2.0 * (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt();
+        (
+            (r[1][0] - r[0][1]) / s,
+            (r[0][2] + r[2][0]) / s,
+            (r[1][2] + r[2][1]) / s,
+            0.25 * s,
+        )
+    };
+    // q and −q are the same rotation; w ≥ 0 picks the angle in [0, π].
+    let sign = if w < 0.0 { -1.0 } else { 1.0 };
+    let n = (x * x + y * y + z * z).sqrt();
+    if n < 1e-300 {
+        return [0.0; 3];
+    }
+    let angle = 2.0 * n.atan2(sign * w);
+    [x, y, z].map(|v| sign * v * angle / n)
+}
+
 // ── Projection ──────────────────────────────────────────────────────────────
 
 /// Project a 3-D ENU point to pixel coordinates through a camera described
@@ -118,6 +239,135 @@ pub(crate) fn project_point(
     Some((u, v))
 }
 
+/// Pixel coordinates of a projected point together with their closed-form
+/// derivatives.  Derivatives w.r.t. the principal point are the identity
+/// and are not stored.
+pub(crate) struct ProjectionJacobian {
+    pub u: f64,
+    pub v: f64,
+    /// `∂(u, v) / ∂p_cam`, with `p_cam = R · (point − cam)` the point in the
+    /// camera frame.  Chain with `−R` for the camera position and with
+    /// `∂R/∂θ · (point − cam)` for the Euler angles.
+    pub d_cam_point: [[f64; 3]; 2],
+    /// `∂(u, v) / ∂(k1, k2, p1, p2)`.
+    pub d_distortion: [[f64; 4]; 2],
+    /// `∂(u, v) / ∂f`.
+    pub d_focal: [f64; 2],
+}
+
+/// `project_point` for a point already in the camera frame, plus its
+/// derivatives (see `ProjectionJacobian`).
+///
+/// Returns `None` when the point is behind the camera (Z_cam ≤ 0).
+pub(crate) fn project_cam_point_jacobian(
+    p: [f64; 3],
+    intr: &CameraIntrinsics,
+) -> Option<ProjectionJacobian> {
+    if p[2] <= 0.0 {
+        return None;
+    }
+    let (k1, k2, p1, p2, f) = (intr.k1, intr.k2, intr.p1, intr.p2, intr.focal_px);
+
+    let inv_z = 1.0 / p[2];
+    let xn = p[0] * inv_z;
+    let yn = p[1] * inv_z;
+
+    let r2 = xn * xn + yn * yn;
+    let radial = 1.0 + k1 * r2 + k2 * r2 * r2;
+    let xd = xn * radial + 2.0 * p1 * xn * yn + p2 * (r2 + 2.0 * xn * xn);
+    let yd = yn * radial + p1 * (r2 + 2.0 * yn * yn) + 2.0 * p2 * xn * yn;
+
+    // ∂radial/∂xn, ∂radial/∂yn
+    let dradial_dr2 = k1 + 2.0 * k2 * r2;
+    let drad_x = dradial_dr2 * 2.0 * xn;
+    let drad_y = dradial_dr2 * 2.0 * yn;
+
+    // Distortion Jacobian ∂(xd, yd)/∂(xn, yn)
+    let dxd_dxn = radial + xn * drad_x + 2.0 * p1 * yn + 6.0 * p2 * xn;
+    let dxd_dyn = xn * drad_y + 2.0 * p1 * xn + 2.0 * p2 * yn;
+    let dyd_dxn = yn * drad_x + 2.0 * p1 * xn + 2.0 * p2 * yn;
+    let dyd_dyn = radial + yn * drad_y + 6.0 * p1 * yn + 2.0 * p2 * xn;
+
+    // Perspective division ∂(xn, yn)/∂p
+    let dxn = [inv_z, 0.0, -xn * inv_z];
+    let dyn_ = [0.0, inv_z, -yn * inv_z];
+
+    let mut d_cam_point = [[0.0; 3]; 2];
+    for k in 0..3 {
+        d_cam_point[0][k] = f * (dxd_dxn * dxn[k] + dxd_dyn * dyn_[k]);
+        d_cam_point[1][k] = f * (dyd_dxn * dxn[k] + dyd_dyn * dyn_[k]);
+    }
+
+    let d_distortion = [
+        [
+            f * xn * r2,
+            f * xn * r2 * r2,
+            f * 2.0 * xn * yn,
+            f * (r2 + 2.0 * xn * xn),
+        ],
+        [
+            f * yn * r2,
+            f * yn * r2 * r2,
+            f * (r2 + 2.0 * yn * yn),
+            f * 2.0 * xn * yn,
+        ],
+    ];
+
+    Some(ProjectionJacobian {
+        u: f * xd + intr.cx,
+        v: f * yd + intr.cy,
+        d_cam_point,
+        d_distortion,
+        d_focal: [xd, yd],
+    })
+}
+
+// ── Inverse projection ──────────────────────────────────────────────────────
+
+/// Map a pixel back to normalised (undistorted) image coordinates
+/// `(xn, yn)`, i.e. the inverse of the distortion + intrinsics step of
+/// `project_point`.
+///
+/// Brown-Conrady distortion has no closed-form inverse, so this uses the
+/// same fixed-point iteration as OpenCV's `undistortPoints`: start from the
+/// distorted coordinates and repeatedly remove the distortion predicted at
+/// the current estimate.  Converges in a few iterations for realistic lenses.
+///
+/// Returns `None` when the iteration does not converge (extreme
+/// coefficients or pixels far outside the calibrated field of view).
+pub(crate) fn undistort_pixel(u: f64, v: f64, intr: &CameraIntrinsics) -> Option<(f64, f64)> {
+    let xd = (u - intr.cx) / intr.focal_px;
+    let yd = (v - intr.cy) / intr.focal_px;
+    let (mut xn, mut yn) = (xd, yd);
+    for _ in 0..50 {
+        let r2 = xn * xn + yn * yn;
+        let radial = 1.0 + intr.k1 * r2 + intr.k2 * r2 * r2;
+        let dx = 2.0 * intr.p1 * xn * yn + intr.p2 * (r2 + 2.0 * xn * xn);
+        let dy = intr.p1 * (r2 + 2.0 * yn * yn) + 2.0 * intr.p2 * xn * yn;
+        let nx = (xd - dx) / radial;
+        let ny = (yd - dy) / radial;
+        let step = (nx - xn).hypot(ny - yn);
+        xn = nx;
+        yn = ny;
+        if step < 1e-12 {
+            break;
+        }
+    }
+    if !(xn.is_finite() && yn.is_finite()) {
+        return None;
+    }
+
+    // Verify by re-distorting: the residual must be well below a pixel.
+    let r2 = xn * xn + yn * yn;
+    let radial = 1.0 + intr.k1 * r2 + intr.k2 * r2 * r2;
+    let rx = xn * radial + 2.0 * intr.p1 * xn * yn + intr.p2 * (r2 + 2.0 * xn * xn);
+    let ry = yn * radial + intr.p1 * (r2 + 2.0 * yn * yn) + 2.0 * intr.p2 * xn * yn;
+    if (rx - xd).hypot(ry - yd) * intr.focal_px > 1e-3 {
+        return None;
+    }
+    Some((xn, yn))
+}
+
 // ── Small linear-algebra helpers ────────────────────────────────────────────
 
 pub(crate) fn mat3_mul(a: &Mat3, b: &Mat3) -> Mat3 {
@@ -130,6 +380,16 @@ pub(crate) fn mat3_mul(a: &Mat3, b: &Mat3) -> Mat3 {
     c
 }
 
+/// Multiply the transpose of `m` by `v`.  For a rotation matrix this applies
+/// the inverse rotation (e.g. camera frame → ENU).
+pub(crate) fn mat3_t_vec(m: &Mat3, v: &[f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[1][0] * v[1] + m[2][0] * v[2],
+        m[0][1] * v[0] + m[1][1] * v[1] + m[2][1] * v[2],
+        m[0][2] * v[0] + m[1][2] * v[1] + m[2][2] * v[2],
+    ]
+}
+
 pub(crate) fn mat3_vec(m: &Mat3, v: &[f64; 3]) -> [f64; 3] {
     [
         m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
@@ -137,3 +397,32 @@ pub(crate) fn mat3_vec(m: &Mat3, v: &[f64; 3]) -> [f64; 3] {
         m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
     ]
 }
+
+pub(crate) fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+// ── Conversions ─────────────────────────────────────────────────────────────
+
+impl From<&crate::types::Intrinsics> for CameraIntrinsics {
+    /// Build the internal camera model from the serialised `Intrinsics`.
+    fn from(i: &crate::types::Intrinsics) -> Self {
+        CameraIntrinsics {
+            focal_px: i.focal_px,
+            cx: i.cx,
+            cy: i.cy,
+            k1: i.k1,
+            k2: i.k2,
+            p1: i.p1,
+            p2: i.p2,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::error::SolveError;
+use crate::geo::{bearing_degrees, enu_to_lla, lla_to_enu};
+use crate::projection::{
+    mat3_t_vec, project_point, rotation_enu_to_cam, undistort_pixel, CameraIntrinsics,
+};
+use crate::types::{
+    BackProjectRequest, BackProjectResponse, ProjectedPixel, ReprojectRequest, ReprojectResponse,
+};
+
+// ── Public entry point ──────────────────────────────────────────────────────
+
+/// Project WGS-84 world points through a known camera pose.
+///
+/// The local ENU frame is anchored at the camera itself, so the camera sits
+/// at the origin and every world point is expressed relative to it.  World
+/// points without an altitude are placed at 0 m (same convention as
+/// `solve_impl`).
+///
+/// Returns one `ProjectedPixel` per input point, in input order.  Points
+/// behind the camera get `u = v = None`; points in front of the camera but
+/// outside the image keep their (off-image) pixel coordinates so the UI can
+/// still draw an edge indicator.
+pub fn reproject_impl(req: &ReprojectRequest) -> Result<ReprojectResponse, SolveError> {
+    if req.image.width.is_nan()
+        || req.image.height.is_nan()
+        || req.image.width <= 0.0
+        || req.image.height <= 0.0
+    {
+        return Err(SolveError::InvalidInput {
+            field: "image".to_string(),
+            message: "Image width and height must be positive".to_string(),
+        });
+    }
+    if req.intrinsics.focal_px.is_nan() || req.intrinsics.focal_px <= 0.0 {
+        return Err(focal_not_positive());
+    }
+
+    let pose = &req.pose;
+    let rot = rotation_enu_to_cam(pose.yaw_deg, pose.pitch_deg, pose.roll_deg);
+    let intr = CameraIntrinsics::from(&req.intrinsics);
+    let cam = [0.0, 0.0, 0.0];
+
+    let mut behind = 0;
+    let mut outside = 0;
+    let pixels: Vec<ProjectedPixel> = req
+        .points
+        .iter()
+        .map(|p| {
+            let enu = lla_to_enu(
+                p.world.lat,
+                p.world.lon,
+                p.world.alt.unwrap_or(0.0),
+                pose.lat,
+                pose.lon,
+                pose.alt,
+            );
+            match project_point(enu, cam, &rot, &intr) {
+                Some((u, v)) => {
+                    let outside_image = !(0.0..req.image.width).contains(&u)
+                        || !(0.0..req.image.height).contains(&v);
+                    if outside_image {
+                        outside += 1;
+                    }
+                    ProjectedPixel {
+                        id: p.id.clone(),
+                        u: Some(u),
+                        v: Some(v),
+                        behind_camera: false,
+                        outside_image,
+                    }
+                }
+                None => {
+                    behind += 1;
+                    ProjectedPixel {
+                        id: p.id.clone(),
+                        u: None,
+                        v: None,
+                        behind_camera: true,
+                        outside_image: true,
+                    }
+                }
+            }
+        })
+        .collect();
+
+    let mut warnings = Vec::new();
+    if behind > 0 {
+        warnings.push(format!("{behind} point(s) are behind the camera."));
+    }
+    if outside > 0 {
+        warnings.push(format!("{outside} point(s) project outside the image."));
+    }
+
+    Ok(ReprojectResponse { pixels, warnings })
+}
+
+/// Intersect the viewing ray through a pixel with a horizontal surface at a
+/// given ellipsoidal altitude – the inverse of `reproject_impl`.
+///
+/// Steps:
+/// 1. Undistort the pixel to normalised image coordinates.
+/// 2. Rotate the camera-frame ray `(xn, yn, 1)` into ENU (camera at origin).
+/// 3. Intersect with the flat plane `up = plane_alt − cam_alt` for a first
+///    estimate of the ray parameter `t`.
+/// 4. Refine `t` with Newton steps on the true ellipsoidal altitude, so the
+///    result stays on the requested altitude surface even kilometres away
+///    where Earth curvature drops the surface below the tangent plane.
+///
+/// Returns an error when the ray never reaches the surface (pointing away
+/// from it or parallel to it) or the pixel cannot be undistorted.
+pub fn back_project_impl(req: &BackProjectRequest) -> Result<BackProjectResponse, SolveError> {
+    if req.intrinsics.focal_px.is_nan() || req.intrinsics.focal_px <= 0.0 {
+        return Err(focal_not_positive());
+    }
+
+    let pose = &req.pose;
+    let intr = CameraIntrinsics::from(&req.intrinsics);
+    let (xn, yn) = undistort_pixel(req.pixel.u, req.pixel.v, &intr).ok_or_else(|| {
+        SolveError::InvalidInput {
+            field: "intrinsics".to_string(),
+            message: "Pixel could not be undistorted with these coefficients".to_string(),
+        }
+    })?;
+
+    let rot = rotation_enu_to_cam(pose.yaw_deg, pose.pitch_deg, pose.roll_deg);
+    let ray = mat3_t_vec(&rot, &[xn, yn, 1.0]);
+    let norm = (ray[0] * ray[0] + ray[1] * ray[1] + ray[2] * ray[2]).sqrt();
+    let dir = [ray[0] / norm, ray[1] / norm, ray[2] / norm];
+
+    // ── Flat-plane intersection ─────────────────────────────────────────
+    let dh = req.plane_alt - pose.alt;
+    if dir[2].abs() < 1e-9 || dh / dir[2] <= 0.0 {
+        return Err(SolveError::NoIntersection);
+    }
+    let mut t = dh / dir[2];
+
+    // ── Ellipsoidal refinement ──────────────────────────────────────────
+    let alt_at = |t: f64| {
+        enu_to_lla(
+            [dir[0] * t, dir[1] * t, dir[2] * t],
+            pose.lat,
+            pose.lon,
+            pose.alt,
+        )
+        .2
+    };
+    for _ in 0..10 {
+        let f = alt_at(t) - req.plane_alt;
+        if f.abs() < 1e-4 {
+            break;
+        }
+        let h = (1e-3 * t).max(1e-3);
+        let df = (alt_at(t + h) - alt_at(t - h)) / (2.0 * h);
+        if df.abs() < 1e-12 {
+            break;
+        }
+        t = (t - f / df).max(0.0);
+    }
+
+    let hit = [dir[0] * t, dir[1] * t, dir[2] * t];
+    let (lat, lon, alt) = enu_to_lla(hit, pose.lat, pose.lon, pose.alt);
+    if (alt - req.plane_alt).abs() > 0.01 {
+        return Err(SolveError::NoIntersection);
+    }
+
+    let mut warnings = Vec::new();
+    if t > 20_000.0 {
+        warnings.push(format!(
+            "Intersection is {:.1} km away; small angular errors move it a lot.",
+            t / 1000.0
+        ));
+    }
+
+    Ok(BackProjectResponse {
+        lat,
+        lon,
+        alt,
+        distance_m: t,
+        bearing_deg: bearing_degrees(pose.lat, pose.lon, lat, lon),
+        warnings,
+    })
+}
+
+fn focal_not_positive() -> SolveError {
+    SolveError::InvalidInput {
+        field: "intrinsics.focalPx".to_string(),
+        message: "Focal length must be positive".to_string(),
+    }
+}
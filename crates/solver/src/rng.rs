@@ -0,0 +1,48 @@
+/// Small, seedable pseudo-random number generator (SplitMix64).
+///
+/// The solver must be deterministic – the same request always produces the
+/// same response – so every random choice (RANSAC samples, …) draws from
+/// this generator seeded from the request instead of from OS entropy.
+/// SplitMix64 is tiny, fast and has good statistical quality for sampling;
+/// it is *not* cryptographically secure.
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+/// Seed used when the request does not provide one.
+pub(crate) const DEFAULT_SEED: u64 = 0x5EED_CA11_B0A7_F00D;
+
+impl SplitMix64 {
+    /// Create a generator from a 64-bit seed.
+    pub(crate) fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    /// Next raw 64-bit value.
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..n` (`n` must be > 0).
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        // Multiply-shift keeps the modulo bias negligible for small `n`.
+        (((self.next_u64() >> 32) * n as u64) >> 32) as usize
+    }
+
+    /// `k` distinct indices drawn uniformly from `0..n` (`k ≤ n`), in the
+    /// order they were drawn.
+    pub(crate) fn sample_distinct(&mut self, n: usize, k: usize) -> Vec<usize> {
+        let mut out = Vec::with_capacity(k);
+        while out.len() < k {
+            let i = self.below(n);
+            if !out.contains(&i) {
+                out.push(i);
+            }
+        }
+        out
+    }
+}
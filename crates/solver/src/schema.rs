@@ -0,0 +1,258 @@
+use serde_json::{json, Map, Value};
+
+use crate::error::SolveError;
+use crate::types::{SolveRequest, SolveResponse};
+
+pub(crate) use solver_derive::JsonSchema;
+
+/// Version of the `solve` request / response JSON.  Bump it, and add a step
+/// to `MIGRATIONS`, whenever a field is renamed, removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// One migration per version: `MIGRATIONS[v]` rewrites a version-`v`
+/// request into version `v + 1` in place.
+const MIGRATIONS: [fn(&mut Value); SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+// ── Schema description ──────────────────────────────────────────────────────
+
+/// JSON Schema (draft 2020-12) of a Rust type, as serde reads or writes it.
+///
+/// The request and response types `#[derive(JsonSchema)]` (from
+/// `solver-derive`), which reads the same serde attributes as serde does.
+/// Objects list every field and set `additionalProperties: false`;
+/// optional fields (`Option`, `#[serde(default)]` or
+/// `skip_serializing_if`) are left out of `required`.  The request schema
+/// doubles as the whitelist for unknown-field warnings.
+pub(crate) trait JsonSchema {
+    fn schema() -> Value;
+}
+
+impl JsonSchema for f64 {
+    fn schema() -> Value {
+        json!({ "type": "number" })
+    }
+}
+
+impl JsonSchema for u32 {
+    fn schema() -> Value {
+        json!({ "type": "integer", "minimum": 0 })
+    }
+}
+
+impl JsonSchema for u64 {
+    fn schema() -> Value {
+        json!({ "type": "integer", "minimum": 0 })
+    }
+}
+
+impl JsonSchema for usize {
+    fn schema() -> Value {
+        json!({ "type": "integer", "minimum": 0 })
+    }
+}
+
+impl JsonSchema for bool {
+    fn schema() -> Value {
+        json!({ "type": "boolean" })
+    }
+}
+
+impl JsonSchema for String {
+    fn schema() -> Value {
+        json!({ "type": "string" })
+    }
+}
+
+/// An absent value and `null` are the same to serde; the schema only
+/// describes the value itself.
+impl<T: JsonSchema> JsonSchema for Option<T> {
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Vec<T> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T: JsonSchema, const N: usize> JsonSchema for [T; N] {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema(), "minItems": N, "maxItems": N })
+    }
+}
+
+/// A field of an object schema.
+pub(crate) struct Field {
+    name: &'static str,
+    schema: Value,
+    required: bool,
+}
+
+pub(crate) fn required<T: JsonSchema>(name: &'static str) -> Field {
+    Field {
+        name,
+        schema: T::schema(),
+        required: true,
+    }
+}
+
+pub(crate) fn optional<T: JsonSchema>(name: &'static str) -> Field {
+    Field {
+        name,
+        schema: T::schema(),
+        required: false,
+    }
+}
+
+pub(crate) fn object(fields: Vec<Field>) -> Value {
+    let required: Vec<&str> = fields
+        .iter()
+        .filter(|f| f.required)
+        .map(|f| f.name)
+        .collect();
+    let properties: Map<String, Value> = fields
+        .into_iter()
+        .map(|f| (f.name.to_string(), f.schema))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+pub(crate) fn string_enum(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+/// JSON Schema of the `solve` request.
+///
+/// Its `additionalProperties: false` describes what a client should send;
+/// the solver itself is lenient and `parse_solve_request` only warns about
+/// undeclared fields, as the schema's `description` notes.
+pub fn request_schema() -> Value {
+    with_header(
+        SolveRequest::schema(),
+        "PoseSolve solve request",
+        "Undeclared fields are not rejected by the solver: they are ignored, \
+         each with a warning in the response's diagnostics.warnings.",
+    )
+}
+
+/// JSON Schema of the `solve` response.
+pub fn response_schema() -> Value {
+    with_header(
+        SolveResponse::schema(),
+        "PoseSolve solve response",
+        "Result of a successful solve.",
+    )
+}
+
+fn with_header(mut schema: Value, title: &str, description: &str) -> Value {
+    let body = schema.as_object_mut().expect("object schema");
+    let mut out = Map::new();
+    out.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    out.insert("title".to_string(), json!(title));
+    out.insert("description".to_string(), json!(description));
+    out.insert("x-schemaVersion".to_string(), json!(SCHEMA_VERSION));
+    out.append(body);
+    Value::Object(out)
+}
+
+// ── Parsing and migration ───────────────────────────────────────────────────
+
+/// Parse a `solve` request: migrate it from its `schemaVersion` to
+/// `SCHEMA_VERSION`, then deserialise it.  Fields the schema does not know
+/// are ignored, each with a warning for the response.
+pub fn parse_solve_request(req_json: &str) -> Result<(SolveRequest, Vec<String>), SolveError> {
+    let mut value: Value = serde_json::from_str(req_json)?;
+    migrate(&mut value)?;
+    let warnings = unknown_fields(&value, &SolveRequest::schema())
+        .into_iter()
+        .map(|path| format!("Ignored unknown request field `{path}`."))
+        .collect();
+    let req = serde_json::from_value(value)?;
+    Ok((req, warnings))
+}
+
+/// Bring a request to `SCHEMA_VERSION`.  Requests without `schemaVersion`
+/// are version 0; newer versions than this solver knows are rejected.
+fn migrate(value: &mut Value) -> Result<(), SolveError> {
+    let Some(obj) = value.as_object_mut() else {
+        // Not an object: leave the error to the typed deserialisation.
+        return Ok(());
+    };
+    let found = match obj.get("schemaVersion") {
+        None => 0,
+        Some(v) => v.as_u64().ok_or_else(|| SolveError::InvalidInput {
+            field: "schemaVersion".to_string(),
+            message: "schemaVersion must be a non-negative integer".to_string(),
+        })?,
+    };
+    if found > u64::from(SCHEMA_VERSION) {
+        return Err(SolveError::UnsupportedSchemaVersion {
+            found,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    for step in &MIGRATIONS[found as usize..] {
+        step(value);
+    }
+    value["schemaVersion"] = json!(SCHEMA_VERSION);
+    Ok(())
+}
+
+/// Version 0 → 1: `refine.huberDelta` became `refine.lossScale` when the
+/// Cauchy and Tukey kernels were added.
+fn migrate_v0_to_v1(value: &mut Value) {
+    if let Some(refine) = value.get_mut("refine").and_then(Value::as_object_mut) {
+        if let Some(delta) = refine.remove("huberDelta") {
+            refine.entry("lossScale").or_insert(delta);
+        }
+    }
+}
+
+/// Paths (`priors.foo`, `correspondences[2].world.bar`) of all object keys
+/// in `value` that `schema` does not declare.
+pub(crate) fn unknown_fields(value: &Value, schema: &Value) -> Vec<String> {
+    let mut out = Vec::new();
+    collect_unknown(value, schema, "", &mut out);
+    out
+}
+
+fn collect_unknown(value: &Value, schema: &Value, path: &str, out: &mut Vec<String>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match value {
+        Value::Object(obj) => {
+            let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+                return;
+            };
+            for (key, v) in obj {
+                match properties.get(key) {
+                    Some(s) => collect_unknown(v, s, &join(key), out),
+                    None => out.push(join(key)),
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(s) = schema.get("items") {
+                for (i, v) in items.iter().enumerate() {
+                    collect_unknown(v, s, &format!("{path}[{i}]"), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,128 @@
+use serde_json::Value;
+
+use crate::batch::catch_panic;
+use crate::{solve_batch, solve_json, BatchOptions, BatchSummary, SolveError, SolverMode};
+
+use super::mode_tests::request_json;
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn run(input: &str, opts: &BatchOptions) -> (Vec<Value>, BatchSummary) {
+    run_bytes(input.as_bytes(), opts)
+}
+
+fn run_bytes(input: &[u8], opts: &BatchOptions) -> (Vec<Value>, BatchSummary) {
+    let mut out = Vec::new();
+    let summary = solve_batch(input, &mut out, opts).expect("batch must not fail");
+    let lines = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("every output line must be JSON"))
+        .collect();
+    (lines, summary)
+}
+
+fn mixed_input() -> String {
+    let good = request_json();
+    let no_points = r#"{"image": {"width": 4000, "height": 3000}}"#;
+    [&good, "", "{ not json", &good, no_points, &good].join("\n")
+}
+
+// ── Batch solving ───────────────────────────────────────────────────────────
+
+#[test]
+fn failures_become_error_lines() {
+    let (lines, summary) = run(&mixed_input(), &BatchOptions::default());
+    assert_eq!(
+        summary,
+        BatchSummary {
+            solved: 3,
+            failed: 2
+        }
+    );
+    let numbers: Vec<u64> = lines.iter().map(|l| l["line"].as_u64().unwrap()).collect();
+    assert_eq!(numbers, vec![1, 3, 4, 5, 6], "blank line 2 is skipped");
+    assert_eq!(lines[1]["error"]["code"], "invalidJson");
+    assert_eq!(lines[3]["error"]["code"], "noEnabledPoints");
+    for i in [0, 2, 4] {
+        assert!(lines[i].get("error").is_none());
+        assert_eq!(lines[i]["response"]["schemaVersion"], 1);
+    }
+}
+
+#[test]
+fn responses_match_single_solves() {
+    let (lines, _) = run(&request_json(), &BatchOptions::default());
+    let single: Value = serde_json::from_str(&solve_json(&request_json()).unwrap()).unwrap();
+    assert_eq!(
+        lines,
+        vec![serde_json::json!({ "line": 1, "response": single })]
+    );
+}
+
+#[test]
+fn parallel_output_keeps_input_order() {
+    // More requests than one chunk, so several chunks are solved.
+    let input = vec![mixed_input(); 8].join("\n");
+    let sequential = run(
+        &input,
+        &BatchOptions {
+            threads: 1,
+            ..BatchOptions::default()
+        },
+    );
+    let parallel = run(
+        &input,
+        &BatchOptions {
+            threads: 4,
+            ..BatchOptions::default()
+        },
+    );
+    assert_eq!(sequential.1.solved, 24);
+    assert_eq!(sequential, parallel);
+}
+
+#[test]
+fn mode_applies_to_every_request() {
+    let input = request_json().replace("\"mean\":3000.0", "\"mean\":2700.0");
+    let opts = BatchOptions {
+        mode: Some(SolverMode::Focal),
+        threads: 1,
+    };
+    let (lines, _) = run(&input, &opts);
+    let focal = lines[0]["response"]["intrinsics"]["focalPx"]
+        .as_f64()
+        .unwrap();
+    assert!((focal - 3000.0).abs() < 1.0, "focal {focal}");
+}
+
+#[test]
+fn invalid_utf8_fails_only_its_line() {
+    let good = request_json();
+    let mut input = Vec::new();
+    input.extend_from_slice(good.as_bytes());
+    input.extend_from_slice(b"\n{\"image\": \xff}\r\n");
+    input.extend_from_slice(good.as_bytes());
+    let (lines, summary) = run_bytes(&input, &BatchOptions::default());
+
+    assert_eq!(
+        summary,
+        BatchSummary {
+            solved: 2,
+            failed: 1
+        }
+    );
+    assert_eq!(lines[1]["line"], 2);
+    assert_eq!(lines[1]["error"]["code"], "invalidJson");
+    assert_eq!(lines[1]["error"]["details"]["column"], 11);
+    assert_eq!(lines[2]["line"], 3);
+    assert_eq!(lines[2]["response"]["schemaVersion"], 1);
+}
+
+#[test]
+fn panics_become_internal_errors() {
+    let err = catch_panic(|| -> Result<(), SolveError> { panic!("solver bug") }).unwrap_err();
+    assert_eq!(err.code(), "internal");
+    assert_eq!(err.to_string(), "Internal solver error: solver bug");
+    assert_eq!(catch_panic(|| Ok(7)), Ok(7));
+}
@@ -0,0 +1,145 @@
+use crate::types::{
+    BootstrapCfg, BootstrapMethod, SolveRequest, SolveResponse, SolverModel, UncertaintyCfg,
+};
+
+use super::helpers::{camera, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// A scene with a couple of pixels of click error on every point, so the
+/// fit leaves residuals to resample.
+fn noisy_request(n_points: usize, cfg: Option<BootstrapCfg>) -> SolveRequest {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..n_points]);
+    let noise = [(1.5, -0.8), (-2.0, 1.1), (0.7, 2.2), (-1.1, -1.9)];
+    for (i, c) in req.correspondences.iter_mut().enumerate() {
+        let (du, dv) = noise[i % noise.len()];
+        c.pixel.u += du;
+        c.pixel.v += dv;
+    }
+    req.model = Some(SolverModel {
+        estimate_distortion: false,
+        ..SolverModel::default()
+    });
+    req.uncertainty = cfg.map(|b| UncertaintyCfg { bootstrap: Some(b) });
+    req
+}
+
+fn cfg(samples: usize, seed: u64, method: BootstrapMethod) -> BootstrapCfg {
+    BootstrapCfg {
+        enabled: true,
+        samples,
+        seed: Some(seed),
+        method,
+    }
+}
+
+fn std_dev(values: impl Iterator<Item = f64>) -> f64 {
+    let v: Vec<f64> = values.collect();
+    let mean = v.iter().sum::<f64>() / v.len() as f64;
+    (v.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / v.len() as f64).sqrt()
+}
+
+fn north_std_m(response: &SolveResponse) -> f64 {
+    let b = response.bootstrap.as_ref().unwrap();
+    std_dev(b.position_samples.iter().map(|p| p[0])) * 111_320.0
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn bootstrap_is_absent_unless_enabled() {
+    assert!(solve_to_response(noisy_request(8, None))
+        .bootstrap
+        .is_none());
+    let disabled = BootstrapCfg {
+        enabled: false,
+        ..cfg(50, 1, BootstrapMethod::Cases)
+    };
+    assert!(solve_to_response(noisy_request(8, Some(disabled)))
+        .bootstrap
+        .is_none());
+}
+
+#[test]
+fn case_bootstrap_returns_one_sample_per_refit() {
+    let response = solve_to_response(noisy_request(8, Some(cfg(60, 7, BootstrapMethod::Cases))));
+    let b = response.bootstrap.as_ref().unwrap();
+    assert_eq!(b.position_samples.len(), 60);
+    assert_eq!(b.orientation_samples.len(), 60);
+    assert!(b.focal_samples.is_none(), "focal is fixed in this scene");
+
+    let spread = north_std_m(&response);
+    assert!(spread > 0.01 && spread < 100.0, "north σ = {spread} m");
+    for o in &b.orientation_samples {
+        assert!(
+            (o[0] - response.pose.yaw_deg).abs() < 10.0,
+            "yaw sample {}",
+            o[0]
+        );
+    }
+}
+
+#[test]
+fn bootstrap_is_reproducible_for_a_seed() {
+    let a = solve_to_response(noisy_request(6, Some(cfg(30, 42, BootstrapMethod::Cases))));
+    let b = solve_to_response(noisy_request(6, Some(cfg(30, 42, BootstrapMethod::Cases))));
+    let c = solve_to_response(noisy_request(6, Some(cfg(30, 43, BootstrapMethod::Cases))));
+    let samples = |r: &SolveResponse| r.bootstrap.as_ref().unwrap().position_samples.clone();
+    assert_eq!(samples(&a), samples(&b));
+    assert_ne!(samples(&a), samples(&c));
+}
+
+#[test]
+fn residual_bootstrap_spreads_small_point_sets() {
+    let response = solve_to_response(noisy_request(
+        4,
+        Some(cfg(60, 3, BootstrapMethod::Residuals)),
+    ));
+    let b = response.bootstrap.as_ref().unwrap();
+    assert_eq!(b.position_samples.len(), 60);
+    assert!(north_std_m(&response) > 0.01);
+}
+
+#[test]
+fn focal_samples_are_reported_when_focal_is_estimated() {
+    let mut req = noisy_request(8, Some(cfg(20, 5, BootstrapMethod::Residuals)));
+    req.model = Some(SolverModel {
+        estimate_focal: Some(true),
+        estimate_distortion: false,
+        ..SolverModel::default()
+    });
+    let response = solve_to_response(req);
+    let focal = response.bootstrap.unwrap().focal_samples.unwrap();
+    assert_eq!(focal.len(), 20);
+    assert!(focal.iter().all(|f| (f - 3000.0).abs() < 300.0));
+}
+
+#[test]
+fn bootstrap_config_parses_from_json() {
+    let u: UncertaintyCfg = serde_json::from_str(
+        r#"{"bootstrap": {"enabled": true, "samples": 100, "seed": 9, "method": "residuals"}}"#,
+    )
+    .unwrap();
+    let b = u.bootstrap.unwrap();
+    assert!(b.enabled);
+    assert_eq!(b.samples, 100);
+    assert_eq!(b.seed, Some(9));
+    assert_eq!(b.method, BootstrapMethod::Residuals);
+
+    let u: UncertaintyCfg =
+        serde_json::from_str(r#"{"bootstrap": {"enabled": true, "samples": 0}}"#).unwrap();
+    assert_eq!(u.bootstrap.unwrap().method, BootstrapMethod::Cases);
+}
+
+#[test]
+fn bootstrap_serialises_as_sample_clouds() {
+    let response = solve_to_response(noisy_request(6, Some(cfg(5, 1, BootstrapMethod::Cases))));
+    let json = serde_json::to_value(&response).unwrap();
+    let b = &json["bootstrap"];
+    assert_eq!(b["positionSamples"].as_array().unwrap().len(), 5);
+    assert_eq!(b["orientationSamples"][0].as_array().unwrap().len(), 3);
+    assert!(b.get("focalSamples").is_none());
+
+    let plain = serde_json::to_value(solve_to_response(noisy_request(6, None))).unwrap();
+    assert!(plain.get("bootstrap").is_none());
+}
@@ -0,0 +1,237 @@
+use serde_json::{json, Value};
+
+use crate::geo::{enu_to_lla, lla_to_enu};
+use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
+use crate::types::{BundleRequest, BundleResponse};
+use crate::{bundle_adjust_impl, SolveError};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+const REF: (f64, f64, f64) = (51.9080, 4.4700, 0.0);
+const FOCAL: f64 = 3000.0;
+
+/// Camera positions (ENU) and yaw along a street, all level.
+const CAMERAS: [([f64; 3], f64); 3] = [
+    ([0.0, 0.0, 2.0], -5.0),
+    ([6.0, 0.0, 2.0], 0.0),
+    ([12.0, 1.0, 2.0], 5.0),
+];
+
+/// Control points: in front of the first two cameras only.
+const CONTROL: [[f64; 3]; 6] = [
+    [-6.0, 30.0, 0.5],
+    [-2.0, 34.0, 9.0],
+    [1.0, 28.0, 4.0],
+    [4.0, 36.0, 12.0],
+    [6.0, 31.0, 1.0],
+    [-4.0, 38.0, 6.0],
+];
+
+/// Tie points: seen by every camera.
+const TIE: [[f64; 3]; 8] = [
+    [6.0, 35.0, 3.0],
+    [8.0, 40.0, 10.0],
+    [10.0, 33.0, 6.0],
+    [12.0, 38.0, 1.5],
+    [9.0, 45.0, 14.0],
+    [14.0, 36.0, 8.0],
+    [7.0, 30.0, 12.0],
+    [11.0, 42.0, 4.0],
+];
+
+fn lla(enu: [f64; 3]) -> (f64, f64, f64) {
+    enu_to_lla(enu, REF.0, REF.1, REF.2)
+}
+
+fn pixel(camera: usize, point: [f64; 3]) -> Option<(f64, f64)> {
+    let (centre, yaw) = CAMERAS[camera];
+    let intr = CameraIntrinsics {
+        focal_px: FOCAL,
+        cx: 2000.0,
+        cy: 1500.0,
+        k1: 0.0,
+        k2: 0.0,
+        p1: 0.0,
+        p2: 0.0,
+    };
+    let (u, v) = project_point(point, centre, &rotation_enu_to_cam(yaw, 0.0, 0.0), &intr)?;
+    ((0.0..4000.0).contains(&u) && (0.0..3000.0).contains(&v)).then_some((u, v))
+}
+
+/// Request JSON for the street scene: camera 2 only sees tie points.
+/// `noise` shifts every pixel by a deterministic pattern of that size.
+fn scene(noise: f64) -> Value {
+    let mut k = 0usize;
+    let images: Vec<Value> = (0..CAMERAS.len())
+        .map(|c| {
+            let named = CONTROL
+                .iter()
+                .enumerate()
+                .filter(|_| c < 2)
+                .map(|(i, p)| (format!("c{i}"), *p))
+                .chain(TIE.iter().enumerate().map(|(i, p)| (format!("t{i}"), *p)));
+            let observations: Vec<Value> = named
+                .map(|(id, p)| {
+                    let (u, v) = pixel(c, p).unwrap_or_else(|| panic!("{id} outside image {c}"));
+                    k += 1;
+                    let shift = noise * if k.is_multiple_of(2) { 1.0 } else { -1.0 };
+                    json!({ "id": id, "pixel": { "u": u + shift, "v": v - shift } })
+                })
+                .collect();
+            json!({
+                "id": format!("img{c}"),
+                "image": { "width": 4000, "height": 3000 },
+                "focalPx": FOCAL,
+                "observations": observations,
+            })
+        })
+        .collect();
+    let control: Vec<Value> = CONTROL
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let (lat, lon, alt) = lla(*p);
+            json!({ "id": format!("c{i}"), "world": { "lat": lat, "lon": lon, "alt": alt } })
+        })
+        .collect();
+    json!({ "images": images, "controlPoints": control })
+}
+
+fn adjust(req: Value) -> Result<BundleResponse, SolveError> {
+    let req: BundleRequest = serde_json::from_value(req).expect("request must parse");
+    bundle_adjust_impl(&req)
+}
+
+fn enu_of(lat: f64, lon: f64, alt: f64) -> [f64; 3] {
+    lla_to_enu(lat, lon, alt, REF.0, REF.1, REF.2)
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+// ── Recovery ────────────────────────────────────────────────────────────────
+
+#[test]
+fn recovers_cameras_and_tie_points_from_exact_data() {
+    let resp = adjust(scene(0.0)).expect("bundle must succeed");
+    assert_eq!(resp.cameras.len(), 3);
+    for (cam, (centre, yaw)) in resp.cameras.iter().zip(CAMERAS) {
+        let p = &cam.pose;
+        let err = distance(enu_of(p.lat, p.lon, p.alt), centre);
+        assert!(err < 0.01, "{}: position off by {err} m", cam.id);
+        let yaw_err = (p.yaw_deg - yaw + 540.0).rem_euclid(360.0) - 180.0;
+        assert!(yaw_err.abs() < 0.01, "{}: yaw {}", cam.id, p.yaw_deg);
+        assert!(p.pitch_deg.abs() < 0.01 && p.roll_deg.abs() < 0.01);
+    }
+    // img2 sees no control point: it was placed through the tie points.
+    assert_eq!(resp.cameras[2].observations, TIE.len());
+
+    let ties: Vec<_> = resp.points.iter().filter(|p| !p.control).collect();
+    assert_eq!(ties.len(), TIE.len());
+    for (point, truth) in ties.iter().zip(TIE) {
+        let err = distance(enu_of(point.lat, point.lon, point.alt), truth);
+        assert!(err < 0.01, "{}: off by {err} m", point.id);
+        assert_eq!(point.observations, 3);
+    }
+    assert!(resp.diagnostics.rmse_px < 1e-3);
+    assert!(resp.diagnostics.termination.converged());
+    assert!(
+        resp.diagnostics.warnings.is_empty(),
+        "{:?}",
+        resp.diagnostics.warnings
+    );
+}
+
+#[test]
+fn noisy_clicks_leave_small_residuals() {
+    let resp = adjust(scene(0.7)).expect("bundle must succeed");
+    assert!(
+        resp.diagnostics.rmse_px < 1.5,
+        "rmse {}",
+        resp.diagnostics.rmse_px
+    );
+    for (point, truth) in resp.points.iter().filter(|p| !p.control).zip(TIE) {
+        let err = distance(enu_of(point.lat, point.lon, point.alt), truth);
+        assert!(err < 0.5, "{}: off by {err} m", point.id);
+    }
+    for cam in &resp.cameras {
+        assert!(cam.rmse_px < 2.0, "{}: rmse {}", cam.id, cam.rmse_px);
+    }
+}
+
+#[test]
+fn estimates_an_unknown_focal_length() {
+    let mut req = scene(0.0);
+    req["images"][2].as_object_mut().unwrap().remove("focalPx");
+    let resp = adjust(req).expect("bundle must succeed");
+    let focal = resp.cameras[2].intrinsics.focal_px;
+    assert!((focal - FOCAL).abs() < 1.0, "focal {focal}");
+    assert_eq!(resp.cameras[0].intrinsics.focal_px, FOCAL);
+}
+
+#[test]
+fn soft_control_points_move_toward_the_images() {
+    // c0 is 2 m off with σ 5 m: the images pull it back.
+    let mut req = scene(0.0);
+    let world = &mut req["controlPoints"][0]["world"];
+    let (lat, lon, alt) = lla([-6.0, 30.0, 2.5]);
+    *world = json!({ "lat": lat, "lon": lon, "alt": alt, "sigmaM": 5.0 });
+    let resp = adjust(req).expect("bundle must succeed");
+    let c0 = resp.points.iter().find(|p| p.id == "c0").unwrap();
+    assert!(c0.control);
+    let err = distance(enu_of(c0.lat, c0.lon, c0.alt), CONTROL[0]);
+    assert!(err < 0.5, "c0 off by {err} m");
+}
+
+// ── Problems ────────────────────────────────────────────────────────────────
+
+#[test]
+fn single_view_tie_points_are_left_out() {
+    let mut req = scene(0.0);
+    let obs = req["images"][0]["observations"].as_array_mut().unwrap();
+    obs.push(json!({ "id": "lonely", "pixel": { "u": 100.0, "v": 100.0 } }));
+    let resp = adjust(req).expect("bundle must succeed");
+    assert!(resp.points.iter().all(|p| p.id != "lonely"));
+    assert_eq!(resp.cameras[0].observations, CONTROL.len() + TIE.len());
+    assert!(
+        resp.diagnostics.warnings[0].contains("lonely"),
+        "{:?}",
+        resp.diagnostics.warnings
+    );
+}
+
+#[test]
+fn unconnected_image_is_degenerate() {
+    let mut req = scene(0.0);
+    let obs = req["images"][2]["observations"].as_array_mut().unwrap();
+    obs.truncate(3);
+    match adjust(req) {
+        Err(SolveError::DegenerateGeometry { reason }) => {
+            assert!(reason.contains("img2"), "{reason}")
+        }
+        other => panic!("expected degenerate geometry, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn invalid_bundles_are_rejected() {
+    let mut req = scene(0.0);
+    req["images"][1]["id"] = json!("img0");
+    assert_eq!(
+        adjust(req).err().unwrap().details()["field"],
+        "images[1].id"
+    );
+
+    let mut req = scene(0.0);
+    req["images"][0]["observations"][3]["pixel"]["u"] = json!(f64::MAX);
+    req["images"][0]["focalPx"] = json!(-1.0);
+    assert_eq!(
+        adjust(req).err().unwrap().details()["field"],
+        "images[0].focalPx"
+    );
+
+    let mut req = scene(0.0);
+    req["controlPoints"] = json!([]);
+    assert_eq!(adjust(req).err().unwrap().code(), "invalidInput");
+}
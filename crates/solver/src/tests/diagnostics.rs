@@ -1,5 +1,3 @@
-use crate::reproject_points;
-
 use super::helpers::{base_request, sample_corr, solve_to_response};
 
 #[test]
@@ -40,8 +38,8 @@ fn covariance_shape_and_labels_are_stable() {
     let req = base_request(vec![sample_corr("p1", 100.0, 200.0, 51.90, 4.46, 20.0)]);
     let response = solve_to_response(req);
 
-    assert_eq!(response.covariance.labels.len(), 10);
-    assert_eq!(response.covariance.matrix.len(), 100);
+    assert_eq!(response.covariance.labels.len(), 13);
+    assert_eq!(response.covariance.matrix.len(), 169);
     assert_eq!(response.covariance.labels[0], "lat");
     assert_eq!(response.covariance.labels[1], "lon");
 }
@@ -96,11 +94,3 @@ fn two_point_warns_about_underdetermined() {
         .any(|w| w.contains("underdetermined")),
         "expected underdetermined warning, got {:?}", response.diagnostics.warnings);
 }
-
-#[test]
-fn reproject_points_returns_not_implemented_warning() {
-    let out = reproject_points("{}".to_string()).expect("reproject must succeed");
-    let value: serde_json::Value = serde_json::from_str(&out).expect("output must be json");
-    assert_eq!(value["pixels"], serde_json::json!([]));
-    assert_eq!(value["warnings"][0], serde_json::json!("Not implemented yet"));
-}
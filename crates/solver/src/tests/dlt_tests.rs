@@ -0,0 +1,144 @@
+use crate::dlt::{dlt, planarity};
+use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
+use crate::types::{SolveRequest, SolverModel};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// An uncalibrated request: no focal prior at all, so the default guess
+/// (0.9 × width = 3600 px) is far from the true 3000 px.
+fn uncalibrated_request(world: &[(f64, f64, f64)]) -> SolveRequest {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), world);
+    req.priors = None;
+    req.model = Some(SolverModel {
+        estimate_focal: Some(false),
+        estimate_distortion: false,
+        estimate_principal_point: false,
+        uncalibrated: true,
+    });
+    req
+}
+
+fn world_points() -> Vec<[f64; 3]> {
+    vec![
+        [-30.0, 110.0, 12.0],
+        [45.0, 140.0, 28.0],
+        [10.0, 80.0, -1.0],
+        [-50.0, 190.0, 35.0],
+        [70.0, 95.0, 4.0],
+        [20.0, 260.0, 55.0],
+        [-15.0, 150.0, 70.0],
+    ]
+}
+
+// ── DLT ─────────────────────────────────────────────────────────────────────
+
+#[allow(clippy::needless_range_loop)]
+#[test]
+fn dlt_recovers_intrinsics_and_pose() {
+    let intr = CameraIntrinsics {
+        focal_px: 2200.0,
+        cx: 1010.0,
+        cy: 590.0,
+        k1: 0.0,
+        k2: 0.0,
+        p1: 0.0,
+        p2: 0.0,
+    };
+    let cam = [12.0, -8.0, 3.0];
+    let rot = rotation_enu_to_cam(25.0, -6.0, 2.0);
+    let world = world_points();
+    let pixels: Vec<[f64; 2]> = world
+        .iter()
+        .map(|&p| {
+            let (u, v) = project_point(p, cam, &rot, &intr).unwrap();
+            [u, v]
+        })
+        .collect();
+
+    let (pose, est) = dlt(&world, &pixels).unwrap();
+    assert!((est.focal_px - 2200.0).abs() < 1e-4, "f = {}", est.focal_px);
+    assert!((est.cx - 1010.0).abs() < 1e-4, "cx = {}", est.cx);
+    assert!((est.cy - 590.0).abs() < 1e-4, "cy = {}", est.cy);
+    for k in 0..3 {
+        assert!((pose.cam[k] - cam[k]).abs() < 1e-5);
+        for c in 0..3 {
+            assert!((pose.rot[k][c] - rot[k][c]).abs() < 1e-8);
+        }
+    }
+}
+
+#[test]
+fn dlt_needs_six_points() {
+    let world = world_points();
+    let pixels = vec![[0.0, 0.0]; 5];
+    assert!(dlt(&world[..5], &pixels).is_none());
+}
+
+#[test]
+fn planarity_separates_planes_from_volumes() {
+    let mut world = world_points();
+    assert!(planarity(&world) > 0.1);
+    for p in &mut world {
+        p[2] = 0.5 * p[0] - 3.0;
+    }
+    assert!(planarity(&world) < 1e-6);
+}
+
+// ── Uncalibrated solve ──────────────────────────────────────────────────────
+
+#[test]
+fn uncalibrated_solve_recovers_focal_without_exif() {
+    let response = solve_to_response(uncalibrated_request(&SCENE));
+
+    assert!(
+        (response.intrinsics.focal_px - 3000.0).abs() < 3.0,
+        "focal = {:.1}",
+        response.intrinsics.focal_px
+    );
+    assert!((response.intrinsics.cx - 2000.0).abs() < 2.0);
+    assert!((response.intrinsics.cy - 1500.0).abs() < 2.0);
+    let cam = camera();
+    let err = haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon);
+    assert!(err < 1.0, "position error {:.2} m", err);
+    assert!(
+        !response
+            .diagnostics
+            .warnings
+            .iter()
+            .any(|w| w.contains("DLT") || w.contains("coplanar")),
+        "{:?}",
+        response.diagnostics.warnings
+    );
+}
+
+#[test]
+fn uncalibrated_solve_warns_about_coplanar_points() {
+    let flat: Vec<(f64, f64, f64)> = SCENE.iter().map(|&(la, lo, _)| (la, lo, 0.0)).collect();
+    let response = solve_to_response(uncalibrated_request(&flat));
+    assert!(
+        response
+            .diagnostics
+            .warnings
+            .iter()
+            .any(|w| w.contains("coplanar")),
+        "{:?}",
+        response.diagnostics.warnings
+    );
+}
+
+#[test]
+fn uncalibrated_solve_warns_with_too_few_points() {
+    let response = solve_to_response(uncalibrated_request(&SCENE[..5]));
+    assert!(response.diagnostics.warnings[0].contains("at least 6"));
+}
+
+#[test]
+fn uncalibrated_flag_parses_from_json() {
+    let model: SolverModel = serde_json::from_str(r#"{"uncalibrated": true}"#).unwrap();
+    assert!(model.uncalibrated);
+    assert!(model.estimate_distortion);
+    let model: SolverModel = serde_json::from_str("{}").unwrap();
+    assert!(!model.uncalibrated);
+}
@@ -0,0 +1,121 @@
+use crate::{solve_json, SolveError};
+
+use super::helpers::{
+    base_request, camera, sample_corr, solve_impl_error, synthetic_request, SCENE,
+};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn error_json(req_json: &str) -> serde_json::Value {
+    let err = solve_json(req_json).expect_err("solve must fail");
+    serde_json::from_str(&err).expect("error must be JSON")
+}
+
+// ── Error kinds ─────────────────────────────────────────────────────────────
+
+#[test]
+fn invalid_json_reports_its_position() {
+    let json = error_json("{\n  \"image\": ");
+    assert_eq!(json["code"], "invalidJson");
+    assert!(json["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Invalid request JSON"));
+    assert_eq!(json["details"]["line"], 2);
+    assert!(json["details"]["column"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn request_without_enabled_points_is_coded() {
+    let json = error_json(r#"{"image": {"width": 4000, "height": 3000}, "correspondences": []}"#);
+    assert_eq!(json["code"], "noEnabledPoints");
+    assert!(json["details"].is_null());
+}
+
+#[test]
+fn non_finite_input_names_the_field() {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..4]);
+    req.correspondences[2].pixel.v = f64::NAN;
+    assert_eq!(
+        solve_impl_error(req),
+        SolveError::NonFiniteInput {
+            field: "correspondences[2].pixel.v".to_string()
+        }
+    );
+
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..4]);
+    req.correspondences[1].world.alt = Some(f64::INFINITY);
+    let err = solve_impl_error(req);
+    assert_eq!(err.code(), "nonFiniteInput");
+    assert_eq!(err.details()["field"], "correspondences[1].world.alt");
+}
+
+#[test]
+fn disabled_points_are_not_checked() {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..4]);
+    req.correspondences[0].pixel.u = f64::NAN;
+    req.correspondences[0].enabled = Some(false);
+    assert!(crate::solve_impl(&req).is_ok());
+}
+
+#[test]
+fn coincident_points_are_degenerate() {
+    let req = base_request(vec![
+        sample_corr("a", 100.0, 200.0, 51.91, 4.47, 20.0),
+        sample_corr("b", 900.0, 700.0, 51.91, 4.47, 20.0),
+        sample_corr("c", 1500.0, 300.0, 51.91, 4.47, 20.0),
+    ]);
+    let err = solve_impl_error(req);
+    assert_eq!(err.code(), "degenerateGeometry");
+    assert!(err.details()["reason"]
+        .as_str()
+        .unwrap()
+        .contains("world point"));
+
+    let req = base_request(vec![
+        sample_corr("a", 500.0, 500.0, 51.910, 4.470, 20.0),
+        sample_corr("b", 500.0, 500.0, 51.911, 4.472, 30.0),
+    ]);
+    assert!(solve_impl_error(req).to_string().contains("one pixel"));
+}
+
+// ── Serialisation ───────────────────────────────────────────────────────────
+
+#[test]
+fn every_error_serialises_with_code_message_and_details() {
+    for err in [
+        SolveError::NoEnabledPoints,
+        SolveError::SingularCovariance,
+        SolveError::NoIntersection,
+        SolveError::DegenerateGeometry {
+            reason: "x".to_string(),
+        },
+        SolveError::InvalidInput {
+            field: "intrinsics.focalPx".to_string(),
+            message: "Focal length must be positive".to_string(),
+        },
+        SolveError::Internal {
+            message: "index out of bounds".to_string(),
+        },
+    ] {
+        let json: serde_json::Value = serde_json::from_str(&err.to_json()).unwrap();
+        assert_eq!(json["code"], err.code());
+        assert_eq!(json["message"], err.to_string());
+        assert_eq!(json["details"], err.details());
+        assert_eq!(json.as_object().unwrap().len(), 3);
+    }
+}
+
+#[test]
+fn successful_solve_json_returns_the_response() {
+    let req = r#"{
+        "image": {"width": 4000, "height": 3000},
+        "correspondences": [
+            {"id": "a", "pixel": {"u": 1000, "v": 1400}, "world": {"lat": 51.911, "lon": 4.466, "alt": 40}},
+            {"id": "b", "pixel": {"u": 2000, "v": 900}, "world": {"lat": 51.912, "lon": 4.470, "alt": 80}},
+            {"id": "c", "pixel": {"u": 3000, "v": 1200}, "world": {"lat": 51.9115, "lon": 4.474, "alt": 60}}
+        ]
+    }"#;
+    let out: serde_json::Value = serde_json::from_str(&solve_json(req).unwrap()).unwrap();
+    assert!(out["pose"]["lat"].is_f64());
+}
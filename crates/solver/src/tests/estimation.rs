@@ -54,6 +54,10 @@ fn bounds_prior_clamps_position() {
             lon_max: 4.456,
         }),
         distortion: None,
+        position: None,
+        yaw_deg: None,
+        pitch_deg: None,
+        roll_deg: None,
     });
 
     let response = solve_to_response(req);
@@ -76,9 +80,13 @@ fn camera_alt_prior_overrides_default_alt_logic() {
     let mut req = base_request(vec![sample_corr("p1", 100.0, 200.0, 51.90, 4.46, 40.0)]);
     req.priors = Some(Priors {
         focal_px: None,
-        camera_alt: Some(GaussianPrior { mean: 123.0 }),
+        camera_alt: Some(GaussianPrior { mean: 123.0, sigma: None }),
         bounds: None,
         distortion: None,
+        position: None,
+        yaw_deg: None,
+        pitch_deg: None,
+        roll_deg: None,
     });
     let response = solve_to_response(req);
 
@@ -97,10 +105,14 @@ fn default_alt_uses_world_alt_minus_two_meters() {
 fn focal_prior_overrides_default_focal() {
     let mut req = base_request(vec![sample_corr("p1", 100.0, 200.0, 51.90, 4.46, 40.0)]);
     req.priors = Some(Priors {
-        focal_px: Some(GaussianPrior { mean: 1777.0 }),
+        focal_px: Some(GaussianPrior { mean: 1777.0, sigma: None }),
         camera_alt: None,
         bounds: None,
         distortion: None,
+        position: None,
+        yaw_deg: None,
+        pitch_deg: None,
+        roll_deg: None,
     });
     let response = solve_to_response(req);
 
@@ -125,10 +137,14 @@ fn multipoint_with_focal_prior_gives_finite_result() {
         sample_corr("p3", 500.0, 300.0, 51.91, 4.48, 30.0),
     ]);
     req.priors = Some(Priors {
-        focal_px: Some(GaussianPrior { mean: 2500.0 }),
+        focal_px: Some(GaussianPrior { mean: 2500.0, sigma: None }),
         camera_alt: None,
         bounds: None,
         distortion: None,
+        position: None,
+        yaw_deg: None,
+        pitch_deg: None,
+        roll_deg: None,
     });
     let response = solve_to_response(req);
 
@@ -147,9 +163,13 @@ fn multipoint_with_altitude_prior_stays_near_prior() {
     ]);
     req.priors = Some(Priors {
         focal_px: None,
-        camera_alt: Some(GaussianPrior { mean: 5.0 }),
+        camera_alt: Some(GaussianPrior { mean: 5.0, sigma: None }),
         bounds: None,
         distortion: None,
+        position: None,
+        yaw_deg: None,
+        pitch_deg: None,
+        roll_deg: None,
     });
     let response = solve_to_response(req);
 
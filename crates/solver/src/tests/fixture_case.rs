@@ -1,5 +1,5 @@
 use super::helpers::haversine_m;
-use crate::solve_impl;
+use crate::{solve_impl, SCHEMA_VERSION};
 use crate::types::{Corr, GaussianPrior, Image, Pixel, Priors, SolveRequest, WorldLla};
 
 /// Integration test: Coolhaven / Erasmus MC Rotterdam skyline.
@@ -36,6 +36,7 @@ fn coolhaven_pipeline_native_types_within_100m() {
                 lat: 51.910523080604555,
                 lon: 4.468806982040406,
                 alt: Some(108.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -50,6 +51,7 @@ fn coolhaven_pipeline_native_types_within_100m() {
                 lat: 51.90841172118256,
                 lon: 4.488505125045777,
                 alt: Some(135.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -64,23 +66,33 @@ fn coolhaven_pipeline_native_types_within_100m() {
                 lat: 51.90652200342031,
                 lon: 4.487367868423463,
                 alt: Some(149.0),
+                sigma_m: None,
             },
         },
     ];
 
     // ── Build request with native types (no JSON parsing) ───────────────
     let req = SolveRequest {
+        schema_version: SCHEMA_VERSION,
         image: Image {
             width: image_width,
             height: image_height,
         },
         correspondences,
         priors: Some(Priors {
-            focal_px: Some(GaussianPrior { mean: focal_px }),
+            focal_px: Some(GaussianPrior { mean: focal_px, sigma: None }),
             camera_alt: None,
             bounds: None,
             distortion: None,
+            position: None,
+            yaw_deg: None,
+            pitch_deg: None,
+            roll_deg: None,
         }),
+        ransac: None,
+        refine: None,
+        uncertainty: None,
+        model: None,
     };
 
     // ── Solve ───────────────────────────────────────────────────────────
@@ -126,7 +138,7 @@ fn coolhaven_pipeline_native_types_within_100m() {
 /// Tolerance: ≤ 100 m (haversine). A 10 m bound is unreachable for this
 /// scene without a distortion-aware projection model.
 #[test]
-fn Pathe() {
+fn pathe() {
     // ── EXIF-derived image parameters ───────────────────────────────────
     // Camera: Google Pixel 3
     // Image size: 4032 × 3024 px
@@ -151,6 +163,7 @@ fn Pathe() {
                 lat: 51.92084017728986,
                 lon: 4.473640322685243,
                 alt: Some(108.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -165,6 +178,7 @@ fn Pathe() {
                 lat: 51.92351998447844,
                 lon: 4.471478462219239,
                 alt: Some(135.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -179,6 +193,7 @@ fn Pathe() {
                 lat: 51.92238191306782,
                 lon: 4.471971988677979,
                 alt: Some(110.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -193,6 +208,7 @@ fn Pathe() {
                 lat: 51.920757464646464,
                 lon: 4.473221898078919,
                 alt: Some(20.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -207,23 +223,33 @@ fn Pathe() {
                 lat: 51.92116440938245,
                 lon: 4.47271227836609,
                 alt: Some(68.0),
+                sigma_m: None,
             },
         },
     ];
 
     // ── Build request with native types (no JSON parsing) ───────────────
     let req = SolveRequest {
+        schema_version: SCHEMA_VERSION,
         image: Image {
             width: image_width,
             height: image_height,
         },
         correspondences,
         priors: Some(Priors {
-            focal_px: Some(GaussianPrior { mean: focal_px }),
+            focal_px: Some(GaussianPrior { mean: focal_px, sigma: None }),
             camera_alt: None,
             bounds: None,
             distortion: None,
+            position: None,
+            yaw_deg: None,
+            pitch_deg: None,
+            roll_deg: None,
         }),
+        ransac: None,
+        refine: None,
+        uncertainty: None,
+        model: None,
     };
 
     // ── Solve ───────────────────────────────────────────────────────────
@@ -254,7 +280,7 @@ fn Pathe() {
 }
 
 #[test]
-fn Maas() {
+fn maas() {
     // ── EXIF-derived image parameters ───────────────────────────────────
     // Camera: Google Pixel 3
     // Image size: 4032 × 3024 px
@@ -279,6 +305,7 @@ fn Maas() {
                 lat: 51.908407170731785,
                 lon: 4.4884439705492705,
                 alt: Some(133.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -293,6 +320,7 @@ fn Maas() {
                 lat: 51.90653193209267,
                 lon: 4.487362504005433,
                 alt: Some(149.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -307,6 +335,7 @@ fn Maas() {
                 lat: 51.90745197954069,
                 lon: 4.489266872406007,
                 alt: Some(98.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -321,6 +350,7 @@ fn Maas() {
                 lat: 51.91639663828099,
                 lon: 4.491353631019593,
                 alt: Some(4.2),
+                sigma_m: None,
             },
         },
         Corr {
@@ -335,6 +365,7 @@ fn Maas() {
                 lat: 51.90680331495428,
                 lon: 4.489020109176637,
                 alt: Some(150.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -349,6 +380,7 @@ fn Maas() {
                 lat: 51.91016236948932,
                 lon: 4.488526582717896,
                 alt: Some(17.0),
+                sigma_m: None,
             },
         },
         Corr {
@@ -363,23 +395,33 @@ fn Maas() {
                 lat: 51.90727657626541,
                 lon: 4.48969602584839,
                 alt: Some(92.0),
+                sigma_m: None,
             },
         },
     ];
 
     // ── Build request with native types (no JSON parsing) ───────────────
     let req = SolveRequest {
+        schema_version: SCHEMA_VERSION,
         image: Image {
             width: image_width,
             height: image_height,
         },
         correspondences,
         priors: Some(Priors {
-            focal_px: Some(GaussianPrior { mean: focal_px }),
+            focal_px: Some(GaussianPrior { mean: focal_px, sigma: None }),
             camera_alt: None,
             bounds: None,
             distortion: None,
+            position: None,
+            yaw_deg: None,
+            pitch_deg: None,
+            roll_deg: None,
         }),
+        ransac: None,
+        refine: None,
+        uncertainty: None,
+        model: None,
     };
 
     // ── Solve ───────────────────────────────────────────────────────────
@@ -0,0 +1,136 @@
+use crate::types::{GaussianPrior, SolveRequest, SolveResponse, SolverModel};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+const TRUE_FOCAL: f64 = 3000.0;
+
+/// Eight well-spread points rendered with `TRUE_FOCAL`, but with the focal
+/// prior replaced by `prior_focal`.
+fn request_with_prior(prior_focal: f64, model: SolverModel) -> SolveRequest {
+    let mut req = synthetic_request(&camera(), TRUE_FOCAL, (4000.0, 3000.0), &SCENE);
+    req.priors.as_mut().unwrap().focal_px = Some(GaussianPrior {
+        mean: prior_focal,
+        sigma: None,
+    });
+    req.model = Some(model);
+    req
+}
+
+fn position_error_m(response: &SolveResponse) -> f64 {
+    let cam = camera();
+    haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon)
+}
+
+fn focal_variance(response: &SolveResponse) -> f64 {
+    let n = response.covariance.labels.len();
+    let i = response
+        .covariance
+        .labels
+        .iter()
+        .position(|l| l == "focalPx")
+        .expect("covariance must have a focalPx entry");
+    response.covariance.matrix[i * n + i]
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn estimate_focal_recovers_true_focal_from_wrong_prior() {
+    let response = solve_to_response(request_with_prior(
+        3300.0,
+        SolverModel {
+            estimate_focal: Some(true),
+            estimate_distortion: false,
+            estimate_principal_point: false,
+            uncalibrated: false,
+        },
+    ));
+
+    let focal_err = (response.intrinsics.focal_px - TRUE_FOCAL).abs();
+    assert!(
+        focal_err < 0.01 * TRUE_FOCAL,
+        "focal should be recovered, got {:.1} px",
+        response.intrinsics.focal_px
+    );
+    assert!(
+        position_error_m(&response) < 1.0,
+        "position error {:.2} m",
+        position_error_m(&response)
+    );
+    assert!(response.diagnostics.rmse_px < 0.5);
+    assert!(focal_variance(&response) > 0.0);
+}
+
+#[test]
+fn fixed_focal_is_reported_unchanged_with_zero_variance() {
+    let response = solve_to_response(request_with_prior(
+        3300.0,
+        SolverModel {
+            estimate_focal: Some(false),
+            estimate_distortion: false,
+            estimate_principal_point: false,
+            uncalibrated: false,
+        },
+    ));
+
+    assert_eq!(response.intrinsics.focal_px, 3300.0);
+    assert_eq!(focal_variance(&response), 0.0);
+}
+
+#[test]
+fn disabled_distortion_stays_at_prior() {
+    let response = solve_to_response(request_with_prior(
+        3300.0,
+        SolverModel {
+            estimate_focal: Some(false),
+            estimate_distortion: false,
+            estimate_principal_point: false,
+            uncalibrated: false,
+        },
+    ));
+
+    assert_eq!(response.intrinsics.k1, 0.0);
+    assert_eq!(response.intrinsics.k2, 0.0);
+    assert_eq!(response.intrinsics.p1, 0.0);
+    assert_eq!(response.intrinsics.p2, 0.0);
+}
+
+#[test]
+fn estimate_focal_with_three_points_warns_and_keeps_prior() {
+    let mut req = request_with_prior(
+        3300.0,
+        SolverModel {
+            estimate_focal: Some(true),
+            estimate_distortion: true,
+            estimate_principal_point: false,
+            uncalibrated: false,
+        },
+    );
+    req.correspondences.truncate(3);
+    let response = solve_to_response(req);
+
+    assert_eq!(response.intrinsics.focal_px, 3300.0);
+    assert!(response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.contains("Focal length estimation needs at least 4")));
+}
+
+#[test]
+fn model_json_defaults_keep_distortion_on() {
+    let model: SolverModel = serde_json::from_str(r#"{"estimateFocal": true}"#).unwrap();
+    assert_eq!(model.estimate_focal, Some(true));
+    assert!(model.estimate_distortion);
+
+    let req: SolveRequest = serde_json::from_str(
+        r#"{
+            "image": {"width": 100, "height": 100},
+            "correspondences": []
+        }"#,
+    )
+    .unwrap();
+    assert!(req.model.is_none());
+}
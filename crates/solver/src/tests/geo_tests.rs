@@ -209,5 +209,5 @@ fn bearing_due_west() {
 #[test]
 fn bearing_always_in_0_360() {
     let b = bearing_degrees(0.0, 0.0, -1.0, -1.0);
-    assert!(b >= 0.0 && b < 360.0, "bearing {} out of range", b);
+    assert!((0.0..360.0).contains(&b), "bearing {} out of range", b);
 }
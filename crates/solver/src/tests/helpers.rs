@@ -1,6 +1,9 @@
-use crate::solve_impl;
+use crate::geo::{enu_to_lla, lla_to_enu};
+use crate::optimizer::EnuCorrespondence;
+use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
+use crate::{solve_impl, SolveError, SCHEMA_VERSION};
 use crate::types::{
-    Corr, Image, Pixel, SolveRequest, SolveResponse, WorldLla,
+    Corr, GaussianPrior, Image, Pixel, Pose, Priors, SolveRequest, SolveResponse, WorldLla,
 };
 
 pub(crate) fn sample_corr(id: &str, u: f64, v: f64, lat: f64, lon: f64, alt: f64) -> Corr {
@@ -16,18 +19,24 @@ pub(crate) fn sample_corr(id: &str, u: f64, v: f64, lat: f64, lon: f64, alt: f64
             lat,
             lon,
             alt: Some(alt),
+            sigma_m: None,
         },
     }
 }
 
 pub(crate) fn base_request(correspondences: Vec<Corr>) -> SolveRequest {
     SolveRequest {
+        schema_version: SCHEMA_VERSION,
         image: Image {
             width: 4000.0,
             height: 3000.0,
         },
         correspondences,
         priors: None,
+        ransac: None,
+        refine: None,
+        uncertainty: None,
+        model: None,
     }
 }
 
@@ -35,10 +44,10 @@ pub(crate) fn solve_to_response(req: SolveRequest) -> SolveResponse {
     solve_impl(&req).expect("solve must succeed")
 }
 
-pub(crate) fn solve_impl_error(req: SolveRequest) -> String {
+pub(crate) fn solve_impl_error(req: SolveRequest) -> SolveError {
     match solve_impl(&req) {
         Ok(_) => panic!("expected solve_impl to fail"),
-        Err(msg) => msg,
+        Err(e) => e,
     }
 }
 
@@ -51,3 +60,113 @@ pub(crate) fn haversine_m(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
     r * c
 }
+
+/// Distortion-free camera with its principal point at the centre of an
+/// `image` of (width, height) px.
+pub(crate) fn pinhole(focal_px: f64, image: (f64, f64)) -> CameraIntrinsics {
+    CameraIntrinsics {
+        focal_px,
+        cx: image.0 / 2.0,
+        cy: image.1 / 2.0,
+        k1: 0.0,
+        k2: 0.0,
+        p1: 0.0,
+        p2: 0.0,
+    }
+}
+
+/// Build a noise-free request for a synthetic camera: every world point
+/// `(lat, lon, alt)` is projected through `pose` (`pinhole`) and the true
+/// focal length is passed as a prior.  Points get ids `pt_0`, `pt_1`, …
+pub(crate) fn synthetic_request(
+    pose: &Pose,
+    focal_px: f64,
+    image: (f64, f64),
+    world_pts: &[(f64, f64, f64)],
+) -> SolveRequest {
+    let intr = pinhole(focal_px, image);
+    let rot = rotation_enu_to_cam(pose.yaw_deg, pose.pitch_deg, pose.roll_deg);
+    let correspondences = world_pts
+        .iter()
+        .enumerate()
+        .map(|(i, &(lat, lon, alt))| {
+            let enu = lla_to_enu(lat, lon, alt, pose.lat, pose.lon, pose.alt);
+            let (u, v) = project_point(enu, [0.0; 3], &rot, &intr)
+                .expect("synthetic world points must be in front of the camera");
+            sample_corr(&format!("pt_{i}"), u, v, lat, lon, alt)
+        })
+        .collect();
+
+    let mut req = base_request(correspondences);
+    req.image = Image {
+        width: image.0,
+        height: image.1,
+    };
+    req.priors = Some(Priors {
+        focal_px: Some(GaussianPrior { mean: focal_px, sigma: None }),
+        camera_alt: None,
+        bounds: None,
+        distortion: None,
+        position: None,
+        yaw_deg: None,
+        pitch_deg: None,
+        roll_deg: None,
+    });
+    req
+}
+
+/// Ground-level camera in Rotterdam, 2 m up, facing north and pitched 5°
+/// down: the viewpoint of the shared synthetic scene.
+pub(crate) fn camera() -> Pose {
+    Pose {
+        lat: 51.9080,
+        lon: 4.4700,
+        alt: 2.0,
+        yaw_deg: 0.0,
+        pitch_deg: -5.0,
+        roll_deg: 0.0,
+    }
+}
+
+/// Eight well-spread world points 250–550 m north of [`camera`], 15–100 m
+/// high. Tests that need fewer points take a prefix.
+pub(crate) const SCENE: [(f64, f64, f64); 8] = [
+    (51.9110, 4.4660, 40.0),
+    (51.9120, 4.4700, 80.0),
+    (51.9115, 4.4740, 60.0),
+    (51.9105, 4.4680, 25.0),
+    (51.9130, 4.4720, 100.0),
+    (51.9108, 4.4710, 35.0),
+    (51.9125, 4.4675, 55.0),
+    (51.9102, 4.4725, 15.0),
+];
+
+/// World points `(east, north, alt)` – metres from the ground point under
+/// `cam`, absolute altitude – as `(lat, lon, alt)`.
+pub(crate) fn world_around(cam: &Pose, offsets: &[(f64, f64, f64)]) -> Vec<(f64, f64, f64)> {
+    offsets
+        .iter()
+        .map(|&(e, n, u)| enu_to_lla([e, n, u], cam.lat, cam.lon, 0.0))
+        .collect()
+}
+
+/// The correspondences of `req` in the ENU frame centred on `origin`, the
+/// way the optimiser sees them (pixel σ 1, exact world points).
+pub(crate) fn enu_correspondences(req: &SolveRequest, origin: &Pose) -> Vec<EnuCorrespondence> {
+    req.correspondences
+        .iter()
+        .map(|c| EnuCorrespondence {
+            enu: lla_to_enu(
+                c.world.lat,
+                c.world.lon,
+                c.world.alt.unwrap_or(0.0),
+                origin.lat,
+                origin.lon,
+                origin.alt,
+            ),
+            pixel: [c.pixel.u, c.pixel.v],
+            sigma: 1.0,
+            world_sigma: 0.0,
+        })
+        .collect()
+}
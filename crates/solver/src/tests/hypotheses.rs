@@ -0,0 +1,96 @@
+use crate::types::{SolveRequest, SolverModel};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn request(n_points: usize) -> SolveRequest {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..n_points]);
+    req.model = Some(SolverModel {
+        estimate_distortion: false,
+        ..SolverModel::default()
+    });
+    req
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn three_points_return_several_hypotheses() {
+    let response = solve_to_response(request(3));
+    assert!(
+        response.hypotheses.len() >= 2,
+        "{} hypotheses",
+        response.hypotheses.len()
+    );
+    assert!(response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.starts_with("Ambiguous geometry")));
+    for h in &response.hypotheses {
+        assert!(h.rmse_px < 1.0, "rmse = {}", h.rmse_px);
+        assert_eq!(h.covariance.labels, response.covariance.labels);
+    }
+}
+
+#[test]
+fn first_hypothesis_is_the_solution() {
+    let response = solve_to_response(request(3));
+    let first = &response.hypotheses[0];
+    assert_eq!(first.pose.lat, response.pose.lat);
+    assert_eq!(first.pose.yaw_deg, response.pose.yaw_deg);
+    assert_eq!(first.rmse_px, response.diagnostics.rmse_px);
+    assert_eq!(first.covariance.matrix, response.covariance.matrix);
+    let err = haversine_m(first.pose.lat, first.pose.lon, camera().lat, camera().lon);
+    assert!(err < 0.5, "position error {err:.3} m");
+}
+
+#[test]
+fn hypotheses_are_distinct_and_ordered_by_cost() {
+    let response = solve_to_response(request(3));
+    let h = &response.hypotheses;
+    for i in 0..h.len() {
+        for j in i + 1..h.len() {
+            let d = haversine_m(h[i].pose.lat, h[i].pose.lon, h[j].pose.lat, h[j].pose.lon);
+            let dalt = (h[i].pose.alt - h[j].pose.alt).abs();
+            let dyaw = ((h[i].pose.yaw_deg - h[j].pose.yaw_deg) % 360.0 + 540.0) % 360.0 - 180.0;
+            assert!(
+                d > 1.0 || dalt > 1.0 || dyaw.abs() > 1.0,
+                "{i} and {j} coincide"
+            );
+        }
+    }
+    for w in h[1..].windows(2) {
+        assert!(w[0].cost <= w[1].cost);
+    }
+}
+
+#[test]
+fn well_determined_scene_has_one_hypothesis() {
+    let response = solve_to_response(request(6));
+    assert_eq!(response.hypotheses.len(), 1);
+    assert!(!response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.starts_with("Ambiguous geometry")));
+}
+
+#[test]
+fn single_point_fallback_reports_one_hypothesis() {
+    let response = solve_to_response(request(1));
+    assert_eq!(response.hypotheses.len(), 1);
+    assert_eq!(response.hypotheses[0].pose.lat, response.pose.lat);
+}
+
+#[test]
+fn hypotheses_serialise_in_camel_case() {
+    let json = serde_json::to_value(solve_to_response(request(3))).unwrap();
+    let h = &json["hypotheses"][1];
+    assert!(h["pose"]["yawDeg"].is_f64());
+    assert!(h["intrinsics"]["focalPx"].is_f64());
+    assert!(h["rmsePx"].is_f64());
+    assert!(h["cost"].is_f64());
+    assert_eq!(h["covariance"]["labels"][0], "lat");
+}
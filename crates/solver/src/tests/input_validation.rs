@@ -1,10 +1,31 @@
-use super::helpers::{base_request, sample_corr, solve_impl_error};
+use crate::types::{BootstrapCfg, Corr, GaussianPrior, Priors, RansacCfg, UncertaintyCfg};
+use crate::SolveError;
+
+use super::helpers::{base_request, sample_corr, solve_impl_error, solve_to_response};
+
+fn three_points() -> Vec<Corr> {
+    vec![
+        sample_corr("p1", 1000.0, 1400.0, 51.911, 4.466, 40.0),
+        sample_corr("p2", 2000.0, 900.0, 51.912, 4.470, 80.0),
+        sample_corr("p3", 3000.0, 1200.0, 51.9115, 4.474, 60.0),
+    ]
+}
+
+fn invalid_field(err: SolveError) -> String {
+    match err {
+        SolveError::InvalidInput { field, .. } | SolveError::NonFiniteInput { field } => field,
+        other => panic!("expected a field error, got {other:?}"),
+    }
+}
 
 #[test]
 fn solve_rejects_empty_correspondence_list() {
     let req = base_request(vec![]);
-    let msg = solve_impl_error(req);
-    assert!(msg.contains("Need at least one enabled correspondence"));
+    let err = solve_impl_error(req);
+    assert_eq!(err, SolveError::NoEnabledPoints);
+    assert!(err
+        .to_string()
+        .contains("Need at least one enabled correspondence"));
 }
 
 #[test]
@@ -12,6 +33,168 @@ fn solve_rejects_when_all_points_disabled() {
     let mut corr = sample_corr("p1", 100.0, 200.0, 51.9, 4.46, 12.0);
     corr.enabled = Some(false);
     let req = base_request(vec![corr]);
-    let msg = solve_impl_error(req);
-    assert!(msg.contains("Need at least one enabled correspondence"));
+    let err = solve_impl_error(req);
+    assert_eq!(err, SolveError::NoEnabledPoints);
+    assert!(err
+        .to_string()
+        .contains("Need at least one enabled correspondence"));
+}
+
+#[test]
+fn solve_rejects_non_positive_image_size() {
+    let mut req = base_request(three_points());
+    req.image.width = 0.0;
+    let err = solve_impl_error(req);
+    assert_eq!(err.code(), "invalidInput");
+    assert_eq!(invalid_field(err), "image.width");
+}
+
+#[test]
+fn solve_rejects_out_of_range_coordinates() {
+    let mut corrs = three_points();
+    corrs[1].world.lat = 95.0;
+    let err = solve_impl_error(base_request(corrs));
+    assert!(err.to_string().contains("within [-90, 90]"), "{err}");
+    assert_eq!(invalid_field(err), "correspondences[1].world.lat");
+
+    let mut corrs = three_points();
+    corrs[2].world.lon = -181.0;
+    assert_eq!(
+        invalid_field(solve_impl_error(base_request(corrs))),
+        "correspondences[2].world.lon"
+    );
+}
+
+#[test]
+fn solve_rejects_non_finite_pixels_and_negative_sigmas() {
+    let mut corrs = three_points();
+    corrs[0].pixel.u = f64::NAN;
+    let err = solve_impl_error(base_request(corrs));
+    assert_eq!(err.code(), "nonFiniteInput");
+    assert_eq!(invalid_field(err), "correspondences[0].pixel.u");
+
+    let mut corrs = three_points();
+    corrs[2].pixel.sigma_px = Some(-1.0);
+    assert_eq!(
+        invalid_field(solve_impl_error(base_request(corrs))),
+        "correspondences[2].pixel.sigmaPx"
+    );
+
+    let mut corrs = three_points();
+    corrs[1].world.sigma_m = Some(-0.5);
+    assert_eq!(
+        invalid_field(solve_impl_error(base_request(corrs))),
+        "correspondences[1].world.sigmaM"
+    );
+}
+
+#[test]
+fn solve_rejects_duplicate_ids() {
+    let mut corrs = three_points();
+    corrs[2].id = "p1".to_string();
+    corrs[2].enabled = Some(false);
+    let err = solve_impl_error(base_request(corrs));
+    assert_eq!(invalid_field(err.clone()), "correspondences[2].id");
+    assert!(err.to_string().contains("correspondences[0]"), "{err}");
+}
+
+#[test]
+fn solve_rejects_invalid_priors_and_settings() {
+    let mut req = base_request(three_points());
+    req.priors = Some(Priors {
+        focal_px: Some(GaussianPrior {
+            mean: -100.0,
+            sigma: None,
+        }),
+        ..Priors::default()
+    });
+    assert_eq!(invalid_field(solve_impl_error(req)), "priors.focalPx.mean");
+
+    let mut req = base_request(three_points());
+    req.ransac = Some(RansacCfg {
+        max_iters: 100,
+        inlier_px: 4.0,
+        target_prob: 1.5,
+        seed: None,
+    });
+    assert_eq!(invalid_field(solve_impl_error(req)), "ransac.targetProb");
+}
+
+#[test]
+fn solve_rejects_out_of_range_counts() {
+    let ransac = |max_iters| RansacCfg {
+        max_iters,
+        inlier_px: 4.0,
+        target_prob: 0.99,
+        seed: None,
+    };
+    for max_iters in [0, 1_000_000] {
+        let mut req = base_request(three_points());
+        req.ransac = Some(ransac(max_iters));
+        assert_eq!(invalid_field(solve_impl_error(req)), "ransac.maxIters");
+    }
+
+    let mut req = base_request(three_points());
+    req.uncertainty = Some(UncertaintyCfg {
+        bootstrap: Some(BootstrapCfg {
+            enabled: true,
+            samples: 1_000_000_000,
+            ..BootstrapCfg::default()
+        }),
+    });
+    assert_eq!(
+        invalid_field(solve_impl_error(req)),
+        "uncertainty.bootstrap.samples"
+    );
+}
+
+#[test]
+fn pixels_outside_the_image_are_warned_about() {
+    let mut corrs = three_points();
+    corrs[1].pixel.u = 4200.0;
+    let response = solve_to_response(base_request(corrs));
+    let warnings = &response.diagnostics.warnings;
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.starts_with("correspondences[1].pixel") && w.contains("outside")),
+        "{warnings:?}"
+    );
+}
+
+#[test]
+fn duplicate_and_conflicting_correspondences_are_warned_about() {
+    let mut corrs = three_points();
+    // Same click and landmark as p1, give or take a fraction of a pixel.
+    corrs.push(sample_corr("p4", 1000.4, 1400.3, 51.911, 4.466, 40.0));
+    // Same pixel as p2, different landmark.
+    corrs.push(sample_corr("p5", 2000.0, 900.0, 51.9125, 4.471, 70.0));
+    let response = solve_to_response(base_request(corrs));
+    let warnings = &response.diagnostics.warnings;
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w == "correspondences[0] and correspondences[3] are duplicates (same pixel and world point)."),
+        "{warnings:?}"
+    );
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.starts_with("correspondences[1] and correspondences[4] share a pixel")),
+        "{warnings:?}"
+    );
+}
+
+#[test]
+fn clean_input_raises_no_validation_warnings() {
+    let response = solve_to_response(base_request(three_points()));
+    assert!(
+        !response
+            .diagnostics
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("correspondences[")),
+        "{:?}",
+        response.diagnostics.warnings
+    );
 }
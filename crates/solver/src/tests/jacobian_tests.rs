@@ -0,0 +1,183 @@
+use crate::optimizer::{
+    numerical_jacobian, residuals_and_jacobian, set_rotation, EnuCorrespondence, PosePriors,
+    Problem, CX, FOCAL, NUM_PARAMS,
+};
+use crate::projection::{
+    euler_from_rotation, euler_local_jacobian, mat3_mul, rotation_enu_to_cam, rotation_from_vector,
+    CameraIntrinsics,
+};
+
+use super::helpers::{camera, enu_correspondences, pinhole, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn intrinsics() -> CameraIntrinsics {
+    pinhole(3000.0, (4000.0, 3000.0))
+}
+
+fn corr(enu: [f64; 3], pixel: [f64; 2], sigma: f64) -> EnuCorrespondence {
+    EnuCorrespondence {
+        enu,
+        pixel,
+        sigma,
+        world_sigma: 0.0,
+    }
+}
+
+/// Six scene points around the shared camera at the ENU origin, with
+/// varied pixel σ.
+fn scene() -> Vec<EnuCorrespondence> {
+    let req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..6]);
+    let mut corrs = enu_correspondences(&req, &camera());
+    for (c, sigma) in corrs.iter_mut().zip([1.0, 2.0, 0.5, 1.0, 1.5, 1.0]) {
+        c.sigma = sigma;
+    }
+    corrs
+}
+
+/// A parameter vector with non-trivial angles, distortion and intrinsics.
+fn params() -> [f64; NUM_PARAMS] {
+    let mut p = [0.0; NUM_PARAMS];
+    p[0] = 3.0;
+    p[1] = -5.0;
+    p[2] = 2.0;
+    set_rotation(&mut p, &rotation_enu_to_cam(7.0, -4.0, 2.5));
+    p[6] = -0.12;
+    p[7] = 0.03;
+    p[8] = 0.002;
+    p[9] = -0.001;
+    p[FOCAL] = 2900.0;
+    p[CX] = 2015.0;
+    p[CX + 1] = 1490.0;
+    p
+}
+
+fn fully_free_problem(corrs: &[EnuCorrespondence]) -> Problem<'_> {
+    let priors = PosePriors {
+        position: Some(([1.0, -2.0], 5.0)),
+        alt: Some((4.0, 10.0)),
+        yaw: Some((355.0, 20.0)),
+        pitch: Some((-3.0, 5.0)),
+        roll: Some((0.0, 5.0)),
+    };
+    let mut problem = Problem::new(corrs, &intrinsics(), priors, [-0.1, 0.0, 0.0, 0.0]);
+    problem.estimate_focal = true;
+    problem.focal_prior = Some((3000.0, 300.0));
+    problem.estimate_principal_point = true;
+    problem.principal_point_sigma = Some(400.0);
+    problem
+}
+
+/// Assert the analytic and numerical Jacobians agree entry by entry, with
+/// a tolerance relative to the column scale.
+fn assert_jacobians_match(params: &[f64; NUM_PARAMS], problem: &Problem) {
+    let (res, analytic) = residuals_and_jacobian(params, problem);
+    let numerical = numerical_jacobian(params, problem);
+    assert_eq!(analytic.len(), res.len());
+    assert_eq!(analytic.len(), numerical.len());
+
+    for j in 0..NUM_PARAMS {
+        let scale = numerical
+            .iter()
+            .map(|row| row[j].abs())
+            .fold(1e-6, f64::max);
+        for (i, (a, n)) in analytic.iter().zip(&numerical).enumerate() {
+            assert!(
+                (a[j] - n[j]).abs() <= 1e-5 * scale,
+                "row {i}, column {j}: analytic {} vs numerical {}",
+                a[j],
+                n[j]
+            );
+        }
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn euler_local_jacobian_matches_finite_differences() {
+    for &(yaw, pitch, roll) in &[(33.0, -12.0, 4.0), (200.0, 75.0, -30.0), (10.0, -88.0, 5.0)] {
+        let rot = rotation_enu_to_cam(yaw, pitch, roll);
+        let jac = euler_local_jacobian(&rot);
+        let h = 1e-7;
+        for k in 0..3 {
+            let mut delta = [0.0; 3];
+            delta[k] = h;
+            let plus = euler_from_rotation(&mat3_mul(&rotation_from_vector(&delta), &rot));
+            delta[k] = -h;
+            let minus = euler_from_rotation(&mat3_mul(&rotation_from_vector(&delta), &rot));
+            let fd = [plus.0 - minus.0, plus.1 - minus.1, plus.2 - minus.2].map(|d| d / (2.0 * h));
+            for a in 0..3 {
+                assert!(
+                    (jac[a][k] - fd[a]).abs() < 1e-4 * (1.0 + fd[a].abs()),
+                    "({yaw}, {pitch}, {roll}) angle {a}, axis {k}: {} vs {}",
+                    jac[a][k],
+                    fd[a]
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn analytic_jacobian_matches_numerical_with_every_parameter_free() {
+    let corrs = scene();
+    let problem = fully_free_problem(&corrs);
+    assert!(problem.free().iter().all(|f| *f));
+    assert_jacobians_match(&params(), &problem);
+}
+
+#[test]
+fn analytic_jacobian_matches_numerical_for_pose_only() {
+    let corrs = scene();
+    let problem = Problem::new(&corrs[..4], &intrinsics(), PosePriors::default(), [0.0; 4]);
+    let mut p = params();
+    p[6..10].copy_from_slice(&[0.0; 4]);
+    assert_jacobians_match(&p, &problem);
+}
+
+#[allow(clippy::needless_range_loop)]
+#[test]
+fn frozen_columns_are_zero() {
+    let corrs = scene();
+    let problem = Problem::new(&corrs[..4], &intrinsics(), PosePriors::default(), [0.0; 4]);
+    let (_, jac) = residuals_and_jacobian(&params(), &problem);
+    for row in &jac {
+        for j in 6..NUM_PARAMS {
+            assert_eq!(row[j], 0.0, "column {j} should be frozen");
+        }
+    }
+}
+
+#[test]
+fn behind_camera_penalty_has_matching_gradient() {
+    // One point behind the camera (south of a north-looking camera).
+    let mut corrs = scene();
+    corrs.push(corr([5.0, -30.0, 1.0], [2000.0, 1500.0], 1.0));
+    let problem = fully_free_problem(&corrs);
+    assert_jacobians_match(&params(), &problem);
+}
+
+#[test]
+fn analytic_jacobian_matches_numerical_near_nadir() {
+    let corrs: Vec<EnuCorrespondence> = scene()
+        .into_iter()
+        .map(|c| corr([c.enu[0], c.enu[1] - 100.0, -150.0], c.pixel, c.sigma))
+        .collect();
+    let mut problem = fully_free_problem(&corrs);
+    problem.priors.pitch = Some((-85.0, 5.0));
+    let mut p = params();
+    set_rotation(&mut p, &rotation_enu_to_cam(40.0, -87.0, 12.0));
+    assert_jacobians_match(&p, &problem);
+}
+
+#[test]
+fn roll_regulariser_has_matching_gradient() {
+    // No roll prior: the cos(pitch)-weighted level-horizon regulariser.
+    let corrs = scene();
+    let mut problem = fully_free_problem(&corrs);
+    problem.priors.roll = None;
+    let mut p = params();
+    set_rotation(&mut p, &rotation_enu_to_cam(7.0, -50.0, 20.0));
+    assert_jacobians_match(&p, &problem);
+}
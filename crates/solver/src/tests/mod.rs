@@ -1,9 +1,31 @@
+mod batch_tests;
+mod bootstrap_tests;
+mod bundle_tests;
 mod diagnostics;
+mod dlt_tests;
+mod errors;
 mod estimation;
 mod fixture_case;
+mod focal_estimation;
 mod geo_tests;
 mod helpers;
+mod hypotheses;
 mod input_validation;
+mod jacobian_tests;
+mod mode_tests;
 mod optimizer_tests;
+mod outlier_rejection;
 mod perfect_cases;
+mod pnp_tests;
+mod pose_priors;
+mod principal_point;
 mod projection_tests;
+mod reproject_tests;
+mod robust_loss;
+mod schema_tests;
+mod steep_pitch;
+mod termination;
+mod triangulate_tests;
+mod uncertainty_tests;
+mod unknown_focal;
+mod world_uncertainty;
@@ -0,0 +1,76 @@
+use serde_json::{json, Value};
+
+use crate::{solve_json, solve_request, SolverMode};
+
+use super::helpers::{camera, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// The synthetic scene as request JSON, with `model` asking for nothing
+/// but the pose.
+pub(crate) fn request_json() -> String {
+    let req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..7]);
+    let correspondences: Vec<Value> = req
+        .correspondences
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.id,
+                "pixel": { "u": c.pixel.u, "v": c.pixel.v },
+                "world": { "lat": c.world.lat, "lon": c.world.lon, "alt": c.world.alt },
+            })
+        })
+        .collect();
+    json!({
+        "schemaVersion": 1,
+        "image": { "width": 4000, "height": 3000 },
+        "correspondences": correspondences,
+        "priors": { "focalPx": { "mean": 3000.0 } },
+        "model": { "estimateFocal": false, "estimateDistortion": false },
+    })
+    .to_string()
+}
+
+// ── Solving ─────────────────────────────────────────────────────────────────
+
+/// `solve_request` output as JSON.
+fn solve(req: &str, mode: Option<SolverMode>) -> Value {
+    serde_json::to_value(solve_request(req, mode).expect("solve must succeed")).unwrap()
+}
+
+#[test]
+fn solves_a_request_like_solve_json() {
+    let req = request_json();
+    let resp = solve_request(&req, None).expect("solve must succeed");
+    assert_eq!(
+        serde_json::to_string(&resp).unwrap(),
+        solve_json(&req).unwrap()
+    );
+}
+
+#[test]
+fn mode_overrides_the_request_model() {
+    // The focal prior is 10 % off; only an estimated focal length recovers.
+    let req = request_json().replace("\"mean\":3000.0", "\"mean\":2700.0");
+    let focal_px = |mode| solve(&req, mode)["intrinsics"]["focalPx"].as_f64().unwrap();
+    assert_eq!(focal_px(None), 2700.0);
+
+    let estimated = focal_px(Some(SolverMode::Focal));
+    assert!((estimated - 3000.0).abs() < 1.0, "focal {estimated}");
+}
+
+#[test]
+fn calibrated_mode_keeps_a_missing_focal_fixed() {
+    let mut req: Value = serde_json::from_str(&request_json()).unwrap();
+    req.as_object_mut().unwrap().remove("priors");
+    let resp = solve(&req.to_string(), Some(SolverMode::Calibrated));
+    assert_eq!(resp["intrinsics"]["focalPx"], 3600.0);
+}
+
+#[test]
+fn solve_errors_are_typed() {
+    let Err(err) = solve_request("{", None) else {
+        panic!("invalid JSON must not solve");
+    };
+    assert_eq!(err.code(), "invalidJson");
+}
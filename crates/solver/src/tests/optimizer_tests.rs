@@ -1,5 +1,6 @@
 use crate::optimizer::{
-    estimate_scene_distance, initialize_pose, levenberg_marquardt, EnuCorrespondence, NUM_PARAMS,
+    estimate_scene_distance, euler_from_params, initialize_pose, levenberg_marquardt,
+    params_from_pose, EnuCorrespondence, PosePriors, Problem, NUM_PARAMS,
 };
 use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
 
@@ -21,6 +22,7 @@ fn synthetic_corrs(
                 enu: pt,
                 pixel: [u, v],
                 sigma: 1.0,
+                world_sigma: 0.0,
             })
         })
         .collect()
@@ -68,7 +70,7 @@ fn initialization_produces_finite_values() {
         [0.0, 0.0, 100.0],
     ];
     let corrs = synthetic_corrs(cam, 0.0, -5.0, 0.0, &intr, &pts);
-    let init = initialize_pose(&corrs, &intr, None, [0.0; 4]);
+    let init = initialize_pose(&corrs, &intr, &PosePriors::default(), [0.0; 4]);
     for (i, v) in init.iter().enumerate() {
         assert!(v.is_finite(), "init param {} is not finite: {}", i, v);
     }
@@ -85,8 +87,8 @@ fn initialization_yaw_roughly_correct() {
         [0.0, 0.0, 100.0],
     ];
     let corrs = synthetic_corrs(cam, 0.0, 0.0, 0.0, &intr, &pts);
-    let init = initialize_pose(&corrs, &intr, None, [0.0; 4]);
-    let yaw = init[3];
+    let init = initialize_pose(&corrs, &intr, &PosePriors::default(), [0.0; 4]);
+    let (yaw, _, _) = euler_from_params(&init);
     // Should be roughly north (0° or close to 360°)
     let yaw_err = (yaw - 0.0).abs().min((yaw - 360.0).abs());
     assert!(
@@ -114,8 +116,8 @@ fn lm_converges_from_true_pose() {
     let corrs = synthetic_corrs(true_cam, true_yaw, true_pitch, true_roll, &intr, &pts);
     assert_eq!(corrs.len(), 4, "all points should project");
 
-    let init = [true_cam[0], true_cam[1], true_cam[2], true_yaw, true_pitch, true_roll, 0.0, 0.0, 0.0, 0.0];
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 50);
+    let init = params_from_pose(true_cam, (true_yaw, true_pitch, true_roll), [0.0; 4], &intr);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 50);
 
     assert!(
         result.cost < 1e-10,
@@ -142,16 +144,13 @@ fn lm_converges_from_perturbed_pose() {
     assert_eq!(corrs.len(), 5);
 
     // Start 20m off in position, 5° off in yaw
-    let init = [
-        true_cam[0] + 20.0,
-        true_cam[1] - 15.0,
-        true_cam[2] + 5.0,
-        true_yaw + 5.0,
-        true_pitch - 2.0,
-        true_roll + 1.0,
-        0.0, 0.0, 0.0, 0.0,
-    ];
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 100);
+    let init = params_from_pose(
+        [true_cam[0] + 20.0, true_cam[1] - 15.0, true_cam[2] + 5.0],
+        (true_yaw + 5.0, true_pitch - 2.0, true_roll + 1.0),
+        [0.0; 4],
+        &intr,
+    );
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 100);
 
     assert!(
         result.cost < 1.0,
@@ -193,15 +192,16 @@ fn lm_recovers_yaw_correctly() {
     ];
     let corrs = synthetic_corrs(true_cam, true_yaw, true_pitch, true_roll, &intr, &pts);
     
-    let init = [5.0, -10.0, 2.0, true_yaw + 3.0, true_pitch + 1.0, 0.5, 0.0, 0.0, 0.0, 0.0];
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 100);
+    let init = params_from_pose([5.0, -10.0, 2.0], (true_yaw + 3.0, true_pitch + 1.0, 0.5), [0.0; 4], &intr);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 100);
 
-    let yaw_err = (result.params[3] - true_yaw).abs();
+    let (yaw, _, _) = euler_from_params(&result.params);
+    let yaw_err = (yaw - true_yaw).rem_euclid(360.0);
     let yaw_err = yaw_err.min(360.0 - yaw_err);
     assert!(
         yaw_err < 2.0,
         "yaw should converge: got {}° vs true {}°",
-        result.params[3],
+        yaw,
         true_yaw
     );
 }
@@ -217,8 +217,12 @@ fn lm_with_altitude_prior() {
     ];
     let corrs = synthetic_corrs(true_cam, 0.0, 0.0, 0.0, &intr, &pts);
 
-    let init = [10.0, -450.0, 20.0, 5.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
-    let result = levenberg_marquardt(init, &corrs, &intr, Some(5.0), [0.0; 4], corrs.len(), 100);
+    let init = params_from_pose([10.0, -450.0, 20.0], (5.0, 1.0, 0.0), [0.0; 4], &intr);
+    let priors = PosePriors {
+        alt: Some((5.0, 10.0)),
+        ..Default::default()
+    };
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, priors, [0.0; 4]), 100);
 
     // Altitude should be close to the prior
     assert!(
@@ -242,8 +246,8 @@ fn lm_with_3_points_minimum() {
     let corrs = synthetic_corrs(true_cam, 0.0, -3.0, 0.0, &intr, &pts);
     assert_eq!(corrs.len(), 3);
 
-    let init = [10.0, -750.0, 5.0, 5.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 100);
+    let init = params_from_pose([10.0, -750.0, 5.0], (5.0, -1.0, 0.0), [0.0; 4], &intr);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 100);
 
     assert!(
         result.cost < 10.0,
@@ -263,16 +267,17 @@ fn lm_with_2_points_uses_regularisation() {
     let corrs = synthetic_corrs(true_cam, 0.0, 0.0, 0.0, &intr, &pts);
     assert_eq!(corrs.len(), 2);
 
-    let init = [10.0, -450.0, 5.0, 5.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 100);
+    let init = params_from_pose([10.0, -450.0, 5.0], (5.0, 1.0, 0.0), [0.0; 4], &intr);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 100);
 
     // With 2 points + regularisation, should still produce a reasonable result
     assert!(result.params.iter().all(|v| v.is_finite()));
     // Roll should be close to 0 due to regularisation
+    let (_, _, roll) = euler_from_params(&result.params);
     assert!(
-        result.params[5].abs() < 20.0,
+        roll.abs() < 20.0,
         "roll should be regularised near 0, got {}",
-        result.params[5]
+        roll
     );
 }
 
@@ -302,8 +307,8 @@ fn recover_pose_facing_northeast() {
     assert_eq!(corrs.len(), 5);
 
     // Start from the initialiser
-    let init = initialize_pose(&corrs, &intr, None, [0.0; 4]);
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 150);
+    let init = initialize_pose(&corrs, &intr, &PosePriors::default(), [0.0; 4]);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 150);
 
     assert!(
         result.cost < 1.0,
@@ -346,8 +351,8 @@ fn recover_pose_facing_south() {
     ];
     let corrs = synthetic_corrs(true_cam, true_yaw, true_pitch, true_roll, &intr, &pts);
 
-    let init = initialize_pose(&corrs, &intr, None, [0.0; 4]);
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 150);
+    let init = initialize_pose(&corrs, &intr, &PosePriors::default(), [0.0; 4]);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 150);
 
     assert!(result.cost < 5.0, "should converge, cost={}", result.cost);
 }
@@ -375,8 +380,8 @@ fn recover_pose_with_large_distance() {
     ];
     let corrs = synthetic_corrs(true_cam, true_yaw, true_pitch, true_roll, &intr, &pts);
 
-    let init = initialize_pose(&corrs, &intr, None, [0.0; 4]);
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 150);
+    let init = initialize_pose(&corrs, &intr, &PosePriors::default(), [0.0; 4]);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 150);
 
     assert!(
         result.cost < 5.0,
@@ -401,8 +406,8 @@ fn lm_result_has_valid_jtj() {
         [20.0, 10.0, 60.0],
     ];
     let corrs = synthetic_corrs(true_cam, 10.0, -3.0, 0.0, &intr, &pts);
-    let init = [0.0, -500.0, 5.0, 10.0, -3.0, 0.0, 0.0, 0.0, 0.0, 0.0];
-    let result = levenberg_marquardt(init, &corrs, &intr, None, [0.0; 4], corrs.len(), 50);
+    let init = params_from_pose([0.0, -500.0, 5.0], (10.0, -3.0, 0.0), [0.0; 4], &intr);
+    let result = levenberg_marquardt(init, &Problem::new(&corrs, &intr, PosePriors::default(), [0.0; 4]), 50);
 
     // The diagonal of JtJ should be positive
     for i in 0..NUM_PARAMS {
@@ -0,0 +1,103 @@
+use crate::types::{RansacCfg, SolveRequest};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Frontend defaults (`App.tsx`).
+fn frontend_ransac() -> RansacCfg {
+    RansacCfg {
+        max_iters: 5000,
+        inlier_px: 2.0,
+        target_prob: 0.999,
+        seed: None,
+    }
+}
+
+/// Synthetic request where point `pt_2` has a mistyped latitude
+/// (~330 m north of its true position) but keeps its true pixel.
+fn request_with_outlier() -> SolveRequest {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE);
+    req.correspondences[2].world.lat += 0.003;
+    req
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn ransac_rejects_mistyped_world_point() {
+    let mut req = request_with_outlier();
+    req.ransac = Some(frontend_ransac());
+    let response = solve_to_response(req);
+
+    let cam = camera();
+    let err = haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon);
+    assert!(err < 1.0, "position error {:.2} m, warnings {:?}", err, response.diagnostics.warnings);
+
+    assert_eq!(response.diagnostics.inlier_ids.len(), 7);
+    assert!(!response.diagnostics.inlier_ids.contains(&"pt_2".to_string()));
+    assert!((response.diagnostics.inlier_ratio - 7.0 / 8.0).abs() < 1e-12);
+    assert!(response.diagnostics.rmse_px < 0.1, "rmse {}", response.diagnostics.rmse_px);
+    assert!(response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.contains("RANSAC rejected 1 outlier(s): pt_2")));
+
+    // The outlier keeps a residual so the UI can show how far off it is.
+    assert_eq!(response.diagnostics.residuals_px.len(), 8);
+    assert!(response.diagnostics.residuals_px[2] > 2.0);
+}
+
+#[test]
+fn without_ransac_the_outlier_biases_the_pose() {
+    let response = solve_to_response(request_with_outlier());
+
+    let cam = camera();
+    let err = haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon);
+    assert!(err > 1.0, "outlier should bias the pose, error {:.2} m", err);
+    assert_eq!(response.diagnostics.inlier_ids.len(), 8);
+    assert_eq!(response.diagnostics.inlier_ratio, 1.0);
+}
+
+#[test]
+fn ransac_keeps_every_point_of_a_clean_scene() {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE);
+    req.ransac = Some(frontend_ransac());
+    let response = solve_to_response(req);
+
+    assert_eq!(response.diagnostics.inlier_ids.len(), 8);
+    assert_eq!(response.diagnostics.inlier_ratio, 1.0);
+    assert!(!response.diagnostics.warnings.iter().any(|w| w.contains("RANSAC")));
+}
+
+#[test]
+fn ransac_is_deterministic_for_a_given_seed() {
+    let run = || {
+        let mut req = request_with_outlier();
+        req.ransac = Some(RansacCfg {
+            seed: Some(42),
+            ..frontend_ransac()
+        });
+        solve_to_response(req)
+    };
+    let (a, b) = (run(), run());
+    assert_eq!(a.pose.lat, b.pose.lat);
+    assert_eq!(a.pose.lon, b.pose.lon);
+    assert_eq!(a.diagnostics.inlier_ids, b.diagnostics.inlier_ids);
+}
+
+#[test]
+fn ransac_config_is_read_from_frontend_json() {
+    let json = r#"{
+        "image": { "width": 4000, "height": 3000 },
+        "correspondences": [],
+        "ransac": { "maxIters": 5000, "inlierPx": 2.0, "targetProb": 0.999 }
+    }"#;
+    let req: SolveRequest = serde_json::from_str(json).expect("request must parse");
+    let cfg = req.ransac.expect("ransac must be kept");
+    assert_eq!(cfg.max_iters, 5000);
+    assert_eq!(cfg.inlier_px, 2.0);
+    assert_eq!(cfg.target_prob, 0.999);
+    assert!(cfg.seed.is_none());
+}
@@ -1,6 +1,6 @@
 use crate::geo::lla_to_enu;
 use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
-use crate::solve_impl;
+use crate::{solve_impl, SCHEMA_VERSION};
 use crate::types::{Corr, GaussianPrior, Image, Pixel, Priors, SolveRequest, WorldLla};
 
 use super::helpers::haversine_m;
@@ -16,6 +16,7 @@ use super::helpers::haversine_m;
 ///   3. Builds a `SolveRequest` with a matching focal-length prior.
 ///
 /// The returned pixel coordinates are mathematically perfect — zero noise.
+#[allow(clippy::too_many_arguments)]
 fn build_perfect_request(
     cam_lat: f64,
     cam_lon: f64,
@@ -68,6 +69,7 @@ fn build_perfect_request(
                     lat,
                     lon,
                     alt: Some(alt),
+                    sigma_m: None,
                 },
             })
         })
@@ -82,22 +84,32 @@ fn build_perfect_request(
     );
 
     SolveRequest {
+        schema_version: SCHEMA_VERSION,
         image: Image {
             width: image_width,
             height: image_height,
         },
         correspondences,
         priors: Some(Priors {
-            focal_px: Some(GaussianPrior { mean: focal_px }),
+            focal_px: Some(GaussianPrior { mean: focal_px, sigma: None }),
             camera_alt: None,
             bounds: None,
             distortion: None,
+            position: None,
+            yaw_deg: None,
+            pitch_deg: None,
+            roll_deg: None,
         }),
+        ransac: None,
+        refine: None,
+        uncertainty: None,
+        model: None,
     }
 }
 
 // ── Assertion helpers ───────────────────────────────────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 fn assert_pose_close(
     response: &crate::types::SolveResponse,
     expected_lat: f64,
@@ -0,0 +1,293 @@
+use crate::geo::lla_to_enu;
+use crate::linalg::{polynomial_real_roots, symmetric_eigen};
+use crate::optimizer::{reprojection_errors, EnuCorrespondence, PosePriors, Problem};
+use crate::pnp::{
+    absolute_orientation, bearing, binomial, epnp, epnp_seed, focal_scan_p3p, minimal_seeds, p3p,
+    p3p_candidates,
+};
+use crate::projection::{
+    euler_from_rotation, mat3_vec, project_point, rotation_enu_to_cam, CameraIntrinsics, Mat3,
+};
+
+use super::helpers::{camera, pinhole, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// The shared pinhole camera with radial distortion `k1`.
+fn intrinsics(k1: f64) -> CameraIntrinsics {
+    CameraIntrinsics {
+        k1,
+        ..pinhole(3000.0, (4000.0, 3000.0))
+    }
+}
+
+/// The shared camera sits at the ENU origin.
+const CAM: [f64; 3] = [0.0; 3];
+
+fn rotation() -> Mat3 {
+    let cam = camera();
+    rotation_enu_to_cam(cam.yaw_deg, cam.pitch_deg, cam.roll_deg)
+}
+
+/// The first six scene points in the camera's ENU frame.
+fn world_points() -> Vec<[f64; 3]> {
+    let cam = camera();
+    SCENE[..6]
+        .iter()
+        .map(|&(lat, lon, alt)| lla_to_enu(lat, lon, alt, cam.lat, cam.lon, cam.alt))
+        .collect()
+}
+
+/// Exact correspondences for the shared camera seen through `intr`.
+fn correspondences(intr: &CameraIntrinsics) -> Vec<EnuCorrespondence> {
+    let rot = rotation();
+    world_points()
+        .into_iter()
+        .map(|enu| {
+            let (u, v) = project_point(enu, CAM, &rot, intr).unwrap();
+            EnuCorrespondence {
+                enu,
+                pixel: [u, v],
+                sigma: 1.0,
+                world_sigma: 0.0,
+            }
+        })
+        .collect()
+}
+
+fn rotation_distance(a: &Mat3, b: &Mat3) -> f64 {
+    let mut max: f64 = 0.0;
+    for r in 0..3 {
+        for c in 0..3 {
+            max = max.max((a[r][c] - b[r][c]).abs());
+        }
+    }
+    max
+}
+
+fn position_distance(a: &[f64], b: &[f64]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+// ── Linear algebra ──────────────────────────────────────────────────────────
+
+#[test]
+fn quartic_roots_are_found() {
+    // (x − 1)(x + 2)(x − 3)(x − 0.5)
+    let roots = polynomial_real_roots(&[1.0, -2.5, -4.0, 8.5, -3.0]);
+    let expected = [-2.0, 0.5, 1.0, 3.0];
+    assert_eq!(roots.len(), 4, "{:?}", roots);
+    for (r, e) in roots.iter().zip(expected) {
+        assert!((r - e).abs() < 1e-9, "{:?}", roots);
+    }
+}
+
+#[test]
+fn double_root_is_reported_once() {
+    // (x − 2)² (x² + 1): one touching real root, two complex
+    let roots = polynomial_real_roots(&[1.0, -4.0, 5.0, -4.0, 4.0]);
+    assert_eq!(roots.len(), 1, "{:?}", roots);
+    assert!((roots[0] - 2.0).abs() < 1e-6);
+}
+
+#[test]
+fn vanishing_leading_coefficient_degrades_to_cubic() {
+    // 0·x⁴ + (x − 1)(x − 2)(x − 3)
+    let roots = polynomial_real_roots(&[0.0, 1.0, -6.0, 11.0, -6.0]);
+    assert_eq!(roots.len(), 3);
+    assert!((roots[2] - 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn symmetric_eigen_reconstructs_matrix() {
+    let a = [
+        [4.0, 1.0, -2.0, 0.5],
+        [1.0, 3.0, 0.0, 1.5],
+        [-2.0, 0.0, 5.0, -1.0],
+        [0.5, 1.5, -1.0, 2.0],
+    ];
+    let (values, vectors) = symmetric_eigen(&a);
+    assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    for i in 0..4 {
+        for j in 0..4 {
+            let rebuilt: f64 = (0..4)
+                .map(|k| vectors[i][k] * values[k] * vectors[j][k])
+                .sum();
+            assert!((rebuilt - a[i][j]).abs() < 1e-10, "({i},{j})");
+        }
+    }
+}
+
+// ── Rotations ───────────────────────────────────────────────────────────────
+
+#[test]
+fn euler_angles_round_trip_through_rotation() {
+    for &(yaw, pitch, roll) in &[
+        (0.0, 0.0, 0.0),
+        (25.0, -6.0, 2.0),
+        (190.0, 30.0, -15.0),
+        (359.0, -80.0, 45.0),
+    ] {
+        let (y, p, r) = euler_from_rotation(&rotation_enu_to_cam(yaw, pitch, roll));
+        let dy = ((y - yaw).rem_euclid(360.0) + 180.0).rem_euclid(360.0) - 180.0;
+        assert!(dy.abs() < 1e-9, "yaw {yaw} -> {y}");
+        assert!((p - pitch).abs() < 1e-9, "pitch {pitch} -> {p}");
+        assert!((r - roll).abs() < 1e-9, "roll {roll} -> {r}");
+    }
+}
+
+#[test]
+fn absolute_orientation_recovers_rigid_motion() {
+    let rot = rotation();
+    let world = world_points();
+    let cam_pts: Vec<[f64; 3]> = world
+        .iter()
+        .map(|p| mat3_vec(&rot, &[p[0] - CAM[0], p[1] - CAM[1], p[2] - CAM[2]]))
+        .collect();
+    let pose = absolute_orientation(&world, &cam_pts).unwrap();
+    assert!(rotation_distance(&pose.rot, &rot) < 1e-9);
+    assert!(position_distance(&pose.cam, &CAM) < 1e-8);
+}
+
+#[test]
+fn absolute_orientation_rejects_collinear_points() {
+    let world = [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, 2.0, 2.0]];
+    assert!(absolute_orientation(&world, &world).is_none());
+}
+
+// ── P3P ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn p3p_returns_the_true_pose_among_candidates() {
+    let intr = intrinsics(0.0);
+    let corrs = correspondences(&intr);
+    let rot = rotation();
+    let world = [corrs[0].enu, corrs[1].enu, corrs[2].enu];
+    let bearings = [0, 1, 2].map(|i| bearing(corrs[i].pixel, &intr).unwrap());
+
+    let candidates = p3p(&world, &bearings);
+    assert!(!candidates.is_empty() && candidates.len() <= 4);
+    assert!(
+        candidates
+            .iter()
+            .any(|c| rotation_distance(&c.rot, &rot) < 1e-6
+                && position_distance(&c.cam, &CAM) < 1e-4),
+        "true pose not among {} candidates",
+        candidates.len()
+    );
+}
+
+#[test]
+fn p3p_candidates_undo_lens_distortion() {
+    let intr = intrinsics(-0.15);
+    let corrs = correspondences(&intr);
+    let problem = Problem::new(&corrs, &intr, PosePriors::default(), [-0.15, 0.0, 0.0, 0.0]);
+
+    let best = p3p_candidates([0, 3, 4], &problem)
+        .into_iter()
+        .map(|p| reprojection_errors(&p, &corrs).1)
+        .fold(f64::INFINITY, f64::min);
+    assert!(best < 1e-6, "best candidate RMSE {best} px");
+}
+
+#[test]
+fn binomial_reports_overflow_instead_of_wrapping() {
+    assert_eq!(binomial(6, 3), Some(20));
+    assert_eq!(binomial(40, 4), Some(91_390));
+    assert_eq!(binomial(3, 3), Some(1));
+    // 10⁷ choose 3 ≈ 1.7·10²⁰ does not fit in 64 bits.
+    assert_eq!(binomial(10_000_000, 3), None);
+}
+
+#[test]
+fn minimal_seeds_skip_degenerate_triples() {
+    let intr = intrinsics(0.0);
+    let mut corrs = correspondences(&intr);
+    // Three points with the same pixel row and ENU positions on a line.
+    for (i, c) in corrs.iter_mut().take(3).enumerate() {
+        c.enu = [i as f64, 100.0, 0.0];
+        c.pixel = [1900.0 + i as f64, 1500.0];
+    }
+    let problem = Problem::new(&corrs[..3], &intr, PosePriors::default(), [0.0; 4]);
+    assert!(minimal_seeds(&problem, 60).is_empty());
+}
+
+// ── EPnP ────────────────────────────────────────────────────────────────────
+
+fn normalized(corrs: &[EnuCorrespondence], intr: &CameraIntrinsics) -> Vec<[f64; 2]> {
+    corrs
+        .iter()
+        .map(|c| {
+            let b = bearing(c.pixel, intr).unwrap();
+            [b[0] / b[2], b[1] / b[2]]
+        })
+        .collect()
+}
+
+#[test]
+fn epnp_recovers_the_exact_pose() {
+    let intr = intrinsics(0.0);
+    let corrs = correspondences(&intr);
+    let world: Vec<[f64; 3]> = corrs.iter().map(|c| c.enu).collect();
+    let pose = epnp(&world, &normalized(&corrs, &intr)).unwrap();
+
+    let rot = rotation();
+    assert!(rotation_distance(&pose.rot, &rot) < 1e-6);
+    assert!(position_distance(&pose.cam, &CAM) < 1e-4);
+}
+
+#[test]
+fn epnp_works_with_the_minimum_of_four_points() {
+    let intr = intrinsics(0.0);
+    let corrs = correspondences(&intr);
+    let world: Vec<[f64; 3]> = corrs[..4].iter().map(|c| c.enu).collect();
+    let pose = epnp(&world, &normalized(&corrs[..4], &intr)).unwrap();
+    assert!(position_distance(&pose.cam, &CAM) < 1e-3);
+}
+
+#[test]
+fn epnp_rejects_planar_and_too_small_inputs() {
+    let intr = intrinsics(0.0);
+    let mut corrs = correspondences(&intr);
+    for c in &mut corrs {
+        c.enu[2] = 0.0;
+    }
+    let world: Vec<[f64; 3]> = corrs.iter().map(|c| c.enu).collect();
+    let norm = normalized(&corrs, &intr);
+    assert!(epnp(&world, &norm).is_none());
+    assert!(epnp(&world[..3], &norm[..3]).is_none());
+}
+
+#[test]
+fn epnp_seed_needs_no_refinement_on_exact_data() {
+    let intr = intrinsics(-0.15);
+    let corrs = correspondences(&intr);
+    let problem = Problem::new(&corrs, &intr, PosePriors::default(), [-0.15, 0.0, 0.0, 0.0]);
+    let seed = epnp_seed(&problem).unwrap();
+    let (_, rmse) = reprojection_errors(&seed, &corrs);
+    assert!(rmse < 1e-3, "seed RMSE {rmse} px");
+}
+
+// ── Focal scan over P3P ─────────────────────────────────────────────────────
+
+#[test]
+fn focal_scan_p3p_recovers_focal_length_and_pose() {
+    let truth = CameraIntrinsics {
+        focal_px: 2200.0,
+        ..intrinsics(0.0)
+    };
+    let corrs = correspondences(&truth);
+    let world = [corrs[0].enu, corrs[1].enu, corrs[3].enu, corrs[4].enu];
+    let pixels = [
+        corrs[0].pixel,
+        corrs[1].pixel,
+        corrs[3].pixel,
+        corrs[4].pixel,
+    ];
+
+    // Start from a badly wrong focal length: only cx / cy are used.
+    let solutions = focal_scan_p3p(&world, &pixels, &intrinsics(0.0), 4000.0);
+    let (pose, focal) = solutions.first().expect("a focal-scan solution");
+    assert!((focal - 2200.0).abs() < 0.5, "focal = {focal}");
+    assert!(position_distance(&pose.cam, &CAM) < 0.05);
+}
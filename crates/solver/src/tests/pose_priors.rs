@@ -0,0 +1,121 @@
+use crate::types::{GaussianPrior, PositionPrior, Priors, SolveRequest};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn scene_request(n_points: usize) -> SolveRequest {
+    synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..n_points])
+}
+
+fn prior(mean: f64, sigma: f64) -> Option<GaussianPrior> {
+    Some(GaussianPrior {
+        mean,
+        sigma: Some(sigma),
+    })
+}
+
+fn priors_mut(req: &mut SolveRequest) -> &mut Priors {
+    req.priors.get_or_insert_with(Priors::default)
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn tight_altitude_sigma_overrides_the_data() {
+    let mut req = scene_request(8);
+    priors_mut(&mut req).camera_alt = prior(22.0, 0.01);
+    let response = solve_to_response(req);
+    assert!(
+        (response.pose.alt - 22.0).abs() < 0.5,
+        "alt = {:.2}",
+        response.pose.alt
+    );
+}
+
+#[test]
+fn loose_altitude_sigma_lets_the_data_win() {
+    let mut req = scene_request(8);
+    priors_mut(&mut req).camera_alt = prior(22.0, 1000.0);
+    let response = solve_to_response(req);
+    assert!(
+        (response.pose.alt - camera().alt).abs() < 0.5,
+        "alt = {:.2}",
+        response.pose.alt
+    );
+}
+
+#[test]
+fn yaw_prior_wraps_across_north() {
+    // Truth is 0°; a prior at 359.5° is only half a degree away.  Without
+    // wrapping it would pull the heading by ~360 σ.
+    let mut req = scene_request(8);
+    priors_mut(&mut req).yaw_deg = prior(359.5, 1.0);
+    let response = solve_to_response(req);
+    let yaw = response.pose.yaw_deg;
+    let err = yaw.min(360.0 - yaw);
+    assert!(err < 0.2, "yaw = {:.3}", yaw);
+}
+
+#[test]
+fn pose_priors_constrain_an_underdetermined_solve() {
+    let cam = camera();
+    let mut req = scene_request(2);
+    let p = priors_mut(&mut req);
+    p.position = Some(PositionPrior {
+        lat: cam.lat,
+        lon: cam.lon,
+        sigma_m: Some(1.0),
+    });
+    p.camera_alt = prior(cam.alt, 1.0);
+    p.pitch_deg = prior(cam.pitch_deg, 0.5);
+    p.roll_deg = prior(cam.roll_deg, 0.5);
+    let response = solve_to_response(req);
+
+    let err = haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon);
+    assert!(err < 3.0, "position error {:.2} m", err);
+    assert!((response.pose.pitch_deg - cam.pitch_deg).abs() < 1.0);
+    assert!(response.pose.roll_deg.abs() < 1.0);
+}
+
+#[test]
+fn single_point_uses_position_and_attitude_priors() {
+    let mut req = scene_request(1);
+    let p = priors_mut(&mut req);
+    p.position = Some(PositionPrior {
+        lat: 51.9,
+        lon: 4.46,
+        sigma_m: None,
+    });
+    p.yaw_deg = prior(-90.0, 10.0);
+    p.pitch_deg = prior(3.0, 1.0);
+    let response = solve_to_response(req);
+
+    assert_eq!(response.pose.lat, 51.9);
+    assert_eq!(response.pose.lon, 4.46);
+    assert_eq!(response.pose.yaw_deg, 270.0);
+    assert_eq!(response.pose.pitch_deg, 3.0);
+    assert!(response.diagnostics.warnings[0].contains("position prior"));
+}
+
+#[test]
+fn priors_json_accepts_sigma_and_pose_fields() {
+    let priors: Priors = serde_json::from_str(
+        r#"{
+            "focalPx": {"mean": 3000, "sigma": 150},
+            "cameraAlt": {"mean": 12},
+            "position": {"lat": 51.9, "lon": 4.47, "sigmaM": 5},
+            "yawDeg": {"mean": 90, "sigma": 15},
+            "pitchDeg": {"mean": -2, "sigma": 3},
+            "rollDeg": {"mean": 0.5, "sigma": 2}
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(priors.focal_px.as_ref().unwrap().sigma, Some(150.0));
+    assert_eq!(priors.camera_alt.as_ref().unwrap().sigma, None);
+    assert_eq!(priors.position.as_ref().unwrap().sigma_m, Some(5.0));
+    assert_eq!(priors.yaw_deg.as_ref().unwrap().mean, 90.0);
+    assert_eq!(priors.pitch_deg.as_ref().unwrap().sigma, Some(3.0));
+    assert_eq!(priors.roll_deg.as_ref().unwrap().mean, 0.5);
+}
@@ -0,0 +1,90 @@
+use crate::types::{Image, SolveRequest, SolveResponse, SolverModel};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Eight points rendered by a 4600×2600 camera (principal point 2300,1300)
+/// and then cropped to 4000×3000, so the true principal point sits
+/// 300 px right of and 200 px above the image centre.
+fn cropped_request(estimate_principal_point: bool) -> SolveRequest {
+    let mut req = synthetic_request(&camera(), 3000.0, (4600.0, 2600.0), &SCENE);
+    req.image = Image {
+        width: 4000.0,
+        height: 3000.0,
+    };
+    req.model = Some(SolverModel {
+        estimate_focal: Some(false),
+        estimate_distortion: false,
+        estimate_principal_point,
+        uncalibrated: false,
+    });
+    req
+}
+
+fn variance(response: &SolveResponse, label: &str) -> f64 {
+    let n = response.covariance.labels.len();
+    let i = response
+        .covariance
+        .labels
+        .iter()
+        .position(|l| l == label)
+        .expect("covariance label must exist");
+    response.covariance.matrix[i * n + i]
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn principal_point_is_recovered_for_cropped_image() {
+    let response = solve_to_response(cropped_request(true));
+
+    assert!(
+        (response.intrinsics.cx - 2300.0).abs() < 5.0,
+        "cx = {:.1}",
+        response.intrinsics.cx
+    );
+    assert!(
+        (response.intrinsics.cy - 1300.0).abs() < 5.0,
+        "cy = {:.1}",
+        response.intrinsics.cy
+    );
+    let cam = camera();
+    let err = haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon);
+    assert!(err < 1.0, "position error {:.2} m", err);
+    assert!(response.diagnostics.rmse_px < 0.5);
+    assert!(variance(&response, "cx") > 0.0);
+    assert!(variance(&response, "cy") > 0.0);
+}
+
+#[test]
+fn principal_point_stays_at_centre_by_default() {
+    let response = solve_to_response(cropped_request(false));
+
+    assert_eq!(response.intrinsics.cx, 2000.0);
+    assert_eq!(response.intrinsics.cy, 1500.0);
+    assert_eq!(variance(&response, "cx"), 0.0);
+    assert_eq!(variance(&response, "cy"), 0.0);
+}
+
+#[test]
+fn principal_point_with_four_points_warns_and_keeps_centre() {
+    let mut req = cropped_request(true);
+    req.correspondences.truncate(4);
+    let response = solve_to_response(req);
+
+    assert_eq!(response.intrinsics.cx, 2000.0);
+    assert!(response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.contains("Principal point estimation needs at least 5")));
+}
+
+#[test]
+fn covariance_labels_end_with_principal_point() {
+    let response = solve_to_response(cropped_request(true));
+    let labels = &response.covariance.labels;
+    assert_eq!(labels[labels.len() - 2], "cx");
+    assert_eq!(labels[labels.len() - 1], "cy");
+}
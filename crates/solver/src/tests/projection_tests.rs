@@ -1,4 +1,4 @@
-use crate::projection::{project_point, rotation_enu_to_cam, CameraIntrinsics};
+use crate::projection::{project_point, rotation_enu_to_cam, undistort_pixel, CameraIntrinsics};
 
 fn default_intrinsics() -> CameraIntrinsics {
     CameraIntrinsics {
@@ -69,7 +69,7 @@ fn point_below_projects_below_center() {
     let rot = rotation_enu_to_cam(0.0, 0.0, 0.0);
     let cam = [0.0, 0.0, 0.0];
     let pt = [0.0, 100.0, -10.0]; // 100 m N, 10 m Down
-    let (u, v) = project_point(pt, cam, &rot, &intr).unwrap();
+    let (_u, v) = project_point(pt, cam, &rot, &intr).unwrap();
     assert!(v > intr.cy, "point below should have v > cy: v={}", v);
 }
 
@@ -272,3 +272,33 @@ fn combined_yaw_and_pitch() {
         v
     );
 }
+
+// ── Undistortion (inverse of the distortion model) ─────────────────────────
+
+#[test]
+fn undistort_pinhole_is_plain_normalisation() {
+    let intr = default_intrinsics();
+    let (xn, yn) = undistort_pixel(700.0, 300.0, &intr).unwrap();
+    assert!((xn - 0.2).abs() < 1e-12, "xn={}", xn);
+    assert!((yn + 0.1).abs() < 1e-12, "yn={}", yn);
+}
+
+#[test]
+fn undistort_inverts_brown_conrady_projection() {
+    let intr = CameraIntrinsics {
+        k1: -0.15,
+        k2: 0.03,
+        p1: 0.001,
+        p2: -0.002,
+        ..default_intrinsics()
+    };
+    let rot = rotation_enu_to_cam(0.0, 0.0, 0.0);
+    let cam = [0.0, 0.0, 0.0];
+    for pt in [[30.0, 100.0, 20.0], [-45.0, 100.0, -35.0], [5.0, 100.0, 1.0]] {
+        let (u, v) = project_point(pt, cam, &rot, &intr).unwrap();
+        let (xn, yn) = undistort_pixel(u, v, &intr).unwrap();
+        // Camera looks North: x = E/N, y = −U/N
+        assert!((xn - pt[0] / pt[1]).abs() < 1e-9, "xn={} for {:?}", xn, pt);
+        assert!((yn + pt[2] / pt[1]).abs() < 1e-9, "yn={} for {:?}", yn, pt);
+    }
+}
@@ -0,0 +1,261 @@
+use super::helpers::haversine_m;
+use crate::geo::enu_to_lla;
+use crate::types::{
+    BackProjectRequest, Image, Intrinsics, Pixel, Pose, ReprojectRequest, WorldLla, WorldPoint,
+};
+use crate::{back_project, back_project_impl, reproject_impl, reproject_points, SolveError};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Camera at (51.9, 4.46, 10 m) looking North, level, f = 1000 px on a
+/// 2000 × 1000 image.
+fn request_with(points: Vec<WorldPoint>) -> ReprojectRequest {
+    ReprojectRequest {
+        pose: Pose {
+            lat: 51.9,
+            lon: 4.46,
+            alt: 10.0,
+            yaw_deg: 0.0,
+            pitch_deg: 0.0,
+            roll_deg: 0.0,
+        },
+        intrinsics: Intrinsics {
+            focal_px: 1000.0,
+            cx: 1000.0,
+            cy: 500.0,
+            k1: 0.0,
+            k2: 0.0,
+            p1: 0.0,
+            p2: 0.0,
+        },
+        image: Image {
+            width: 2000.0,
+            height: 1000.0,
+        },
+        points,
+    }
+}
+
+/// World point at the given ENU offset from the camera in `request_with`.
+fn point_at_enu(id: &str, enu: [f64; 3]) -> WorldPoint {
+    let (lat, lon, alt) = enu_to_lla(enu, 51.9, 4.46, 10.0);
+    WorldPoint {
+        id: id.to_string(),
+        world: WorldLla {
+            lat,
+            lon,
+            alt: Some(alt),
+            sigma_m: None,
+        },
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn point_straight_ahead_projects_to_principal_point() {
+    let req = request_with(vec![point_at_enu("ahead", [0.0, 200.0, 0.0])]);
+    let resp = reproject_impl(&req).expect("reproject must succeed");
+
+    let px = &resp.pixels[0];
+    assert_eq!(px.id, "ahead");
+    assert!((px.u.unwrap() - 1000.0).abs() < 1e-3, "u = {:?}", px.u);
+    assert!((px.v.unwrap() - 500.0).abs() < 1e-3, "v = {:?}", px.v);
+    assert!(!px.behind_camera);
+    assert!(!px.outside_image);
+    assert!(resp.warnings.is_empty());
+}
+
+#[test]
+fn point_to_the_east_projects_right_of_centre() {
+    // 100 m ahead, 10 m East → u = cx + f · 10/100 = 1100
+    let req = request_with(vec![point_at_enu("east", [10.0, 100.0, 0.0])]);
+    let resp = reproject_impl(&req).expect("reproject must succeed");
+
+    let px = &resp.pixels[0];
+    assert!((px.u.unwrap() - 1100.0).abs() < 0.05, "u = {:?}", px.u);
+    assert!((px.v.unwrap() - 500.0).abs() < 0.05, "v = {:?}", px.v);
+}
+
+#[test]
+fn point_behind_camera_is_flagged_without_pixel() {
+    let req = request_with(vec![point_at_enu("behind", [0.0, -50.0, 0.0])]);
+    let resp = reproject_impl(&req).expect("reproject must succeed");
+
+    let px = &resp.pixels[0];
+    assert!(px.behind_camera);
+    assert!(px.u.is_none() && px.v.is_none());
+    assert!(resp
+        .warnings
+        .iter()
+        .any(|w| w.contains("behind the camera")));
+}
+
+#[test]
+fn point_outside_field_of_view_is_flagged() {
+    // 45° to the right with f = 1000 px → u = 2000, just past the right edge.
+    let req = request_with(vec![point_at_enu("wide", [150.0, 100.0, 0.0])]);
+    let resp = reproject_impl(&req).expect("reproject must succeed");
+
+    let px = &resp.pixels[0];
+    assert!(!px.behind_camera);
+    assert!(px.outside_image);
+    assert!(px.u.unwrap() > 2000.0);
+}
+
+#[test]
+fn distortion_moves_off_centre_points() {
+    let pinhole = reproject_impl(&request_with(vec![point_at_enu("p", [30.0, 100.0, 0.0])]))
+        .expect("reproject must succeed");
+
+    let mut req = request_with(vec![point_at_enu("p", [30.0, 100.0, 0.0])]);
+    req.intrinsics.k1 = -0.2; // barrel distortion pulls points toward the centre
+    let barrel = reproject_impl(&req).expect("reproject must succeed");
+
+    let u_pin = pinhole.pixels[0].u.unwrap();
+    let u_bar = barrel.pixels[0].u.unwrap();
+    assert!(
+        u_bar < u_pin,
+        "barrel u {} should be < pinhole u {}",
+        u_bar,
+        u_pin
+    );
+}
+
+#[test]
+fn output_preserves_input_order_and_ids() {
+    let req = request_with(vec![
+        point_at_enu("a", [0.0, 100.0, 0.0]),
+        point_at_enu("b", [0.0, -100.0, 0.0]),
+        point_at_enu("c", [-5.0, 100.0, 2.0]),
+    ]);
+    let resp = reproject_impl(&req).expect("reproject must succeed");
+
+    let ids: Vec<&str> = resp.pixels.iter().map(|p| p.id.as_str()).collect();
+    assert_eq!(ids, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn rejects_non_positive_image_size() {
+    let mut req = request_with(vec![]);
+    req.image.width = 0.0;
+    assert!(reproject_impl(&req).is_err());
+}
+
+#[test]
+fn wasm_entry_point_accepts_frontend_json() {
+    // Distortion coefficients are optional on input.
+    let json = r#"{
+        "pose": { "lat": 51.9, "lon": 4.46, "alt": 10.0, "yawDeg": 0.0, "pitchDeg": 0.0, "rollDeg": 0.0 },
+        "intrinsics": { "focalPx": 1000.0, "cx": 1000.0, "cy": 500.0 },
+        "image": { "width": 2000.0, "height": 1000.0 },
+        "points": [ { "id": "p1", "world": { "lat": 51.901, "lon": 4.46, "alt": 10.0 } } ]
+    }"#;
+    let out = reproject_points(json.to_string()).expect("reproject must succeed");
+    let value: serde_json::Value = serde_json::from_str(&out).expect("output must be json");
+
+    assert_eq!(value["pixels"][0]["id"], serde_json::json!("p1"));
+    assert_eq!(value["pixels"][0]["behindCamera"], serde_json::json!(false));
+    assert_eq!(value["pixels"][0]["outsideImage"], serde_json::json!(false));
+    let u = value["pixels"][0]["u"]
+        .as_f64()
+        .expect("u must be a number");
+    assert!((u - 1000.0).abs() < 0.5, "u = {}", u);
+}
+
+// ── Back-projection ─────────────────────────────────────────────────────────
+
+/// Back-project `(u, v)` through the camera of `request_with` onto the
+/// surface at `plane_alt`.
+fn back_request(u: f64, v: f64, plane_alt: f64) -> BackProjectRequest {
+    let base = request_with(vec![]);
+    BackProjectRequest {
+        pose: base.pose,
+        intrinsics: base.intrinsics,
+        pixel: Pixel {
+            u,
+            v,
+            sigma_px: None,
+        },
+        plane_alt,
+    }
+}
+
+#[test]
+fn back_projection_inverts_reprojection() {
+    let mut pose = request_with(vec![]).pose;
+    pose.pitch_deg = -10.0;
+    pose.yaw_deg = 30.0;
+
+    // Project a ground point (alt 0 m) into the tilted camera …
+    let ground = point_at_enu("g", [40.0, 60.0, -10.0]);
+    let mut fwd = request_with(vec![ground]);
+    fwd.pose = pose;
+    fwd.intrinsics.k1 = -0.1;
+    fwd.intrinsics.p1 = 0.002;
+    let projected = reproject_impl(&fwd).expect("reproject must succeed");
+    let px = &projected.pixels[0];
+
+    // … and cast it back onto the 0 m plane.
+    let mut back = back_request(px.u.unwrap(), px.v.unwrap(), 0.0);
+    back.pose = fwd.pose;
+    back.intrinsics = fwd.intrinsics;
+    let hit = back_project_impl(&back).expect("back-projection must succeed");
+
+    let (lat, lon, alt) = enu_to_lla([40.0, 60.0, -10.0], 51.9, 4.46, 10.0);
+    let err = haversine_m(hit.lat, hit.lon, lat, lon);
+    assert!(err < 0.01, "horizontal error {:.4} m", err);
+    assert!((hit.alt - alt).abs() < 0.01, "alt {} vs {}", hit.alt, alt);
+    assert!((hit.distance_m - (40.0f64.powi(2) + 60.0f64.powi(2) + 100.0).sqrt()).abs() < 0.01);
+}
+
+#[test]
+fn back_projection_follows_earth_curvature() {
+    // A ray just below the horizon from 10 m up reaches the 0 m surface
+    // several km away; the hit must lie on the ellipsoid, not the tangent plane.
+    let hit = back_project_impl(&back_request(1000.0, 502.0, 0.0))
+        .expect("back-projection must succeed");
+    assert!(hit.alt.abs() < 0.01, "alt {}", hit.alt);
+    assert!(hit.distance_m > 3000.0, "distance {}", hit.distance_m);
+    assert!(hit.bearing_deg < 1.0 || hit.bearing_deg > 359.0, "bearing {}", hit.bearing_deg);
+}
+
+#[test]
+fn back_projection_rejects_ray_above_horizon() {
+    // Pixel above the principal point looks up and never reaches the ground.
+    let err = back_project_impl(&back_request(1000.0, 300.0, 0.0))
+        .err()
+        .expect("upward ray must be rejected");
+    assert_eq!(err, SolveError::NoIntersection);
+    assert!(err.to_string().contains("does not intersect"), "{}", err);
+}
+
+#[test]
+fn back_projection_hits_plane_above_camera() {
+    // Looking up at a 50 m roof line from 10 m.
+    let hit = back_project_impl(&back_request(1000.0, 300.0, 50.0))
+        .expect("back-projection must succeed");
+    assert!((hit.alt - 50.0).abs() < 0.01);
+    // tan(θ) = 200/1000 → horizontal distance ≈ 40 m / 0.2 = 200 m
+    let horiz = (hit.distance_m.powi(2) - 40.0f64.powi(2)).sqrt();
+    assert!((horiz - 200.0).abs() < 0.5, "horizontal distance {}", horiz);
+}
+
+#[test]
+fn back_project_wasm_entry_point_accepts_frontend_json() {
+    let json = r#"{
+        "pose": { "lat": 51.9, "lon": 4.46, "alt": 10.0, "yawDeg": 0.0, "pitchDeg": -45.0, "rollDeg": 0.0 },
+        "intrinsics": { "focalPx": 1000.0, "cx": 1000.0, "cy": 500.0 },
+        "pixel": { "u": 1000.0, "v": 500.0 },
+        "planeAlt": 0.0
+    }"#;
+    let out = back_project(json.to_string()).expect("back-projection must succeed");
+    let value: serde_json::Value = serde_json::from_str(&out).expect("output must be json");
+
+    // 45° down from 10 m → 10 m north of the camera.
+    let lat = value["lat"].as_f64().unwrap();
+    let lon = value["lon"].as_f64().unwrap();
+    let d = haversine_m(51.9, 4.46, lat, lon);
+    assert!((d - 10.0).abs() < 0.05, "distance {}", d);
+    assert!(value["distanceM"].as_f64().unwrap() > 14.0);
+}
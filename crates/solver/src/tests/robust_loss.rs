@@ -0,0 +1,125 @@
+use crate::optimizer::RobustKernel;
+use crate::parse_solve_request;
+use crate::types::{RefineCfg, RobustLoss, SolveRequest, SolveResponse};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Eight-point scene where `pt_3` was clicked 60 px too far right – a
+/// sloppy click that RANSAC with a generous threshold would keep.
+fn request_with_sloppy_click(loss: RobustLoss) -> SolveRequest {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE);
+    req.correspondences[3].pixel.u += 60.0;
+    req.refine = Some(RefineCfg {
+        max_iters: None,
+        robust_loss: loss,
+        loss_scale: None,
+    });
+    req
+}
+
+fn position_error_m(response: &SolveResponse) -> f64 {
+    let cam = camera();
+    haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon)
+}
+
+// ── Kernel weights ──────────────────────────────────────────────────────────
+
+#[test]
+fn huber_weight_is_one_inside_and_decays_linearly_outside() {
+    let k = RobustKernel::new(RobustLoss::Huber, Some(2.0));
+    assert_eq!(k.weight(0.5), 1.0);
+    assert_eq!(k.weight(2.0), 1.0);
+    assert!((k.weight(8.0) - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn cauchy_weight_halves_at_the_scale() {
+    let k = RobustKernel::new(RobustLoss::Cauchy, Some(3.0));
+    assert_eq!(k.weight(0.0), 1.0);
+    assert!((k.weight(3.0) - 0.5).abs() < 1e-12);
+    assert!(k.weight(30.0) < 0.01);
+}
+
+#[test]
+fn tukey_weight_vanishes_beyond_the_scale() {
+    let k = RobustKernel::new(RobustLoss::Tukey, None);
+    assert!((k.scale - 4.685).abs() < 1e-12, "default Tukey constant");
+    assert_eq!(k.weight(0.0), 1.0);
+    assert!(k.weight(2.0) > 0.0 && k.weight(2.0) < 1.0);
+    assert_eq!(k.weight(4.685), 0.0);
+    assert_eq!(k.weight(100.0), 0.0);
+}
+
+#[test]
+fn none_kernel_never_downweights() {
+    let k = RobustKernel::new(RobustLoss::None, Some(0.1));
+    assert_eq!(k.weight(1e6), 1.0);
+}
+
+// ── End-to-end ──────────────────────────────────────────────────────────────
+
+#[test]
+fn robust_kernels_fit_the_majority_more_tightly() {
+    // Least squares spreads the bad click over every point (and the free
+    // distortion terms); a robust kernel lets the majority fit tightly.
+    let median = |r: &SolveResponse| {
+        let mut v = r.diagnostics.residuals_px.clone();
+        v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        v[v.len() / 2]
+    };
+    let plain = solve_to_response(request_with_sloppy_click(RobustLoss::None));
+
+    for loss in [RobustLoss::Huber, RobustLoss::Cauchy, RobustLoss::Tukey] {
+        let robust = solve_to_response(request_with_sloppy_click(loss));
+        assert!(
+            median(&robust) < 0.5 * median(&plain),
+            "{:?}: median residual {:.3} px vs least squares {:.3} px",
+            loss,
+            median(&robust),
+            median(&plain)
+        );
+        assert_eq!(robust.diagnostics.weights.len(), 8);
+        assert!(robust.diagnostics.weights.iter().any(|w| *w < 0.5));
+    }
+}
+
+#[test]
+fn tukey_effectively_ignores_the_sloppy_click() {
+    // Redescending kernel: the bad click ends up with zero weight and the
+    // remaining seven perfect points pin the pose exactly.
+    let response = solve_to_response(request_with_sloppy_click(RobustLoss::Tukey));
+    assert!(position_error_m(&response) < 0.5, "error {:.3} m", position_error_m(&response));
+    assert_eq!(response.diagnostics.weights[3], 0.0);
+    assert!(response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.contains("down-weighted 1 point(s)") && w.contains("pt_3")));
+}
+
+#[test]
+fn plain_least_squares_reports_unit_weights() {
+    let response = solve_to_response(request_with_sloppy_click(RobustLoss::None));
+    assert!(response.diagnostics.weights.iter().all(|w| *w == 1.0));
+}
+
+#[test]
+fn refine_config_migrates_legacy_huber_delta() {
+    let json = r#"{
+        "image": { "width": 4000, "height": 3000 },
+        "correspondences": [],
+        "refine": { "maxIters": 50, "robustLoss": "huber", "huberDelta": 1.0 }
+    }"#;
+    let (req, warnings) = parse_solve_request(json).expect("request must parse");
+    assert!(warnings.is_empty(), "{warnings:?}");
+    let refine = req.refine.expect("refine must be kept");
+    assert_eq!(refine.max_iters, Some(50));
+    assert_eq!(refine.robust_loss, RobustLoss::Huber);
+    assert_eq!(refine.loss_scale, Some(1.0));
+
+    let json = r#"{ "image": { "width": 1, "height": 1 }, "refine": { "robustLoss": "tukey" } }"#;
+    let req: SolveRequest = serde_json::from_str(json).expect("request must parse");
+    assert_eq!(req.refine.unwrap().robust_loss, RobustLoss::Tukey);
+}
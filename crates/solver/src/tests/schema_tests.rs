@@ -0,0 +1,313 @@
+use std::path::PathBuf;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_json::{json, Value};
+
+use crate::schema::unknown_fields;
+use crate::types::{
+    BootstrapCfg, Bounds, Corr, DistortionPrior, GaussianPrior, Image, Pixel, PositionPrior,
+    Priors, RansacCfg, RefineCfg, SolveRequest, SolverModel, UncertaintyCfg, WorldLla,
+};
+use crate::{
+    parse_solve_request, request_schema, response_schema, solve_json, SolveError, SCHEMA_VERSION,
+};
+
+use super::helpers::{camera, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// The synthetic scene as request JSON, without `schemaVersion`.
+fn request_json() -> Value {
+    let req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..6]);
+    let correspondences: Vec<Value> = req
+        .correspondences
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.id,
+                "pixel": { "u": c.pixel.u, "v": c.pixel.v },
+                "world": { "lat": c.world.lat, "lon": c.world.lon, "alt": c.world.alt },
+            })
+        })
+        .collect();
+    json!({
+        "image": { "width": 4000, "height": 3000 },
+        "correspondences": correspondences,
+        "priors": { "focalPx": { "mean": 3000.0 } },
+    })
+}
+
+/// Deserializer that records the field list a derived `Deserialize` impl
+/// passes to `deserialize_struct`, then bails out.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields captured"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+        identifier ignored_any
+    }
+}
+
+/// The JSON field names serde accepts for the struct `T`, sorted.
+fn serde_fields<T: for<'de> Deserialize<'de>>() -> Vec<&'static str> {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    let mut fields = fields.to_vec();
+    fields.sort_unstable();
+    fields
+}
+
+/// The properties `schema` declares at `path` (`a.b[].c`), sorted.
+fn schema_fields<'a>(schema: &'a Value, path: &str) -> Vec<&'a str> {
+    let mut node = schema;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        node = match segment.strip_suffix("[]") {
+            Some(name) => &node["properties"][name]["items"],
+            None => &node["properties"][segment],
+        };
+    }
+    let mut fields: Vec<&str> = node["properties"]
+        .as_object()
+        .unwrap_or_else(|| panic!("no object schema at `{path}`"))
+        .keys()
+        .map(String::as_str)
+        .collect();
+    fields.sort_unstable();
+    fields
+}
+
+fn solve_value(req: &Value) -> Result<Value, Value> {
+    solve_json(&req.to_string())
+        .map(|s| serde_json::from_str(&s).unwrap())
+        .map_err(|s| serde_json::from_str(&s).unwrap())
+}
+
+/// Paths of `required` properties of `schema` that `value` lacks.
+fn missing_required(value: &Value, schema: &Value, path: &str, out: &mut Vec<String>) {
+    match value {
+        Value::Object(obj) => {
+            for name in schema["required"].as_array().into_iter().flatten() {
+                let name = name.as_str().unwrap();
+                if !obj.contains_key(name) {
+                    out.push(format!("{path}.{name}"));
+                }
+            }
+            for (key, v) in obj {
+                if let Some(s) = schema["properties"].get(key) {
+                    missing_required(v, s, &format!("{path}.{key}"), out);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                missing_required(v, &schema["items"], &format!("{path}[{i}]"), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn schema_file(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("schema")
+        .join(name)
+}
+
+// ── Versioning and migration ────────────────────────────────────────────────
+
+#[test]
+fn response_carries_the_schema_version() {
+    let resp = solve_value(&request_json()).expect("solve must succeed");
+    assert_eq!(resp["schemaVersion"], SCHEMA_VERSION);
+}
+
+#[test]
+fn unversioned_session_migrates_huber_delta() {
+    let mut req = request_json();
+    req["refine"] = json!({ "maxIters": 50, "robustLoss": "huber", "huberDelta": 2.0 });
+    let (parsed, warnings) = parse_solve_request(&req.to_string()).expect("must parse");
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(parsed.schema_version, SCHEMA_VERSION);
+    assert_eq!(parsed.refine.unwrap().loss_scale, Some(2.0));
+}
+
+#[test]
+fn migration_keeps_an_explicit_loss_scale() {
+    let mut req = request_json();
+    req["refine"] = json!({ "robustLoss": "cauchy", "huberDelta": 2.0, "lossScale": 3.0 });
+    let (parsed, _) = parse_solve_request(&req.to_string()).expect("must parse");
+    assert_eq!(parsed.refine.unwrap().loss_scale, Some(3.0));
+}
+
+#[test]
+fn huber_delta_is_unknown_in_current_requests() {
+    let mut req = request_json();
+    req["schemaVersion"] = json!(SCHEMA_VERSION);
+    req["refine"] = json!({ "robustLoss": "huber", "huberDelta": 2.0 });
+    let (parsed, warnings) = parse_solve_request(&req.to_string()).expect("must parse");
+    assert_eq!(parsed.refine.unwrap().loss_scale, None);
+    assert_eq!(
+        warnings,
+        vec!["Ignored unknown request field `refine.huberDelta`.".to_string()]
+    );
+}
+
+#[test]
+fn newer_schema_version_is_rejected() {
+    let mut req = request_json();
+    req["schemaVersion"] = json!(SCHEMA_VERSION + 1);
+    let err = solve_value(&req).expect_err("solve must fail");
+    assert_eq!(err["code"], "unsupportedSchemaVersion");
+    assert_eq!(err["details"]["found"], SCHEMA_VERSION + 1);
+    assert_eq!(err["details"]["supported"], SCHEMA_VERSION);
+
+    req["schemaVersion"] = json!("1");
+    let Err(err) = parse_solve_request(&req.to_string()) else {
+        panic!("a string schemaVersion must be rejected");
+    };
+    assert_eq!(
+        err,
+        SolveError::InvalidInput {
+            field: "schemaVersion".to_string(),
+            message: "schemaVersion must be a non-negative integer".to_string(),
+        }
+    );
+}
+
+// ── Unknown fields ──────────────────────────────────────────────────────────
+
+#[test]
+fn unknown_fields_are_reported_as_warnings() {
+    let mut req = request_json();
+    req["schemaVersion"] = json!(SCHEMA_VERSION);
+    req["exif"] = json!({ "make": "ACME" });
+    req["correspondences"][2]["world"]["elevation"] = json!(12.0);
+    req["priors"]["focalPx"]["sigmaPx"] = json!(50.0);
+    let resp = solve_value(&req).expect("solve must succeed");
+    let warnings: Vec<&str> = resp["diagnostics"]["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w.as_str().unwrap())
+        .collect();
+    for path in [
+        "correspondences[2].world.elevation",
+        "exif",
+        "priors.focalPx.sigmaPx",
+    ] {
+        let warning = format!("Ignored unknown request field `{path}`.");
+        assert!(warnings.contains(&warning.as_str()), "{warnings:?}");
+    }
+}
+
+#[test]
+fn known_fields_produce_no_warnings() {
+    let mut req = request_json();
+    req["schemaVersion"] = json!(SCHEMA_VERSION);
+    req["correspondences"][0]["enabled"] = json!(true);
+    req["correspondences"][0]["pixel"]["sigmaPx"] = json!(1.0);
+    req["correspondences"][0]["world"]["sigmaM"] = json!(0.5);
+    req["priors"]["distortion"] = json!({ "k1": 0.0 });
+    req["model"] = json!({ "estimateFocal": false, "estimatePrincipalPoint": false });
+    req["ransac"] = json!({ "maxIters": 100, "inlierPx": 8.0, "targetProb": 0.99, "seed": 1 });
+    req["refine"] = json!({ "maxIters": 30, "robustLoss": "tukey", "lossScale": 4.0 });
+    req["uncertainty"] = json!({ "bootstrap": { "enabled": false, "method": "residuals" } });
+    let (_, warnings) = parse_solve_request(&req.to_string()).expect("must parse");
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+// ── Generated JSON Schema ───────────────────────────────────────────────────
+
+#[test]
+fn response_matches_the_response_schema() {
+    let mut req = request_json();
+    req["uncertainty"] = json!({ "bootstrap": { "enabled": true, "samples": 8, "seed": 3 } });
+    let resp = solve_value(&req).expect("solve must succeed");
+    let schema = response_schema();
+    assert_eq!(unknown_fields(&resp, &schema), Vec::<String>::new());
+    let mut missing = Vec::new();
+    missing_required(&resp, &schema, "", &mut missing);
+    assert!(missing.is_empty(), "{missing:?}");
+}
+
+/// The schema is also the whitelist for unknown-field warnings, so every
+/// field serde reads must be declared, under the same name.
+#[test]
+fn request_schema_declares_every_serde_field() {
+    let schema = request_schema();
+    let cases = [
+        ("", serde_fields::<SolveRequest>()),
+        ("image", serde_fields::<Image>()),
+        ("correspondences[]", serde_fields::<Corr>()),
+        ("correspondences[].pixel", serde_fields::<Pixel>()),
+        ("correspondences[].world", serde_fields::<WorldLla>()),
+        ("priors", serde_fields::<Priors>()),
+        ("priors.focalPx", serde_fields::<GaussianPrior>()),
+        ("priors.position", serde_fields::<PositionPrior>()),
+        ("priors.bounds", serde_fields::<Bounds>()),
+        ("priors.distortion", serde_fields::<DistortionPrior>()),
+        ("model", serde_fields::<SolverModel>()),
+        ("ransac", serde_fields::<RansacCfg>()),
+        ("refine", serde_fields::<RefineCfg>()),
+        ("uncertainty", serde_fields::<UncertaintyCfg>()),
+        ("uncertainty.bootstrap", serde_fields::<BootstrapCfg>()),
+    ];
+    for (path, fields) in cases {
+        assert!(!fields.is_empty(), "no serde fields for `{path}`");
+        assert_eq!(schema_fields(&schema, path), fields, "at `{path}`");
+    }
+}
+
+#[test]
+fn request_schema_rejects_additional_properties() {
+    let schema = request_schema();
+    assert_eq!(schema["additionalProperties"], false);
+    // The solver itself only warns; the description must say so.
+    assert!(schema["description"].as_str().unwrap().contains("warning"));
+    assert_eq!(schema["required"], json!(["image"]));
+    assert_eq!(
+        schema["properties"]["refine"]["properties"]["robustLoss"]["enum"],
+        json!(["none", "huber", "cauchy", "tukey"])
+    );
+}
+
+/// The schemas under `schema/` are generated; run with `UPDATE_SCHEMA=1`
+/// to rewrite them after changing the request or response types.
+#[test]
+fn committed_schemas_are_up_to_date() {
+    for (name, schema) in [
+        ("solve-request.schema.json", request_schema()),
+        ("solve-response.schema.json", response_schema()),
+    ] {
+        let generated = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+        let path = schema_file(name);
+        if std::env::var_os("UPDATE_SCHEMA").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &generated).unwrap();
+            continue;
+        }
+        let committed = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "{name} is stale; regenerate with UPDATE_SCHEMA=1 cargo test"
+        );
+    }
+}
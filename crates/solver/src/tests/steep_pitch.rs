@@ -0,0 +1,138 @@
+use crate::projection::{rotation_enu_to_cam, rotation_from_vector, rotation_to_vector, Mat3};
+use crate::types::{Pose, SolveRequest};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, world_around};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Ground points spread under a camera, with a little relief.
+const GROUND: [(f64, f64, f64); 8] = [
+    (-35.0, 25.0, 0.0),
+    (30.0, 30.0, 4.0),
+    (5.0, -20.0, 1.0),
+    (-25.0, -30.0, 8.0),
+    (40.0, -10.0, 2.0),
+    (-10.0, 5.0, 12.0),
+    (15.0, 35.0, 0.5),
+    (-40.0, -5.0, 3.0),
+];
+
+/// Points overhead (a bridge deck or canopy seen from below).
+const OVERHEAD: [(f64, f64, f64); 8] = [
+    (-20.0, 15.0, 40.0),
+    (18.0, 20.0, 45.0),
+    (3.0, -12.0, 42.0),
+    (-15.0, -18.0, 50.0),
+    (22.0, -6.0, 41.0),
+    (-6.0, 3.0, 55.0),
+    (9.0, 21.0, 43.0),
+    (-24.0, -3.0, 47.0),
+];
+
+fn request(cam: &Pose, offsets: &[(f64, f64, f64)]) -> SolveRequest {
+    let world = world_around(cam, offsets);
+    synthetic_request(cam, 3000.0, (4000.0, 3000.0), &world)
+}
+
+fn rotation_distance(a: &Mat3, b: &Mat3) -> f64 {
+    let mut max: f64 = 0.0;
+    for r in 0..3 {
+        for c in 0..3 {
+            max = max.max((a[r][c] - b[r][c]).abs());
+        }
+    }
+    max
+}
+
+/// Solve and check position and attitude.  Yaw and roll are compared
+/// through the rotation they describe, since near ±90° pitch only their
+/// combination is defined.
+fn assert_recovered(cam: &Pose, offsets: &[(f64, f64, f64)]) {
+    let response = solve_to_response(request(cam, offsets));
+    let pose = &response.pose;
+    let err = haversine_m(pose.lat, pose.lon, cam.lat, cam.lon);
+    assert!(err < 0.05, "position error {:.3} m", err);
+    assert!((pose.alt - cam.alt).abs() < 0.05, "alt = {}", pose.alt);
+    assert!(
+        (pose.pitch_deg - cam.pitch_deg).abs() < 0.01,
+        "pitch = {}",
+        pose.pitch_deg
+    );
+    let solved = rotation_enu_to_cam(pose.yaw_deg, pose.pitch_deg, pose.roll_deg);
+    let truth = rotation_enu_to_cam(cam.yaw_deg, cam.pitch_deg, cam.roll_deg);
+    assert!(rotation_distance(&solved, &truth) < 1e-4);
+}
+
+/// The shared camera moved to `alt` and turned to the given attitude.
+fn camera_at(alt: f64, yaw_deg: f64, pitch_deg: f64, roll_deg: f64) -> Pose {
+    Pose {
+        alt,
+        yaw_deg,
+        pitch_deg,
+        roll_deg,
+        ..camera()
+    }
+}
+
+// ── Rotation vector ─────────────────────────────────────────────────────────
+
+#[test]
+fn rotation_vector_round_trips() {
+    for w in [
+        [0.0, 0.0, 0.0],
+        [1e-9, -2e-9, 0.5e-9],
+        [0.3, -0.2, 0.1],
+        [-1.2, 2.0, 0.4],
+        [0.0, 0.0, std::f64::consts::PI - 1e-9],
+        [2.2, -1.6, 1.0],
+    ] {
+        let back = rotation_to_vector(&rotation_from_vector(&w));
+        for k in 0..3 {
+            assert!((back[k] - w[k]).abs() < 1e-7, "{:?} -> {:?}", w, back);
+        }
+    }
+}
+
+#[test]
+fn rotation_vector_represents_every_attitude() {
+    for &(yaw, pitch, roll) in &[
+        (0.0, 0.0, 0.0),
+        (180.0, 0.0, 0.0),
+        (45.0, -90.0, 0.0),
+        (300.0, 90.0, 0.0),
+        (120.0, -60.0, 170.0),
+    ] {
+        let rot = rotation_enu_to_cam(yaw, pitch, roll);
+        let w = rotation_to_vector(&rot);
+        let angle = (w[0] * w[0] + w[1] * w[1] + w[2] * w[2]).sqrt();
+        assert!(angle <= std::f64::consts::PI + 1e-12);
+        assert!(rotation_distance(&rotation_from_vector(&w), &rot) < 1e-12);
+    }
+}
+
+// ── Solves ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn nadir_photo_is_solved() {
+    assert_recovered(&camera_at(120.0, 30.0, -90.0, 0.0), &GROUND);
+}
+
+#[test]
+fn near_nadir_photo_is_solved() {
+    assert_recovered(&camera_at(120.0, 200.0, -88.5, 3.0), &GROUND);
+}
+
+#[test]
+fn upward_photo_is_solved() {
+    assert_recovered(&camera_at(2.0, 75.0, 89.0, -2.0), &OVERHEAD);
+}
+
+#[test]
+fn horizontal_photo_is_solved_as_before() {
+    // Same ground points, now seen from the side at a shallow angle.
+    let offsets: Vec<(f64, f64, f64)> = GROUND
+        .iter()
+        .map(|&(e, n, u)| (e, n + 250.0, u * 3.0 + 5.0))
+        .collect();
+    assert_recovered(&camera_at(2.0, 0.0, -2.0, 0.0), &offsets);
+}
@@ -0,0 +1,176 @@
+use crate::optimizer::{
+    levenberg_marquardt, params_from_pose, EnuCorrespondence, LmControl, PosePriors, Problem,
+};
+use crate::projection::CameraIntrinsics;
+use crate::types::{SolverModel, TerminationReason};
+
+use super::helpers::{
+    base_request, camera, enu_correspondences, pinhole, sample_corr, solve_to_response,
+    synthetic_request, SCENE,
+};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+fn intr() -> CameraIntrinsics {
+    pinhole(3000.0, (4000.0, 3000.0))
+}
+
+/// Attitude of the shared camera; it sits at the ENU origin.  Its zero
+/// roll means the default roll regulariser adds no cost at the truth.
+fn angles() -> (f64, f64, f64) {
+    let cam = camera();
+    (cam.yaw_deg, cam.pitch_deg, cam.roll_deg)
+}
+
+/// The first five scene points, each pixel shifted by `noise[i]` px.
+fn corrs(noise: &[(f64, f64)]) -> Vec<EnuCorrespondence> {
+    let req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..5]);
+    let mut corrs = enu_correspondences(&req, &camera());
+    for (c, (du, dv)) in corrs.iter_mut().zip(noise) {
+        c.pixel[0] += du;
+        c.pixel[1] += dv;
+    }
+    corrs
+}
+
+fn perturbed_start() -> [f64; 13] {
+    let (yaw, pitch, roll) = angles();
+    params_from_pose(
+        [20.0, -15.0, 5.0],
+        (yaw + 5.0, pitch - 2.0, roll + 1.0),
+        [0.0; 4],
+        &intr(),
+    )
+}
+
+const NOISE: [(f64, f64); 5] = [
+    (0.8, -1.1),
+    (-1.4, 0.3),
+    (0.5, 1.2),
+    (-0.2, -0.9),
+    (1.0, 0.6),
+];
+
+// ── Optimiser ───────────────────────────────────────────────────────────────
+
+#[test]
+fn exact_fit_stops_on_the_gradient() {
+    let c = corrs(&[(0.0, 0.0); 5]);
+    let problem = Problem::new(&c, &intr(), PosePriors::default(), [0.0; 4]);
+    let start = params_from_pose([0.0; 3], angles(), [0.0; 4], &intr());
+    let result = levenberg_marquardt(start, &problem, 50);
+    assert_eq!(result.termination, TerminationReason::ConvergedGradient);
+    assert_eq!(result.iterations, 0);
+}
+
+#[test]
+fn noisy_fit_converges_before_the_iteration_limit() {
+    let c = corrs(&NOISE);
+    let problem = Problem::new(&c, &intr(), PosePriors::default(), [0.0; 4]);
+    let result = levenberg_marquardt(perturbed_start(), &problem, 300);
+    assert!(
+        result.termination.converged(),
+        "stopped with {:?}",
+        result.termination
+    );
+    assert!(result.iterations < 300);
+    assert!(result.cost > 1e-3, "noise leaves a residual cost");
+}
+
+#[test]
+fn iteration_budget_is_reported() {
+    let c = corrs(&NOISE);
+    let problem = Problem::new(&c, &intr(), PosePriors::default(), [0.0; 4]);
+    let result = levenberg_marquardt(perturbed_start(), &problem, 1);
+    assert_eq!(result.termination, TerminationReason::MaxIterations);
+    assert_eq!(result.iterations, 1);
+    assert!(!result.termination.converged());
+}
+
+#[test]
+fn lm_control_applies_the_shared_stopping_rules() {
+    let mut lm = LmControl::new(1.0);
+    assert!(!lm.gradient_converged(1.0, 1.0));
+    assert!(lm.gradient_converged(1e-11, 1.0));
+    assert!(lm.gradient_converged(0.5, 0.0));
+
+    assert_eq!(lm.accept(1.0, 0.5, 1e-6, 1.0), None);
+    assert_eq!(
+        lm.accept(1.0, 0.5, 1e-12, 1.0),
+        Some(TerminationReason::ConvergedStep)
+    );
+    assert_eq!(
+        lm.accept(1.0, 1.0 - 1e-14, 1e-6, 1.0),
+        Some(TerminationReason::ConvergedCost)
+    );
+    let mut raises = 0;
+    while lm.can_damp() {
+        lm.reject();
+        raises += 1;
+    }
+    assert!(raises > 30, "λ overflowed after {raises} raises");
+}
+
+#[test]
+fn restart_at_the_minimum_overflows_lambda() {
+    // Restart from the previous optimum until no step lowers the cost at
+    // all; the steps there are tiny, but rejected, so λ must overflow
+    // rather than the solve claiming a converged step.
+    let c = corrs(&NOISE);
+    let problem = Problem::new(&c, &intr(), PosePriors::default(), [0.0; 4]);
+    let mut result = levenberg_marquardt(perturbed_start(), &problem, 300);
+    for _ in 0..10 {
+        let restart = levenberg_marquardt(result.params, &problem, 300);
+        if restart.params == result.params {
+            assert_eq!(restart.termination, TerminationReason::LambdaOverflow);
+            assert_eq!(restart.iterations, 1);
+            assert!(!restart.termination.converged());
+            return;
+        }
+        result = restart;
+    }
+    panic!("the cost kept decreasing after ten restarts");
+}
+
+// ── Solve response ──────────────────────────────────────────────────────────
+
+fn noisy_request() -> crate::types::SolveRequest {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..5]);
+    for (c, (du, dv)) in req.correspondences.iter_mut().zip(NOISE) {
+        c.pixel.u += du;
+        c.pixel.v += dv;
+    }
+    req.model = Some(SolverModel {
+        estimate_distortion: false,
+        ..SolverModel::default()
+    });
+    req
+}
+
+#[test]
+fn noisy_solve_reports_convergence_without_a_warning() {
+    let response = solve_to_response(noisy_request());
+    let d = &response.diagnostics;
+    assert!(d.termination.unwrap().converged(), "{:?}", d.termination);
+    assert!(
+        !d.warnings.iter().any(|w| w.contains("converge")),
+        "{:?}",
+        d.warnings
+    );
+}
+
+#[test]
+fn termination_serialises_in_camel_case() {
+    let json = serde_json::to_value(solve_to_response(noisy_request())).unwrap();
+    let reason = json["diagnostics"]["termination"].as_str().unwrap();
+    assert!(
+        ["convergedGradient", "convergedStep", "convergedCost"].contains(&reason),
+        "{reason}"
+    );
+    assert!(json["diagnostics"]["iterations"].is_u64());
+
+    let single = base_request(vec![sample_corr("p1", 100.0, 200.0, 51.90, 4.46, 20.0)]);
+    let json = serde_json::to_value(solve_to_response(single)).unwrap();
+    assert!(json["diagnostics"].get("termination").is_none());
+    assert_eq!(json["diagnostics"]["iterations"], 0);
+}
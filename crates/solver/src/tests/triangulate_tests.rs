@@ -0,0 +1,225 @@
+use serde_json::{json, Value};
+
+use crate::geo::{enu_to_lla, lla_to_enu};
+use crate::types::{ReprojectRequest, TriangulateRequest, TriangulateResponse};
+use crate::{reproject_impl, triangulate, triangulate_impl, SolveError};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+const REF: (f64, f64, f64) = (51.9080, 4.4700, 0.0);
+
+/// The feature: a window corner 40 m north, 12 m up.
+const TARGET: [f64; 3] = [5.0, 40.0, 12.0];
+
+fn lla(enu: [f64; 3]) -> (f64, f64, f64) {
+    enu_to_lla(enu, REF.0, REF.1, REF.2)
+}
+
+fn enu_of(resp: &TriangulateResponse) -> [f64; 3] {
+    lla_to_enu(resp.lat, resp.lon, resp.alt, REF.0, REF.1, REF.2)
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// A solved camera at ENU `centre` aimed at `target`, seeing it at the
+/// pixel `reproject_impl` predicts, as request JSON.
+fn view(centre: [f64; 3], target: [f64; 3], intrinsics: Value) -> Value {
+    let d = [
+        target[0] - centre[0],
+        target[1] - centre[1],
+        target[2] - centre[2],
+    ];
+    // Off-centre aim, so the feature does not sit on the principal point.
+    let yaw = d[0].atan2(d[1]).to_degrees() + 3.0;
+    let pitch = d[2].atan2(d[0].hypot(d[1])).to_degrees() - 2.0;
+    let (lat, lon, alt) = lla(centre);
+    let pose = json!({
+        "lat": lat, "lon": lon, "alt": alt,
+        "yawDeg": yaw, "pitchDeg": pitch, "rollDeg": 1.0,
+    });
+    let (tlat, tlon, talt) = lla(target);
+    let req: ReprojectRequest = serde_json::from_value(json!({
+        "pose": pose,
+        "intrinsics": intrinsics,
+        "image": { "width": 4000, "height": 3000 },
+        "points": [{ "id": "target", "world": { "lat": tlat, "lon": tlon, "alt": talt } }],
+    }))
+    .unwrap();
+    let px = &reproject_impl(&req).unwrap().pixels[0];
+    json!({
+        "pose": pose,
+        "intrinsics": intrinsics,
+        "pixel": { "u": px.u.unwrap(), "v": px.v.unwrap() },
+    })
+}
+
+fn pinhole() -> Value {
+    json!({ "focalPx": 3000.0, "cx": 2000.0, "cy": 1500.0 })
+}
+
+/// Three cameras along the street, 8 m apart.
+fn street() -> Value {
+    let views: Vec<Value> = [[-8.0, 0.0, 2.0], [0.0, 0.0, 2.0], [8.0, 1.0, 2.0]]
+        .into_iter()
+        .map(|c| view(c, TARGET, pinhole()))
+        .collect();
+    json!({ "schemaVersion": 1, "views": views })
+}
+
+fn triangulate_json(req: Value) -> Result<TriangulateResponse, SolveError> {
+    let req: TriangulateRequest = serde_json::from_value(req).expect("request must parse");
+    triangulate_impl(&req)
+}
+
+// ── Recovery ────────────────────────────────────────────────────────────────
+
+#[test]
+fn three_views_recover_the_point() {
+    let resp = triangulate_json(street()).expect("triangulation must succeed");
+    let err = distance(enu_of(&resp), TARGET);
+    assert!(err < 1e-3, "off by {err} m");
+    assert!(resp.rmse_px < 1e-6, "rmse {}", resp.rmse_px);
+    assert!(resp.termination.converged());
+    assert!(resp.warnings.is_empty(), "{:?}", resp.warnings);
+
+    // Outer cameras at ±8 m, 40 m from the feature: about 2·atan(8/40).
+    let angle = resp.triangulation_angle_deg;
+    assert!((20.0..24.0).contains(&angle), "angle {angle}");
+    assert_eq!(resp.covariance.labels, ["east", "north", "up"]);
+    assert_eq!(resp.residuals_px.len(), 3);
+}
+
+#[test]
+fn distortion_is_modelled() {
+    let intrinsics = json!({
+        "focalPx": 2500.0, "cx": 1990.0, "cy": 1510.0,
+        "k1": -0.12, "k2": 0.03, "p1": 0.001, "p2": -0.0005,
+    });
+    let views: Vec<Value> = [[-6.0, 0.0, 2.0], [6.0, -1.0, 1.5]]
+        .into_iter()
+        .map(|c| view(c, TARGET, intrinsics.clone()))
+        .collect();
+    let resp = triangulate_json(json!({ "views": views })).unwrap();
+    let err = distance(enu_of(&resp), TARGET);
+    assert!(err < 1e-3, "off by {err} m");
+}
+
+#[test]
+fn distant_cameras_use_their_own_level() {
+    // Cameras 3 km apart see a mast 2 km away: their ENU axes differ by
+    // about 0.03°, worth ~1 m at that range if ignored.
+    let mast = [0.0, 2000.0, 150.0];
+    let views: Vec<Value> = [[-1500.0, 0.0, 30.0], [1500.0, 0.0, 30.0]]
+        .into_iter()
+        .map(|c| view(c, mast, pinhole()))
+        .collect();
+    let resp = triangulate_json(json!({ "views": views })).unwrap();
+    let err = distance(enu_of(&resp), mast);
+    assert!(err < 0.01, "off by {err} m");
+}
+
+#[test]
+fn disabled_views_are_ignored() {
+    let mut req = street();
+    let mut bad = view([20.0, 0.0, 2.0], TARGET, pinhole());
+    bad["pixel"]["u"] = json!(100.0);
+    bad["enabled"] = json!(false);
+    req["views"].as_array_mut().unwrap().push(bad);
+
+    let resp = triangulate_json(req).unwrap();
+    assert!(distance(enu_of(&resp), TARGET) < 1e-3);
+    assert_eq!(resp.residuals_px.len(), 4);
+    assert!(resp.residuals_px[3].is_none());
+}
+
+// ── Uncertainty ─────────────────────────────────────────────────────────────
+
+#[test]
+fn narrow_baseline_is_uncertain_in_depth() {
+    let narrow: Vec<Value> = [[-0.5, 0.0, 2.0], [0.5, 0.0, 2.0]]
+        .into_iter()
+        .map(|c| view(c, TARGET, pinhole()))
+        .collect();
+    let narrow = triangulate_json(json!({ "views": narrow })).unwrap();
+    let wide = triangulate_json(street()).unwrap();
+
+    assert!(narrow.triangulation_angle_deg < 2.0);
+    assert!(narrow.warnings[0].contains("poorly determined"));
+    // Depth (north) dominates the narrow covariance; σ is at least 1 px.
+    let var = |r: &TriangulateResponse, k: usize| r.covariance.matrix[4 * k];
+    assert!(var(&narrow, 1) > 30.0 * var(&narrow, 0));
+    assert!(var(&narrow, 1) > 100.0 * var(&wide, 1));
+    assert!(wide.horizontal_ellipse.semi_major_m > 0.0);
+    assert!(narrow.horizontal_ellipse.semi_major_m > 10.0 * wide.horizontal_ellipse.semi_major_m);
+}
+
+#[test]
+fn a_bad_click_is_reported() {
+    let mut req = street();
+    let u = req["views"][2]["pixel"]["u"].as_f64().unwrap();
+    req["views"][2]["pixel"]["u"] = json!(u + 40.0);
+    let resp = triangulate_json(req).unwrap();
+    assert!(resp.rmse_px > 5.0);
+    assert!(
+        resp.warnings.iter().any(|w| w.starts_with("views[2]")),
+        "{:?}",
+        resp.warnings
+    );
+}
+
+// ── Problems ────────────────────────────────────────────────────────────────
+
+#[test]
+fn needs_two_enabled_views() {
+    let mut req = street();
+    req["views"][1]["enabled"] = json!(false);
+    req["views"][2]["enabled"] = json!(false);
+    let err = triangulate_json(req).err().unwrap();
+    assert_eq!(err.details()["field"], "views");
+}
+
+#[test]
+fn invalid_views_name_the_field() {
+    let mut req = street();
+    req["views"][1]["intrinsics"]["focalPx"] = json!(0.0);
+    let err = triangulate_json(req).err().unwrap();
+    assert_eq!(err.details()["field"], "views[1].intrinsics.focalPx");
+
+    let mut req = street();
+    req["views"][0]["pose"]["lat"] = json!(95.0);
+    let err = triangulate_json(req).err().unwrap();
+    assert_eq!(err.details()["field"], "views[0].pose.lat");
+}
+
+#[test]
+fn parallel_rays_are_degenerate() {
+    // Both cameras look north and see the feature at the principal point.
+    let views: Vec<Value> = [[0.0, 0.0, 2.0], [5.0, 0.0, 2.0]]
+        .into_iter()
+        .map(|c| {
+            let (lat, lon, alt) = lla(c);
+            json!({
+                "pose": { "lat": lat, "lon": lon, "alt": alt, "yawDeg": 0.0, "pitchDeg": 0.0, "rollDeg": 0.0 },
+                "intrinsics": pinhole(),
+                "pixel": { "u": 2000.0, "v": 1500.0 },
+            })
+        })
+        .collect();
+    match triangulate_json(json!({ "views": views })) {
+        Err(SolveError::DegenerateGeometry { .. }) => {}
+        other => panic!("expected degenerate geometry, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn wasm_entry_point_returns_camel_case_json() {
+    let out = triangulate(street().to_string()).expect("triangulation must succeed");
+    let value: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(value["schemaVersion"], 1);
+    assert!(value["triangulationAngleDeg"].as_f64().unwrap() > 20.0);
+    assert_eq!(value["covariance"]["matrix"].as_array().unwrap().len(), 9);
+    assert!(value["horizontalEllipse"]["semiMajorM"].is_number());
+    assert_eq!(value["residualsPx"].as_array().unwrap().len(), 3);
+}
@@ -0,0 +1,166 @@
+use crate::estimator::build_covariance;
+use crate::geo::metres_per_degree;
+use crate::optimizer::{OptResult, FOCAL, NUM_PARAMS};
+use crate::types::{SolveResponse, SolverModel, TerminationReason};
+use crate::uncertainty::error_ellipse;
+
+use super::helpers::{
+    base_request, camera, sample_corr, solve_to_response, synthetic_request, SCENE,
+};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Six points with a pixel or two of click error, distortion fixed.
+fn noisy_response() -> SolveResponse {
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &SCENE[..6]);
+    for (i, c) in req.correspondences.iter_mut().enumerate() {
+        c.pixel.u += [1.2, -0.7, 0.4, -1.5, 0.9, -0.3][i];
+        c.pixel.v += [-0.6, 1.1, -1.3, 0.2, 0.8, -0.9][i];
+    }
+    req.model = Some(SolverModel {
+        estimate_distortion: false,
+        ..SolverModel::default()
+    });
+    solve_to_response(req)
+}
+
+fn variance(response: &SolveResponse, label: &str) -> f64 {
+    let cov = &response.covariance;
+    let n = cov.labels.len();
+    let i = cov.labels.iter().position(|l| l == label).unwrap();
+    cov.matrix[i * n + i]
+}
+
+// ── Building blocks ─────────────────────────────────────────────────────────
+
+#[test]
+fn metres_per_degree_follows_the_ellipsoid() {
+    let (m_lat, m_lon) = metres_per_degree(0.0, 0.0);
+    assert!((m_lat - 110_574.3).abs() < 1.0, "{m_lat}");
+    assert!((m_lon - 111_319.5).abs() < 1.0, "{m_lon}");
+    let (m_lat, m_lon) = metres_per_degree(45.0, 0.0);
+    assert!((m_lat - 111_132.0).abs() < 1.0, "{m_lat}");
+    assert!((m_lon - 78_846.8).abs() < 1.0, "{m_lon}");
+}
+
+#[test]
+fn ellipse_of_axis_aligned_covariance() {
+    // σ_east = 2 m, σ_north = 1 m
+    let e = error_ellipse(&[[4.0, 0.0], [0.0, 1.0]], 5.991, 0.95);
+    assert!((e.semi_major_m - (5.991f64 * 4.0).sqrt()).abs() < 1e-12);
+    assert!((e.semi_minor_m - 5.991f64.sqrt()).abs() < 1e-12);
+    assert!((e.azimuth_deg - 90.0).abs() < 1e-12);
+}
+
+#[test]
+fn ellipse_of_correlated_covariance_points_northeast() {
+    // Eigenvalues 4 (along NE) and 1 (along NW)
+    let e = error_ellipse(&[[2.5, 1.5], [1.5, 2.5]], 1.0, 0.95);
+    assert!((e.semi_major_m - 2.0).abs() < 1e-12);
+    assert!((e.semi_minor_m - 1.0).abs() < 1e-12);
+    assert!((e.azimuth_deg - 45.0).abs() < 1e-9);
+
+    let e = error_ellipse(&[[2.5, -1.5], [-1.5, 2.5]], 1.0, 0.95);
+    assert!((e.azimuth_deg - 135.0).abs() < 1e-9);
+}
+
+#[test]
+fn unconstrained_parameter_has_no_linearised_covariance() {
+    // Pose and focal length free; the focal column carries no information.
+    let mut free = [false; NUM_PARAMS];
+    free[..6].fill(true);
+    free[FOCAL] = true;
+    let mut result = OptResult {
+        params: [0.0; NUM_PARAMS],
+        cost: 1.0,
+        iterations: 1,
+        termination: TerminationReason::ConvergedGradient,
+        jtj: [0.0; NUM_PARAMS * NUM_PARAMS],
+        free,
+    };
+    for i in (0..NUM_PARAMS).filter(|&i| i != FOCAL) {
+        result.jtj[i * NUM_PARAMS + i] = 1.0;
+    }
+    assert!(build_covariance(&result, &[], 51.9, 0.0).is_none());
+
+    result.jtj[FOCAL * NUM_PARAMS + FOCAL] = 1.0;
+    assert!(build_covariance(&result, &[], 51.9, 0.0).is_some());
+}
+
+// ── Solve response ──────────────────────────────────────────────────────────
+
+#[test]
+fn ellipse_matches_the_position_covariance() {
+    let response = noisy_response();
+    let e = &response.uncertainty.horizontal_ellipse;
+    assert_eq!(e.confidence, 0.95);
+    assert!(e.semi_major_m.is_finite() && e.semi_major_m > 0.0);
+    assert!(e.semi_minor_m > 0.0 && e.semi_minor_m <= e.semi_major_m);
+    assert!((0.0..180.0).contains(&e.azimuth_deg));
+
+    // The ellipse's axes bracket the 95 % extent along North and East.
+    let (m_lat, m_lon) = metres_per_degree(response.pose.lat, response.pose.alt);
+    let k = 5.991_464_547f64.sqrt();
+    for extent in [
+        k * variance(&response, "lat").sqrt() * m_lat,
+        k * variance(&response, "lon").sqrt() * m_lon,
+    ] {
+        assert!(extent <= e.semi_major_m * (1.0 + 1e-9));
+        assert!(extent >= e.semi_minor_m * (1.0 - 1e-9));
+    }
+}
+
+#[test]
+fn intervals_are_centred_on_the_estimate() {
+    let response = noisy_response();
+    let u = &response.uncertainty;
+    for (interval, value, label) in [
+        (&u.alt, response.pose.alt, "alt"),
+        (&u.yaw_deg, response.pose.yaw_deg, "yawDeg"),
+        (&u.pitch_deg, response.pose.pitch_deg, "pitchDeg"),
+        (&u.roll_deg, response.pose.roll_deg, "rollDeg"),
+    ] {
+        assert!((interval.sigma - variance(&response, label).sqrt()).abs() < 1e-12);
+        assert!(interval.sigma > 0.0, "{label}");
+        assert!((interval.lower95 + interval.upper95 - 2.0 * value).abs() < 1e-9);
+        assert!((interval.upper95 - value - 1.959_964 * interval.sigma).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn correlation_has_unit_diagonal_for_free_parameters() {
+    let response = noisy_response();
+    let corr = &response.uncertainty.correlation;
+    let n = corr.labels.len();
+    assert_eq!(corr.labels, response.covariance.labels);
+    for i in 0..n {
+        let free = variance(&response, &corr.labels[i]) > 0.0;
+        assert_eq!(corr.matrix[i * n + i], if free { 1.0 } else { 0.0 });
+        for j in 0..n {
+            let r = corr.matrix[i * n + j];
+            assert!((-1.0..=1.0).contains(&r));
+            assert!((r - corr.matrix[j * n + i]).abs() < 1e-12);
+        }
+    }
+    // Distortion is fixed in this request.
+    let k1 = corr.labels.iter().position(|l| l == "k1").unwrap();
+    assert!(corr.matrix[k1 * n..(k1 + 1) * n].iter().all(|r| *r == 0.0));
+}
+
+#[test]
+fn single_point_fallback_still_reports_a_summary() {
+    let req = base_request(vec![sample_corr("p1", 100.0, 200.0, 51.90, 4.46, 20.0)]);
+    let response = solve_to_response(req);
+    assert!(response.uncertainty.horizontal_ellipse.semi_major_m > 0.0);
+    assert!((response.uncertainty.yaw_deg.sigma - 5.0).abs() < 1e-12);
+}
+
+#[test]
+fn summary_serialises_in_camel_case() {
+    let json = serde_json::to_value(noisy_response()).unwrap();
+    let u = &json["uncertainty"];
+    assert!(u["horizontalEllipse"]["semiMajorM"].is_f64());
+    assert!(u["horizontalEllipse"]["azimuthDeg"].is_f64());
+    assert!(u["yawDeg"]["lower95"].is_f64());
+    assert_eq!(u["correlation"]["labels"][0], "lat");
+}
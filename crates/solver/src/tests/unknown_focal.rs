@@ -0,0 +1,113 @@
+use crate::types::{RansacCfg, SolveRequest, SolverModel};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request, SCENE};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// An archive photo: true focal 2000 px on a 4000 px wide image, but no
+/// `focalPx` (so the default guess is 3600 px) and no model settings.
+fn request_without_exif(world: &[(f64, f64, f64)]) -> SolveRequest {
+    let mut req = synthetic_request(&camera(), 2000.0, (4000.0, 3000.0), world);
+    req.priors = None;
+    req
+}
+
+fn assert_recovered(req: SolveRequest) {
+    let response = solve_to_response(req);
+    assert!(
+        (response.intrinsics.focal_px - 2000.0).abs() < 2.0,
+        "focal = {:.1}",
+        response.intrinsics.focal_px
+    );
+    let cam = camera();
+    let err = haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon);
+    assert!(err < 1.0, "position error {:.2} m", err);
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn missing_focal_is_estimated_by_default() {
+    assert_recovered(request_without_exif(&SCENE));
+}
+
+#[test]
+fn missing_focal_is_estimated_from_four_points() {
+    let mut req = request_without_exif(&SCENE[..4]);
+    req.model = Some(SolverModel {
+        estimate_focal: None,
+        estimate_distortion: false,
+        estimate_principal_point: false,
+        uncalibrated: false,
+    });
+    assert_recovered(req);
+}
+
+#[test]
+fn explicitly_fixed_focal_keeps_the_default_guess() {
+    let mut req = request_without_exif(&SCENE);
+    req.model = Some(SolverModel {
+        estimate_focal: Some(false),
+        ..SolverModel::default()
+    });
+    let response = solve_to_response(req);
+    assert_eq!(response.intrinsics.focal_px, 3600.0);
+}
+
+#[test]
+fn missing_focal_with_three_points_warns_and_keeps_the_guess() {
+    let response = solve_to_response(request_without_exif(&SCENE[..3]));
+    assert_eq!(response.intrinsics.focal_px, 3600.0);
+    assert!(response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.contains("Focal length estimation needs at least 4")));
+}
+
+#[test]
+fn ransac_uses_four_point_samples_without_exif() {
+    let mut req = request_without_exif(&SCENE);
+    // Mistyped latitude (~330 m off) with the true pixel kept.
+    req.correspondences[2].world.lat += 0.003;
+    req.ransac = Some(RansacCfg {
+        max_iters: 2000,
+        inlier_px: 2.0,
+        target_prob: 0.999,
+        seed: None,
+    });
+    let response = solve_to_response(req);
+
+    assert!(!response
+        .diagnostics
+        .inlier_ids
+        .contains(&"pt_2".to_string()));
+    assert_eq!(response.diagnostics.inlier_ids.len(), 7);
+    assert!((response.intrinsics.focal_px - 2000.0).abs() < 2.0);
+}
+
+#[test]
+fn ransac_is_skipped_when_four_points_are_only_a_minimal_sample() {
+    let mut req = request_without_exif(&SCENE[..4]);
+    req.ransac = Some(RansacCfg {
+        max_iters: 5000,
+        inlier_px: 2.0,
+        target_prob: 0.999,
+        seed: None,
+    });
+    let response = solve_to_response(req);
+
+    assert!(!response
+        .diagnostics
+        .warnings
+        .iter()
+        .any(|w| w.contains("RANSAC")));
+    assert!((response.intrinsics.focal_px - 2000.0).abs() < 2.0);
+}
+
+#[test]
+fn known_focal_stays_fixed() {
+    let req = synthetic_request(&camera(), 2000.0, (4000.0, 3000.0), &SCENE);
+    let response = solve_to_response(req);
+    assert_eq!(response.intrinsics.focal_px, 2000.0);
+}
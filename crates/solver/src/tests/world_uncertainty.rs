@@ -0,0 +1,118 @@
+use crate::optimizer::{
+    propagate_world_sigma, set_rotation, EnuCorrespondence, CX, FOCAL, NUM_PARAMS,
+};
+use crate::projection::rotation_enu_to_cam;
+use crate::types::{SolveRequest, SolverModel, WorldLla};
+
+use super::helpers::{camera, haversine_m, solve_to_response, synthetic_request};
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Six distant, precisely surveyed points plus three points ~30 m in front
+/// of the camera whose map clicks landed 6 m too far north.
+fn request_with_sloppy_near_points(near_sigma_m: Option<f64>) -> SolveRequest {
+    let world = [
+        (51.9110, 4.4660, 40.0),
+        (51.9120, 4.4700, 80.0),
+        (51.9115, 4.4740, 60.0),
+        (51.9105, 4.4680, 25.0),
+        (51.9130, 4.4720, 100.0),
+        (51.9125, 4.4675, 55.0),
+        // near points
+        (51.90827, 4.46985, 0.0),
+        (51.90830, 4.47015, 0.5),
+        (51.90835, 4.47000, 1.0),
+    ];
+    let mut req = synthetic_request(&camera(), 3000.0, (4000.0, 3000.0), &world);
+    for c in &mut req.correspondences[6..] {
+        c.world.lat += 6.0 / 111_320.0;
+        c.world.sigma_m = near_sigma_m;
+    }
+    req.model = Some(SolverModel {
+        estimate_focal: Some(false),
+        estimate_distortion: false,
+        estimate_principal_point: false,
+        uncalibrated: false,
+    });
+    req
+}
+
+fn position_error_m(req: SolveRequest) -> f64 {
+    let response = solve_to_response(req);
+    let cam = camera();
+    haversine_m(response.pose.lat, response.pose.lon, cam.lat, cam.lon)
+}
+
+fn params_looking_north(focal_px: f64) -> [f64; NUM_PARAMS] {
+    let mut p = [0.0; NUM_PARAMS];
+    set_rotation(&mut p, &rotation_enu_to_cam(0.0, 0.0, 0.0));
+    p[FOCAL] = focal_px;
+    p[CX] = 500.0;
+    p[CX + 1] = 500.0;
+    p
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn world_sigma_inflates_pixel_sigma_by_depth() {
+    let corrs = [
+        EnuCorrespondence {
+            enu: [0.0, 100.0, 0.0],
+            pixel: [500.0, 500.0],
+            sigma: 2.0,
+            world_sigma: 0.1,
+        },
+        EnuCorrespondence {
+            enu: [0.0, 10.0, 0.0],
+            pixel: [500.0, 500.0],
+            sigma: 2.0,
+            world_sigma: 0.0,
+        },
+    ];
+    let out = propagate_world_sigma(&params_looking_north(1000.0), &corrs);
+
+    // f·σ/z = 1000 · 0.1 / 100 = 1 px, combined in quadrature with 2 px.
+    assert!((out[0].sigma - 5.0f64.sqrt()).abs() < 1e-9);
+    // Exact world points keep their pixel sigma.
+    assert_eq!(out[1].sigma, 2.0);
+}
+
+#[test]
+fn world_sigma_is_ignored_behind_the_camera() {
+    let corrs = [EnuCorrespondence {
+        enu: [0.0, -50.0, 0.0],
+        pixel: [500.0, 500.0],
+        sigma: 1.5,
+        world_sigma: 10.0,
+    }];
+    let out = propagate_world_sigma(&params_looking_north(1000.0), &corrs);
+    assert_eq!(out[0].sigma, 1.5);
+}
+
+#[test]
+fn uncertain_near_points_stop_dominating_precise_far_points() {
+    let exact = position_error_m(request_with_sloppy_near_points(None));
+    let modelled = position_error_m(request_with_sloppy_near_points(Some(8.0)));
+
+    assert!(
+        modelled < 1.0,
+        "with sigmaM the pose should follow the precise points, error {:.2} m",
+        modelled
+    );
+    assert!(
+        modelled < exact,
+        "sigmaM should help: {:.2} m with vs {:.2} m without",
+        modelled,
+        exact
+    );
+}
+
+#[test]
+fn world_sigma_parses_from_json() {
+    let w: WorldLla =
+        serde_json::from_str(r#"{"lat": 51.9, "lon": 4.47, "sigmaM": 12.5}"#).unwrap();
+    assert_eq!(w.sigma_m, Some(12.5));
+    let w: WorldLla = serde_json::from_str(r#"{"lat": 51.9, "lon": 4.47}"#).unwrap();
+    assert_eq!(w.sigma_m, None);
+}
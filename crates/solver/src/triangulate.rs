@@ -0,0 +1,345 @@
+use crate::error::SolveError;
+use crate::geo::{enu_to_lla, lla_to_enu};
+use crate::linalg::{invert3, solve_linear};
+use crate::optimizer::LmControl;
+use crate::projection::{
+    dot, mat3_mul, mat3_t_vec, mat3_vec, project_cam_point_jacobian, rotation_enu_to_cam,
+    undistort_pixel, CameraIntrinsics, Mat3,
+};
+use crate::schema::SCHEMA_VERSION;
+use crate::types::{
+    Covariance, TerminationReason, TriangulateRequest, TriangulateResponse, TriangulateView,
+};
+use crate::uncertainty::{error_ellipse, CHI2_2DOF_95};
+use crate::validate::validate_triangulate;
+
+/// Refinement iterations; three unknowns converge in a handful.
+const MAX_ITERS: usize = 50;
+
+/// Rays meeting at less than this angle (deg) are parallel as far as
+/// clicked pixels can tell.
+const MIN_ANGLE_DEG: f64 = 1e-3;
+
+/// Rays meeting at less than this angle (deg) get a warning: the distance
+/// along them is poorly determined.
+const NARROW_ANGLE_DEG: f64 = 2.0;
+
+/// Views reprojecting further than this (px) from their click get a
+/// warning: a wrong click or a poorly solved pose.
+const MAX_RESIDUAL_PX: f64 = 5.0;
+
+/// An enabled view in the common ENU frame.
+struct View {
+    centre: [f64; 3],
+    /// Common ENU → camera.
+    rot: Mat3,
+    intr: CameraIntrinsics,
+    pixel: [f64; 2],
+    sigma: f64,
+    /// Unit ENU direction of the ray through `pixel`.
+    ray: [f64; 3],
+}
+
+// ── Entry point ─────────────────────────────────────────────────────────────
+
+/// Locate a feature seen in two or more solved images.
+///
+/// Steps:
+/// 1. Express every enabled view in one ENU frame, anchored at the first
+///    enabled camera; each camera's rotation is corrected for the turn
+///    between its own ENU axes and the common ones.
+/// 2. Linear triangulation: the point closest to all undistorted viewing
+///    rays in the least-squares sense.
+/// 3. Refine the point by Levenberg-Marquardt on the reprojection error,
+///    distortion included, each view weighted by `pixel.sigmaPx`.
+/// 4. Covariance `σ² (JᵀJ)⁻¹`, with `σ²` the reduced χ² but never below 1,
+///    so a perfect fit still reports the uncertainty of the clicks.  The
+///    uncertainty of the poses themselves is not included.
+pub fn triangulate_impl(req: &TriangulateRequest) -> Result<TriangulateResponse, SolveError> {
+    validate_triangulate(req)?;
+
+    let enabled: Vec<(usize, &TriangulateView)> = req
+        .views
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| v.enabled.unwrap_or(true))
+        .collect();
+    let origin = &enabled[0].1.pose;
+    let (ref_lat, ref_lon, ref_alt) = (origin.lat, origin.lon, origin.alt);
+    let views = enabled
+        .iter()
+        .map(|&(i, v)| view(i, v, ref_lat, ref_lon, ref_alt))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let rays: Vec<([f64; 3], [f64; 3])> = views.iter().map(|v| (v.centre, v.ray)).collect();
+    let angle = widest_angle_deg(&rays);
+    if angle < MIN_ANGLE_DEG {
+        return Err(SolveError::DegenerateGeometry {
+            reason: "the viewing rays are parallel".to_string(),
+        });
+    }
+    let initial = intersect_rays(&rays).ok_or_else(|| SolveError::DegenerateGeometry {
+        reason: "the viewing rays do not meet in front of the cameras".to_string(),
+    })?;
+    let (x, termination) = refine(&views, initial);
+    let normal = normal_equations(&views, &x).ok_or_else(|| SolveError::DegenerateGeometry {
+        reason: "the refined point lies behind a camera".to_string(),
+    })?;
+
+    let n_obs = 2 * views.len();
+    let sigma2 = if n_obs > 3 {
+        (normal.cost / (n_obs - 3) as f64).max(1.0)
+    } else {
+        1.0
+    };
+    let cov = invert3(&normal.jtj).ok_or(SolveError::SingularCovariance)?;
+    let (lat, lon, alt) = enu_to_lla(x, ref_lat, ref_lon, ref_alt);
+    // Into the ENU axes at the point itself.
+    let turn = frame_rotation(ref_lat, ref_lon, lat, lon);
+    let cov =
+        mat3_mul(&mat3_mul(&turn, &cov), &transpose(&turn)).map(|row| row.map(|v| v * sigma2));
+
+    let mut errors = vec![None; req.views.len()];
+    for ((i, _), v) in enabled.iter().zip(&views) {
+        errors[*i] = reprojection_error(v, &x);
+    }
+    let used: Vec<f64> = errors.iter().flatten().copied().collect();
+    let rmse_px = (used.iter().map(|e| e * e).sum::<f64>() / used.len() as f64).sqrt();
+
+    let mut warnings = Vec::new();
+    if angle < NARROW_ANGLE_DEG {
+        warnings.push(format!(
+            "Viewing rays meet at only {angle:.2}°; the distance along them is poorly determined."
+        ));
+    }
+    for (i, e) in errors.iter().enumerate() {
+        if let Some(e) = e.filter(|e| *e > MAX_RESIDUAL_PX) {
+            warnings.push(format!(
+                "views[{i}] reprojects {e:.1} px from its pixel; check the click and that view's pose."
+            ));
+        }
+    }
+    if !termination.converged() {
+        warnings.push(format!("Refinement stopped early: {termination:?}."));
+    }
+
+    Ok(TriangulateResponse {
+        schema_version: SCHEMA_VERSION,
+        lat,
+        lon,
+        alt,
+        covariance: Covariance {
+            matrix: cov.iter().flatten().copied().collect(),
+            labels: ["east", "north", "up"].map(String::from).to_vec(),
+        },
+        horizontal_ellipse: error_ellipse(
+            &[[cov[0][0], cov[0][1]], [cov[1][0], cov[1][1]]],
+            CHI2_2DOF_95,
+            0.95,
+        ),
+        triangulation_angle_deg: angle,
+        rmse_px,
+        residuals_px: errors,
+        termination,
+        warnings,
+    })
+}
+
+/// `view` (request index `i`) in the ENU frame at `ref_*`.
+fn view(
+    i: usize,
+    view: &TriangulateView,
+    ref_lat: f64,
+    ref_lon: f64,
+    ref_alt: f64,
+) -> Result<View, SolveError> {
+    let pose = &view.pose;
+    let intr = CameraIntrinsics::from(&view.intrinsics);
+    let (u, v) = (view.pixel.u, view.pixel.v);
+    let (xn, yn) = undistort_pixel(u, v, &intr).ok_or_else(|| SolveError::InvalidInput {
+        field: format!("views[{i}].intrinsics"),
+        message: "Pixel could not be undistorted with these coefficients".to_string(),
+    })?;
+    let local = rotation_enu_to_cam(pose.yaw_deg, pose.pitch_deg, pose.roll_deg);
+    let rot = mat3_mul(
+        &local,
+        &frame_rotation(ref_lat, ref_lon, pose.lat, pose.lon),
+    );
+    let d = mat3_t_vec(&rot, &[xn, yn, 1.0]);
+    let n = dot(&d, &d).sqrt();
+    Ok(View {
+        centre: lla_to_enu(pose.lat, pose.lon, pose.alt, ref_lat, ref_lon, ref_alt),
+        rot,
+        intr,
+        pixel: [u, v],
+        sigma: view.pixel.sigma_px.unwrap_or(1.0).max(1e-6),
+        ray: d.map(|c| c / n),
+    })
+}
+
+// ── Linear triangulation ────────────────────────────────────────────────────
+
+/// The point closest to all rays `(origin, unit direction)` in the
+/// least-squares sense: `Σ (I − d dᵀ) x = Σ (I − d dᵀ) c`.  `None` for
+/// fewer than two rays, parallel rays or a point behind one of the origins.
+pub(crate) fn intersect_rays(rays: &[([f64; 3], [f64; 3])]) -> Option<[f64; 3]> {
+    if rays.len() < 2 {
+        return None;
+    }
+    let mut a = [[0.0; 3]; 3];
+    let mut b = [0.0; 3];
+    for (c, d) in rays {
+        for i in 0..3 {
+            for j in 0..3 {
+                let m = if i == j { 1.0 } else { 0.0 } - d[i] * d[j];
+                a[i][j] += m;
+                b[i] += m * c[j];
+            }
+        }
+    }
+    let x = solve_linear(&a, &b)?;
+    let in_front = rays
+        .iter()
+        .all(|(c, d)| dot(d, &[x[0] - c[0], x[1] - c[1], x[2] - c[2]]) > 0.0);
+    in_front.then_some(x)
+}
+
+/// Largest angle (deg) between the directions of any two rays.
+pub(crate) fn widest_angle_deg(rays: &[([f64; 3], [f64; 3])]) -> f64 {
+    let widest = rays
+        .iter()
+        .flat_map(|(_, a)| rays.iter().map(move |(_, b)| dot(a, b)))
+        .fold(1.0f64, f64::min);
+    widest.clamp(-1.0, 1.0).acos().to_degrees()
+}
+
+// ── Refinement ──────────────────────────────────────────────────────────────
+
+/// Gauss-Newton normal equations of the whitened reprojection errors.
+struct Normal {
+    jtj: Mat3,
+    jtr: [f64; 3],
+    cost: f64,
+}
+
+/// `None` when `x` is behind one of the cameras.
+#[allow(clippy::needless_range_loop)]
+fn normal_equations(views: &[View], x: &[f64; 3]) -> Option<Normal> {
+    let mut n = Normal {
+        jtj: [[0.0; 3]; 3],
+        jtr: [0.0; 3],
+        cost: 0.0,
+    };
+    for v in views {
+        let p = mat3_vec(
+            &v.rot,
+            &[x[0] - v.centre[0], x[1] - v.centre[1], x[2] - v.centre[2]],
+        );
+        let proj = project_cam_point_jacobian(p, &v.intr)?;
+        let r = [
+            (proj.u - v.pixel[0]) / v.sigma,
+            (proj.v - v.pixel[1]) / v.sigma,
+        ];
+        for (row, res) in proj.d_cam_point.iter().zip(r) {
+            // ∂p_cam/∂x = R
+            let mut j = [0.0; 3];
+            for k in 0..3 {
+                j[k] = (0..3).map(|m| row[m] * v.rot[m][k]).sum::<f64>() / v.sigma;
+            }
+            for a in 0..3 {
+                n.jtr[a] += j[a] * res;
+                for b in 0..3 {
+                    n.jtj[a][b] += j[a] * j[b];
+                }
+            }
+            n.cost += res * res;
+        }
+    }
+    Some(n)
+}
+
+/// Levenberg-Marquardt on the point, with the stopping rules of
+/// `LmControl`.
+#[allow(clippy::needless_range_loop)]
+fn refine(views: &[View], initial: [f64; 3]) -> ([f64; 3], TerminationReason) {
+    let mut x = initial;
+    let Some(mut normal) = normal_equations(views, &x) else {
+        return (x, TerminationReason::LambdaOverflow);
+    };
+    let mut lm = LmControl::new(1e-3);
+
+    for _ in 0..MAX_ITERS {
+        let gradient = normal.jtr.iter().fold(0.0f64, |m, g| m.max(g.abs()));
+        if lm.gradient_converged(gradient, normal.cost) {
+            return (x, TerminationReason::ConvergedGradient);
+        }
+        let mut stop = Some(TerminationReason::LambdaOverflow);
+        while lm.can_damp() {
+            let mut a = normal.jtj;
+            for i in 0..3 {
+                a[i][i] += lm.lambda() * a[i][i].max(1e-12);
+            }
+            if let Some(delta) = solve_linear(&a, &normal.jtr.map(|g| -g)) {
+                let candidate = [x[0] + delta[0], x[1] + delta[1], x[2] + delta[2]];
+                if let Some(next) = normal_equations(views, &candidate) {
+                    if next.cost < normal.cost {
+                        stop = lm.accept(
+                            normal.cost,
+                            next.cost,
+                            dot(&delta, &delta).sqrt(),
+                            dot(&x, &x).sqrt(),
+                        );
+                        x = candidate;
+                        normal = next;
+                        break;
+                    }
+                }
+            }
+            lm.reject();
+        }
+        if let Some(reason) = stop {
+            return (x, reason);
+        }
+    }
+    (x, TerminationReason::MaxIterations)
+}
+
+/// Pixel distance between the projection of `x` and the click of `v`.
+fn reprojection_error(v: &View, x: &[f64; 3]) -> Option<f64> {
+    let p = mat3_vec(
+        &v.rot,
+        &[x[0] - v.centre[0], x[1] - v.centre[1], x[2] - v.centre[2]],
+    );
+    let proj = project_cam_point_jacobian(p, &v.intr)?;
+    Some((proj.u - v.pixel[0]).hypot(proj.v - v.pixel[1]))
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// Rotation from the ENU axes at `(from_lat, from_lon)` to those at
+/// `(to_lat, to_lon)`; the identity for nearby points.
+fn frame_rotation(from_lat: f64, from_lon: f64, to_lat: f64, to_lon: f64) -> Mat3 {
+    mat3_mul(
+        &enu_axes(to_lat, to_lon),
+        &transpose(&enu_axes(from_lat, from_lon)),
+    )
+}
+
+/// East, north and up unit vectors at `(lat, lon)` as ECEF rows.
+fn enu_axes(lat: f64, lon: f64) -> Mat3 {
+    let (slat, clat) = lat.to_radians().sin_cos();
+    let (slon, clon) = lon.to_radians().sin_cos();
+    [
+        [-slon, clon, 0.0],
+        [-slat * clon, -slat * slon, clat],
+        [clat * clon, clat * slon, slat],
+    ]
+}
+
+fn transpose(m: &Mat3) -> Mat3 {
+    [
+        [m[0][0], m[1][0], m[2][0]],
+        [m[0][1], m[1][1], m[2][1]],
+        [m[0][2], m[1][2], m[2][2]],
+    ]
+}
@@ -1,22 +1,150 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize)]
+use crate::schema::JsonSchema;
+
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SolveRequest {
+    /// Schema version the request was written for (see `schema.rs`);
+    /// 0 / absent for requests that predate versioning.
+    #[serde(default)]
+    pub schema_version: u32,
     pub image: Image,
     #[serde(default)]
     pub correspondences: Vec<Corr>,
     #[serde(default)]
     pub priors: Option<Priors>,
+    /// Which intrinsics are estimated.  When absent, only the pose (and
+    /// distortion, given enough points) is estimated.
+    #[serde(default)]
+    pub model: Option<SolverModel>,
+    /// Outlier rejection settings.  When absent, every enabled
+    /// correspondence is used as-is.
+    #[serde(default)]
+    pub ransac: Option<RansacCfg>,
+    /// Final-refinement settings (robust loss).  When absent, a plain
+    /// least-squares refinement is used.
+    #[serde(default)]
+    pub refine: Option<RefineCfg>,
+    /// Sampling-based uncertainty settings.  When absent only the
+    /// linearised covariance is reported.
+    #[serde(default)]
+    pub uncertainty: Option<UncertaintyCfg>,
 }
 
-#[derive(Deserialize)]
+/// Which camera parameters the solver estimates (matches the frontend's
+/// `SolverModel`).
+///
+/// * `estimate_focal`      – optimise the focal length (at least four
+///   correspondences), with `focalPx` (if given) acting as a soft prior
+///   instead of a fixed value.  When unset it follows `focalPx`: estimated
+///   (with focal-scan minimal samples) exactly when there is no `focalPx`.  An
+///   explicit `false` keeps the focal length fixed even then.
+/// * `estimate_distortion` – optimise k1, k2, p1, p2 once there are at
+///   least five correspondences.  Defaults to `true`; when `false` the
+///   coefficients stay at the `distortion` prior (or 0).
+/// * `estimate_principal_point` – optimise cx, cy (at least five
+///   correspondences), softly tied to the image centre.  For cropped or
+///   perspective-corrected images.
+/// * `uncalibrated` – no EXIF needed: recover focal length, principal point
+///   and pose together from a direct linear transform (at least six
+///   non-coplanar correspondences), then refine.  Implies
+///   `estimate_focal` and `estimate_principal_point`.
+#[derive(Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SolverModel {
+    #[serde(default)]
+    pub estimate_focal: Option<bool>,
+    #[serde(default = "default_true")]
+    pub estimate_distortion: bool,
+    #[serde(default)]
+    pub estimate_principal_point: bool,
+    #[serde(default)]
+    pub uncalibrated: bool,
+}
+
+impl Default for SolverModel {
+    fn default() -> Self {
+        SolverModel {
+            estimate_focal: None,
+            estimate_distortion: true,
+            estimate_principal_point: false,
+            uncalibrated: false,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Hypothesize-and-verify outlier rejection settings (matches the
+/// frontend's `RansacCfg`).
+///
+/// * `max_iters`   – upper bound on the number of minimal samples tried.
+/// * `inlier_px`   – reprojection error (px) below which a point supports
+///   a hypothesis.
+/// * `target_prob` – stop early once an outlier-free sample has been drawn
+///   with at least this probability.
+/// * `seed`        – RNG seed; the default keeps solves reproducible.
+#[derive(Deserialize, JsonSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RansacCfg {
+    pub max_iters: usize,
+    pub inlier_px: f64,
+    pub target_prob: f64,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// Uncertainty settings (matches the frontend's `UncertaintyCfg`).
+#[derive(Deserialize, JsonSchema, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UncertaintyCfg {
+    #[serde(default)]
+    pub bootstrap: Option<BootstrapCfg>,
+}
+
+/// Bootstrap resampling around the final fit.
+///
+/// * `enabled` – run the bootstrap at all.
+/// * `samples` – number of refits (0 = default of 200).
+/// * `seed`    – RNG seed; the default keeps solves reproducible.
+/// * `method`  – what is resampled, see `BootstrapMethod`.
+#[derive(Deserialize, JsonSchema, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapCfg {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub samples: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub method: BootstrapMethod,
+}
+
+/// What a bootstrap sample redraws.
+#[derive(Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BootstrapMethod {
+    /// Correspondences with replacement (case resampling).  Makes no
+    /// assumption about the error model, but needs a few more points than
+    /// unknowns to say anything.
+    #[default]
+    Cases,
+    /// Residuals with replacement, added to the fitted pixel positions.
+    /// Keeps the geometry fixed, so it also works for small point sets.
+    Residuals,
+}
+
+#[derive(Deserialize, JsonSchema)]
 pub struct Image {
     pub width: f64,
     pub height: f64,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct Corr {
     pub id: String,
     pub pixel: Pixel,
@@ -25,7 +153,7 @@ pub struct Corr {
     pub enabled: Option<bool>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Pixel {
     pub u: f64,
@@ -34,16 +162,31 @@ pub struct Pixel {
     pub sigma_px: Option<f64>,
 }
 
-#[derive(Deserialize)]
+/// A world point.  `sigma_m` is the 1-σ uncertainty of its position in
+/// metres (e.g. a map click at low zoom); absent means exact.
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WorldLla {
     pub lat: f64,
     pub lon: f64,
     #[serde(default)]
     pub alt: Option<f64>,
+    #[serde(default)]
+    pub sigma_m: Option<f64>,
 }
 
-#[derive(Deserialize, Default)]
+/// Soft constraints on the camera.  Every prior is optional; a missing
+/// `sigma` falls back to a sensible default (see `GaussianPrior`).
+///
+/// * `focal_px`   – focal length (px).  Fixed unless `model.estimateFocal`;
+///   default σ 10 % of the mean.  When absent the focal length is
+///   estimated from the correspondences unless `model.estimateFocal` is
+///   `false`.
+/// * `camera_alt` – camera altitude (m); default σ 10 m.
+/// * `position`   – rough GNSS fix; default σ 10 m.
+/// * `yaw_deg`    – compass heading (°, 0 = North); default σ 20°.
+/// * `pitch_deg`, `roll_deg` – IMU attitude (°); default σ 5°.
+#[derive(Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Priors {
     #[serde(default)]
@@ -57,6 +200,14 @@ pub struct Priors {
     /// lenses.  Any omitted field defaults to 0.
     #[serde(default)]
     pub distortion: Option<DistortionPrior>,
+    #[serde(default)]
+    pub position: Option<PositionPrior>,
+    #[serde(default)]
+    pub yaw_deg: Option<GaussianPrior>,
+    #[serde(default)]
+    pub pitch_deg: Option<GaussianPrior>,
+    #[serde(default)]
+    pub roll_deg: Option<GaussianPrior>,
 }
 
 /// Initial-guess (and weak Gaussian prior) for Brown-Conrady distortion
@@ -67,7 +218,7 @@ pub struct Priors {
 ///   wide-angle smartphone lenses).
 /// * `p1`, `p2` – tangential (decentring) distortion.  Usually ≈ 0 for
 ///   modern lenses.
-#[derive(Deserialize, Default, Clone)]
+#[derive(Deserialize, JsonSchema, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DistortionPrior {
     #[serde(default)]
@@ -80,12 +231,60 @@ pub struct DistortionPrior {
     pub p2: Option<f64>,
 }
 
-#[derive(Deserialize)]
+/// Final-refinement settings (matches the frontend's `RefineCfg`).
+///
+/// * `max_iters`   – LM iterations per reweighting round (default 50).
+/// * `robust_loss` – kernel applied to each point's σ-normalised residual.
+/// * `loss_scale`  – kernel threshold in σ units (`huberDelta` before
+///   schema version 1).  Defaults to the usual 95 % efficiency constants
+///   (Huber 1.345, Cauchy 2.385, Tukey 4.685).
+#[derive(Deserialize, JsonSchema, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RefineCfg {
+    #[serde(default)]
+    pub max_iters: Option<usize>,
+    #[serde(default)]
+    pub robust_loss: RobustLoss,
+    #[serde(default)]
+    pub loss_scale: Option<f64>,
+}
+
+/// Robust kernel used by the iteratively-reweighted refinement.
+#[derive(Deserialize, JsonSchema, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RobustLoss {
+    /// Plain least squares.
+    #[default]
+    None,
+    /// Quadratic near zero, linear beyond the threshold.
+    Huber,
+    /// Logarithmic growth – large residuals have little influence.
+    Cauchy,
+    /// Redescending – residuals beyond the threshold get zero weight.
+    Tukey,
+}
+
+/// A one-dimensional Gaussian prior.  `sigma` is in the units of `mean`;
+/// when absent the solver uses the default for that parameter.
+#[derive(Deserialize, JsonSchema)]
 pub struct GaussianPrior {
     pub mean: f64,
+    #[serde(default)]
+    pub sigma: Option<f64>,
 }
 
-#[derive(Deserialize)]
+/// Horizontal camera position prior (e.g. a phone's GNSS fix) with an
+/// isotropic standard deviation in metres.
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionPrior {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default)]
+    pub sigma_m: Option<f64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Bounds {
     pub lat_min: f64,
@@ -94,7 +293,7 @@ pub struct Bounds {
     pub lon_max: f64,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Pose {
     pub lat: f64,
@@ -105,42 +304,370 @@ pub struct Pose {
     pub roll_deg: f64,
 }
 
-#[derive(Serialize)]
+/// Camera intrinsics as exchanged with the frontend.  Distortion
+/// coefficients may be omitted on input (pure pinhole) and default to 0.
+#[derive(Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Intrinsics {
     pub focal_px: f64,
     pub cx: f64,
     pub cy: f64,
     /// Radial distortion coefficient (first order).
+    #[serde(default)]
     pub k1: f64,
     /// Radial distortion coefficient (second order).
+    #[serde(default)]
     pub k2: f64,
     /// Tangential distortion coefficient 1.
+    #[serde(default)]
     pub p1: f64,
     /// Tangential distortion coefficient 2.
+    #[serde(default)]
     pub p2: f64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema, Clone)]
 pub struct Covariance {
     pub matrix: Vec<f64>,
     pub labels: Vec<String>,
 }
 
-#[derive(Serialize)]
+/// Readable summaries of `covariance`, so clients need not re-derive them.
+///
+/// Angle intervals are centred on the reported angle and may leave its
+/// usual range (e.g. a yaw interval of `[-3, 7]` around 2°).
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UncertaintySummary {
+    /// 95 % horizontal error ellipse of the camera position.
+    pub horizontal_ellipse: ErrorEllipse,
+    pub alt: ConfidenceInterval,
+    pub yaw_deg: ConfidenceInterval,
+    pub pitch_deg: ConfidenceInterval,
+    pub roll_deg: ConfidenceInterval,
+    /// Correlation coefficients, same order and labels as `covariance`.
+    /// Fixed parameters have zero rows and columns.
+    pub correlation: Covariance,
+}
+
+/// Error ellipse in a local horizontal plane.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorEllipse {
+    pub semi_major_m: f64,
+    pub semi_minor_m: f64,
+    /// Direction of the semi-major axis, clockwise from North, in `[0, 180)`.
+    pub azimuth_deg: f64,
+    /// Probability mass inside the ellipse (0.95).
+    pub confidence: f64,
+}
+
+/// 1-σ standard deviation and the two-sided 95 % interval of one output.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfidenceInterval {
+    pub sigma: f64,
+    pub lower95: f64,
+    pub upper95: f64,
+}
+
+#[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Diagnostics {
     pub rmse_px: f64,
     pub inlier_ratio: f64,
     pub residuals_px: Vec<f64>,
     pub inlier_ids: Vec<String>,
+    /// Final robust-loss weight per enabled point, aligned with
+    /// `residuals_px` (1 = full influence, 0 = ignored / RANSAC outlier).
+    pub weights: Vec<f64>,
+    /// Why the final optimiser run stopped, and after how many iterations.
+    /// No termination reason for the single-point fallback, which does not
+    /// optimise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub termination: Option<TerminationReason>,
+    pub iterations: usize,
     pub warnings: Vec<String>,
 }
 
-#[derive(Serialize)]
+/// Why the Levenberg-Marquardt optimiser stopped.
+#[derive(Serialize, JsonSchema, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TerminationReason {
+    /// The gradient vanished (relative to its starting value), or the fit
+    /// is exact.
+    ConvergedGradient,
+    /// An accepted update became negligible compared with the parameters.
+    ConvergedStep,
+    /// An accepted step no longer lowered the cost noticeably.
+    ConvergedCost,
+    /// The iteration budget ran out first.
+    MaxIterations,
+    /// No damping made the cost decrease (numerical trouble).
+    LambdaOverflow,
+}
+
+impl TerminationReason {
+    /// Whether one of the convergence tolerances was met.
+    pub fn converged(self) -> bool {
+        matches!(
+            self,
+            Self::ConvergedGradient | Self::ConvergedStep | Self::ConvergedCost
+        )
+    }
+}
+
+/// Parameter clouds from the bootstrap refits, one entry per sample.
+///
+/// * `position_samples`    – `[lat, lon, alt]`.
+/// * `orientation_samples` – `[yaw°, pitch°, roll°]`; yaw is unwrapped
+///   around the reported yaw, so it may leave `[0, 360)`.
+/// * `focal_samples`       – focal length (px), only when it is estimated.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Bootstrap {
+    pub position_samples: Vec<[f64; 3]>,
+    pub orientation_samples: Vec<[f64; 3]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focal_samples: Option<Vec<f64>>,
+}
+
+/// One distinct local minimum of the fit.  `cost` is the final LM cost
+/// (sum of squared σ-scaled residuals, prior terms included) and `rmse_px`
+/// the reprojection RMSE over the inliers.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PoseHypothesis {
+    pub pose: Pose,
+    pub intrinsics: Intrinsics,
+    pub cost: f64,
+    pub rmse_px: f64,
+    pub covariance: Covariance,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct SolveResponse {
+    /// Always the current `SCHEMA_VERSION`.
+    pub schema_version: u32,
     pub pose: Pose,
     pub intrinsics: Intrinsics,
     pub covariance: Covariance,
+    pub uncertainty: UncertaintySummary,
+    /// Present when `uncertainty.bootstrap.enabled`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<Bootstrap>,
+    /// Distinct poses that fit the data: `pose` itself first, then the
+    /// alternatives by increasing cost.  More than one when the geometry
+    /// is ambiguous (e.g. three points, or points close to a line).
+    pub hypotheses: Vec<PoseHypothesis>,
     pub diagnostics: Diagnostics,
 }
+
+// ── Reprojection ────────────────────────────────────────────────────────────
+
+/// Input of `reproject_points`: a (solved) camera and the world points whose
+/// predicted pixel positions should be drawn on the image.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprojectRequest {
+    pub pose: Pose,
+    pub intrinsics: Intrinsics,
+    pub image: Image,
+    #[serde(default)]
+    pub points: Vec<WorldPoint>,
+}
+
+/// A world point identified by the same id the frontend uses for its markers.
+#[derive(Deserialize)]
+pub struct WorldPoint {
+    pub id: String,
+    pub world: WorldLla,
+}
+
+/// Predicted pixel position of one world point.
+///
+/// `u`/`v` are `None` (JSON `null`) when the point lies behind the camera,
+/// because no meaningful pixel exists in that case.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectedPixel {
+    pub id: String,
+    pub u: Option<f64>,
+    pub v: Option<f64>,
+    /// The point is behind the image plane (camera-frame Z ≤ 0).
+    pub behind_camera: bool,
+    /// The point projects outside `[0, width) × [0, height)`.
+    pub outside_image: bool,
+}
+
+#[derive(Serialize)]
+pub struct ReprojectResponse {
+    pub pixels: Vec<ProjectedPixel>,
+    pub warnings: Vec<String>,
+}
+
+// ── Back-projection ─────────────────────────────────────────────────────────
+
+/// Input of `back_project`: a (solved) camera, a pixel in its image and the
+/// ellipsoidal altitude of the horizontal surface the viewing ray should hit
+/// (e.g. street level).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackProjectRequest {
+    pub pose: Pose,
+    pub intrinsics: Intrinsics,
+    pub pixel: Pixel,
+    /// Altitude (m above the WGS-84 ellipsoid) of the target surface.
+    pub plane_alt: f64,
+}
+
+/// World location seen at the requested pixel.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackProjectResponse {
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: f64,
+    /// Straight-line distance from the camera to the intersection (m).
+    pub distance_m: f64,
+    /// Bearing from the camera to the intersection (0 = N, 90 = E).
+    pub bearing_deg: f64,
+    pub warnings: Vec<String>,
+}
+
+// ── Bundle adjustment ───────────────────────────────────────────────────────
+
+/// Input of `bundle_adjust`: several images of one scene that share point
+/// ids.  Points listed in `control_points` have known coordinates; every
+/// other id seen in at least two images is a tie point whose position is
+/// estimated together with the camera poses.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleRequest {
+    #[serde(default)]
+    pub schema_version: u32,
+    pub images: Vec<BundleImage>,
+    /// Known world points.  With `world.sigmaM` they are refined under a
+    /// Gaussian prior of that σ, otherwise they stay fixed.
+    #[serde(default)]
+    pub control_points: Vec<WorldPoint>,
+    /// Bundle LM iterations (default 100).
+    #[serde(default)]
+    pub max_iters: Option<usize>,
+}
+
+/// One image of a `BundleRequest`.  The camera is a distortion-free pinhole
+/// with the principal point at the image centre; without `focalPx` its
+/// focal length is estimated.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImage {
+    pub id: String,
+    pub image: Image,
+    #[serde(default)]
+    pub focal_px: Option<f64>,
+    pub observations: Vec<Observation>,
+}
+
+/// Pixel at which point `id` (a control or tie point) is seen.
+#[derive(Deserialize)]
+pub struct Observation {
+    pub id: String,
+    pub pixel: Pixel,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleResponse {
+    /// Always the current `SCHEMA_VERSION`.
+    pub schema_version: u32,
+    /// One entry per request image, in request order.
+    pub cameras: Vec<BundleCamera>,
+    /// Every adjusted point: control points first, then tie points, each in
+    /// order of first appearance.
+    pub points: Vec<BundlePoint>,
+    pub diagnostics: BundleDiagnostics,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleCamera {
+    pub id: String,
+    pub pose: Pose,
+    pub intrinsics: Intrinsics,
+    pub rmse_px: f64,
+    /// Observations used in the adjustment.
+    pub observations: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundlePoint {
+    pub id: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: f64,
+    /// A control point (known coordinates) rather than a tie point.
+    pub control: bool,
+    /// Images the point was used in.
+    pub observations: usize,
+    pub rmse_px: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleDiagnostics {
+    /// Over all observations used.
+    pub rmse_px: f64,
+    pub iterations: usize,
+    pub termination: TerminationReason,
+    pub warnings: Vec<String>,
+}
+
+// ── Triangulation ───────────────────────────────────────────────────────────
+
+/// Input of `triangulate`: one feature clicked in two or more solved images.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriangulateRequest {
+    #[serde(default)]
+    pub schema_version: u32,
+    pub views: Vec<TriangulateView>,
+}
+
+/// A solved camera and the pixel at which it sees the feature.
+#[derive(Deserialize)]
+pub struct TriangulateView {
+    pub pose: Pose,
+    pub intrinsics: Intrinsics,
+    pub pixel: Pixel,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriangulateResponse {
+    /// Always the current `SCHEMA_VERSION`.
+    pub schema_version: u32,
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: f64,
+    /// Position covariance (m²) over `east`, `north`, `up` at the point.
+    pub covariance: Covariance,
+    /// 95 % horizontal error ellipse.
+    pub horizontal_ellipse: ErrorEllipse,
+    /// Widest angle (deg) between two viewing rays; the smaller it is, the
+    /// less certain the distance along the rays.
+    pub triangulation_angle_deg: f64,
+    /// Over the enabled views.
+    pub rmse_px: f64,
+    /// Reprojection error (px) per view in request order, `None` when
+    /// disabled.
+    pub residuals_px: Vec<Option<f64>>,
+    pub termination: TerminationReason,
+    pub warnings: Vec<String>,
+}
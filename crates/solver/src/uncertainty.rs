@@ -0,0 +1,108 @@
+use crate::geo::metres_per_degree;
+use crate::types::{ConfidenceInterval, Covariance, ErrorEllipse, Pose, UncertaintySummary};
+
+/// 95 % quantile of the χ² distribution with 2 degrees of freedom: the
+/// squared Mahalanobis radius of the 95 % horizontal ellipse.
+pub(crate) const CHI2_2DOF_95: f64 = 5.991_464_547_107_979;
+
+/// Two-sided 95 % quantile of the standard normal distribution.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+// ── Summaries ───────────────────────────────────────────────────────────────
+
+/// Error ellipse, intervals and correlation matrix derived from `cov`
+/// (labels as produced by the estimator) around the solved `pose`.
+pub(crate) fn summarize(cov: &Covariance, pose: &Pose) -> UncertaintySummary {
+    let n = cov.labels.len();
+    let index = |label: &str| cov.labels.iter().position(|l| l == label);
+    let entry = |a: Option<usize>, b: Option<usize>| match (a, b) {
+        (Some(i), Some(j)) => cov.matrix[i * n + j],
+        _ => 0.0,
+    };
+    let (lat, lon) = (index("lat"), index("lon"));
+
+    // lat / lon (degrees) → north / east (metres)
+    let (m_lat, m_lon) = metres_per_degree(pose.lat, pose.alt);
+    let horizontal = [
+        [
+            entry(lon, lon) * m_lon * m_lon,
+            entry(lat, lon) * m_lat * m_lon,
+        ],
+        [
+            entry(lat, lon) * m_lat * m_lon,
+            entry(lat, lat) * m_lat * m_lat,
+        ],
+    ];
+
+    let interval = |label: &str, value: f64| {
+        let i = index(label);
+        confidence_interval(value, entry(i, i))
+    };
+
+    UncertaintySummary {
+        horizontal_ellipse: error_ellipse(&horizontal, CHI2_2DOF_95, 0.95),
+        alt: interval("alt", pose.alt),
+        yaw_deg: interval("yawDeg", pose.yaw_deg),
+        pitch_deg: interval("pitchDeg", pose.pitch_deg),
+        roll_deg: interval("rollDeg", pose.roll_deg),
+        correlation: correlation(cov),
+    }
+}
+
+/// Ellipse `xᵀ Σ⁻¹ x = scale` of the 2×2 covariance `Σ` over `[east,
+/// north]`: semi-axes `√(scale·λ)` along the eigenvectors.
+pub(crate) fn error_ellipse(cov: &[[f64; 2]; 2], scale: f64, confidence: f64) -> ErrorEllipse {
+    let (a, b, d) = (cov[0][0], cov[0][1], cov[1][1]);
+    let mean = 0.5 * (a + d);
+    let radius = (0.25 * (a - d) * (a - d) + b * b).sqrt();
+    let (major, minor) = ((mean + radius).max(0.0), (mean - radius).max(0.0));
+    // Major-axis direction (east, north) = (b, λ₁ − a), or an axis when b = 0.
+    let (east, north) = if b.abs() > 1e-300 {
+        (b, major - a)
+    } else if a >= d {
+        (1.0, 0.0)
+    } else {
+        (0.0, 1.0)
+    };
+    ErrorEllipse {
+        semi_major_m: (scale * major).sqrt(),
+        semi_minor_m: (scale * minor).sqrt(),
+        azimuth_deg: east.atan2(north).to_degrees().rem_euclid(180.0),
+        confidence,
+    }
+}
+
+/// `value ± 1.96 σ` for the variance `var` (negative round-off → 0).
+fn confidence_interval(value: f64, var: f64) -> ConfidenceInterval {
+    let sigma = var.max(0.0).sqrt();
+    ConfidenceInterval {
+        sigma,
+        lower95: value - Z_95 * sigma,
+        upper95: value + Z_95 * sigma,
+    }
+}
+
+/// `ρᵢⱼ = Σᵢⱼ / √(Σᵢᵢ Σⱼⱼ)`; rows and columns with zero variance (fixed
+/// parameters) are left at zero.
+fn correlation(cov: &Covariance) -> Covariance {
+    let n = cov.labels.len();
+    let sigma: Vec<f64> = (0..n)
+        .map(|i| cov.matrix[i * n + i].max(0.0).sqrt())
+        .collect();
+    let mut matrix = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..n {
+            if sigma[i] > 0.0 && sigma[j] > 0.0 {
+                matrix[i * n + j] = if i == j {
+                    1.0
+                } else {
+                    (cov.matrix[i * n + j] / (sigma[i] * sigma[j])).clamp(-1.0, 1.0)
+                };
+            }
+        }
+    }
+    Covariance {
+        matrix,
+        labels: cov.labels.clone(),
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+
+use crate::error::SolveError;
+use crate::geo::metres_per_degree;
+use crate::schema::SCHEMA_VERSION;
+use crate::types::{
+    BundleRequest, Corr, GaussianPrior, Pixel, SolveRequest, TriangulateRequest, WorldLla,
+};
+
+/// Two clicks closer than this (px) mark the same pixel.
+const DUPLICATE_PIXEL_PX: f64 = 1.0;
+
+/// Two world points closer than this (m) are the same landmark.
+const DUPLICATE_WORLD_M: f64 = 0.5;
+
+/// Upper bounds on the iteration and sample counts a request may ask for,
+/// far above anything useful but low enough that a solve still finishes.
+const MAX_RANSAC_ITERS: usize = 100_000;
+const MAX_BOOTSTRAP_SAMPLES: usize = 10_000;
+
+// ── Request validation ──────────────────────────────────────────────────────
+
+/// Check `req` before anything is solved.
+///
+/// Rejects non-finite and out-of-range numbers and duplicate ids, naming
+/// the offending field (`correspondences[3].world.lat`).  Disabled
+/// correspondences only need a unique id.  Returns warnings for input that
+/// is suspicious but solvable: pixels outside the image and duplicate or
+/// conflicting correspondences.
+pub(crate) fn validate(req: &SolveRequest) -> Result<Vec<String>, SolveError> {
+    check("image.width", req.image.width, |v| v > 0.0, "positive")?;
+    check("image.height", req.image.height, |v| v > 0.0, "positive")?;
+
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    for (i, c) in req.correspondences.iter().enumerate() {
+        if let Some(first) = ids.insert(c.id.as_str(), i) {
+            return Err(SolveError::InvalidInput {
+                field: format!("correspondences[{i}].id"),
+                message: format!(
+                    "Duplicate correspondence id \"{}\" (also correspondences[{first}])",
+                    c.id
+                ),
+            });
+        }
+    }
+
+    let enabled: Vec<(usize, &Corr)> = req
+        .correspondences
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.enabled.unwrap_or(true))
+        .collect();
+    for &(i, c) in &enabled {
+        check_corr(i, c)?;
+    }
+    check_priors(req)?;
+    check_settings(req)?;
+
+    let mut warnings = Vec::new();
+    for &(i, c) in &enabled {
+        let (u, v) = (c.pixel.u, c.pixel.v);
+        if u < 0.0 || v < 0.0 || u > req.image.width || v > req.image.height {
+            warnings.push(format!(
+                "correspondences[{i}].pixel ({u:.1}, {v:.1}) lies outside the {} × {} image.",
+                req.image.width, req.image.height
+            ));
+        }
+    }
+    for (k, &(i, a)) in enabled.iter().enumerate() {
+        for &(j, b) in &enabled[k + 1..] {
+            let same_pixel =
+                (a.pixel.u - b.pixel.u).hypot(a.pixel.v - b.pixel.v) <= DUPLICATE_PIXEL_PX;
+            let same_world = world_distance_m(a, b) <= DUPLICATE_WORLD_M;
+            let problem = match (same_pixel, same_world) {
+                (true, true) => "are duplicates (same pixel and world point)",
+                (true, false) => "share a pixel but mark different world points",
+                (false, true) => "mark the same world point at different pixels",
+                (false, false) => continue,
+            };
+            warnings.push(format!(
+                "correspondences[{i}] and correspondences[{j}] {problem}."
+            ));
+        }
+    }
+    Ok(warnings)
+}
+
+/// Pixel, world point and their σ of correspondence `i`.
+fn check_corr(i: usize, c: &Corr) -> Result<(), SolveError> {
+    let path = format!("correspondences[{i}]");
+    check_pixel(&path, &c.pixel)?;
+    check_world(&path, &c.world)
+}
+
+/// `pixel` of the correspondence or observation at `path`.
+fn check_pixel(path: &str, pixel: &Pixel) -> Result<(), SolveError> {
+    check(&format!("{path}.pixel.u"), pixel.u, |_| true, "")?;
+    check(&format!("{path}.pixel.v"), pixel.v, |_| true, "")?;
+    if let Some(s) = pixel.sigma_px {
+        // 0 is accepted and clamped to a tiny σ by the solver.
+        check(
+            &format!("{path}.pixel.sigmaPx"),
+            s,
+            |v| v >= 0.0,
+            "non-negative",
+        )?;
+    }
+    Ok(())
+}
+
+/// `world` of the correspondence or control point at `path`.
+fn check_world(path: &str, world: &WorldLla) -> Result<(), SolveError> {
+    check(
+        &format!("{path}.world.lat"),
+        world.lat,
+        is_latitude,
+        "within [-90, 90]",
+    )?;
+    check(
+        &format!("{path}.world.lon"),
+        world.lon,
+        is_longitude,
+        "within [-180, 180]",
+    )?;
+    if let Some(alt) = world.alt {
+        check(&format!("{path}.world.alt"), alt, |_| true, "")?;
+    }
+    if let Some(s) = world.sigma_m {
+        check(
+            &format!("{path}.world.sigmaM"),
+            s,
+            |v| v >= 0.0,
+            "non-negative",
+        )?;
+    }
+    Ok(())
+}
+
+fn check_priors(req: &SolveRequest) -> Result<(), SolveError> {
+    let Some(p) = req.priors.as_ref() else {
+        return Ok(());
+    };
+    let gaussian =
+        |name: &str, g: Option<&GaussianPrior>, in_range: fn(f64) -> bool, requirement: &str| {
+            let Some(g) = g else {
+                return Ok(());
+            };
+            check(
+                &format!("priors.{name}.mean"),
+                g.mean,
+                in_range,
+                requirement,
+            )?;
+            match g.sigma {
+                Some(s) => check(&format!("priors.{name}.sigma"), s, |v| v > 0.0, "positive"),
+                None => Ok(()),
+            }
+        };
+    gaussian("focalPx", p.focal_px.as_ref(), |v| v > 0.0, "positive")?;
+    gaussian("cameraAlt", p.camera_alt.as_ref(), |_| true, "")?;
+    gaussian("yawDeg", p.yaw_deg.as_ref(), |_| true, "")?;
+    gaussian(
+        "pitchDeg",
+        p.pitch_deg.as_ref(),
+        is_latitude,
+        "within [-90, 90]",
+    )?;
+    gaussian("rollDeg", p.roll_deg.as_ref(), |_| true, "")?;
+
+    if let Some(pos) = &p.position {
+        check(
+            "priors.position.lat",
+            pos.lat,
+            is_latitude,
+            "within [-90, 90]",
+        )?;
+        check(
+            "priors.position.lon",
+            pos.lon,
+            is_longitude,
+            "within [-180, 180]",
+        )?;
+        if let Some(s) = pos.sigma_m {
+            check("priors.position.sigmaM", s, |v| v > 0.0, "positive")?;
+        }
+    }
+    if let Some(b) = &p.bounds {
+        check(
+            "priors.bounds.latMin",
+            b.lat_min,
+            is_latitude,
+            "within [-90, 90]",
+        )?;
+        check(
+            "priors.bounds.latMax",
+            b.lat_max,
+            |v| is_latitude(v) && v >= b.lat_min,
+            "within [latMin, 90]",
+        )?;
+        check(
+            "priors.bounds.lonMin",
+            b.lon_min,
+            is_longitude,
+            "within [-180, 180]",
+        )?;
+        check(
+            "priors.bounds.lonMax",
+            b.lon_max,
+            |v| is_longitude(v) && v >= b.lon_min,
+            "within [lonMin, 180]",
+        )?;
+    }
+    if let Some(d) = &p.distortion {
+        for (name, value) in [("k1", d.k1), ("k2", d.k2), ("p1", d.p1), ("p2", d.p2)] {
+            if let Some(v) = value {
+                check(&format!("priors.distortion.{name}"), v, |_| true, "")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// RANSAC and refinement settings.
+fn check_settings(req: &SolveRequest) -> Result<(), SolveError> {
+    if let Some(r) = &req.ransac {
+        check_count("ransac.maxIters", r.max_iters, 1, MAX_RANSAC_ITERS)?;
+        check("ransac.inlierPx", r.inlier_px, |v| v > 0.0, "positive")?;
+        check(
+            "ransac.targetProb",
+            r.target_prob,
+            |v| v > 0.0 && v <= 1.0,
+            "within (0, 1]",
+        )?;
+    }
+    if let Some(s) = req.refine.as_ref().and_then(|r| r.loss_scale) {
+        check("refine.lossScale", s, |v| v > 0.0, "positive")?;
+    }
+    if let Some(b) = req.uncertainty.as_ref().and_then(|u| u.bootstrap.as_ref()) {
+        // 0 asks for the default sample count.
+        check_count(
+            "uncertainty.bootstrap.samples",
+            b.samples,
+            0,
+            MAX_BOOTSTRAP_SAMPLES,
+        )?;
+    }
+    Ok(())
+}
+
+// ── Bundle request validation ───────────────────────────────────────────────
+
+/// Check a `BundleRequest` the way `validate` checks a `SolveRequest`:
+/// unique image, observation and control point ids, positive image sizes
+/// and focal lengths, and finite, in-range numbers.
+pub(crate) fn validate_bundle(req: &BundleRequest) -> Result<(), SolveError> {
+    if u64::from(req.schema_version) > u64::from(SCHEMA_VERSION) {
+        return Err(SolveError::UnsupportedSchemaVersion {
+            found: req.schema_version.into(),
+            supported: SCHEMA_VERSION,
+        });
+    }
+    if req.images.is_empty() {
+        return Err(SolveError::InvalidInput {
+            field: "images".to_string(),
+            message: "Bundle adjustment needs at least one image".to_string(),
+        });
+    }
+    unique_ids("images", req.images.iter().map(|im| im.id.as_str()))?;
+    for (i, im) in req.images.iter().enumerate() {
+        let path = format!("images[{i}]");
+        check(
+            &format!("{path}.image.width"),
+            im.image.width,
+            |v| v > 0.0,
+            "positive",
+        )?;
+        check(
+            &format!("{path}.image.height"),
+            im.image.height,
+            |v| v > 0.0,
+            "positive",
+        )?;
+        if let Some(f) = im.focal_px {
+            check(&format!("{path}.focalPx"), f, |v| v > 0.0, "positive")?;
+        }
+        let observations = format!("{path}.observations");
+        unique_ids(&observations, im.observations.iter().map(|o| o.id.as_str()))?;
+        for (j, o) in im.observations.iter().enumerate() {
+            if o.enabled.unwrap_or(true) {
+                check_pixel(&format!("{observations}[{j}]"), &o.pixel)?;
+            }
+        }
+    }
+    unique_ids(
+        "controlPoints",
+        req.control_points.iter().map(|p| p.id.as_str()),
+    )?;
+    for (i, p) in req.control_points.iter().enumerate() {
+        check_world(&format!("controlPoints[{i}]"), &p.world)?;
+    }
+    Ok(())
+}
+
+// ── Triangulation request validation ───────────────────────────────────────
+
+/// Check a `TriangulateRequest`: at least two enabled views, each with a
+/// valid pose, a positive focal length and a finite pixel.
+pub(crate) fn validate_triangulate(req: &TriangulateRequest) -> Result<(), SolveError> {
+    if u64::from(req.schema_version) > u64::from(SCHEMA_VERSION) {
+        return Err(SolveError::UnsupportedSchemaVersion {
+            found: req.schema_version.into(),
+            supported: SCHEMA_VERSION,
+        });
+    }
+    let enabled = req.views.iter().filter(|v| v.enabled.unwrap_or(true));
+    if enabled.count() < 2 {
+        return Err(SolveError::InvalidInput {
+            field: "views".to_string(),
+            message: "Triangulation needs at least two enabled views".to_string(),
+        });
+    }
+    for (i, view) in req.views.iter().enumerate() {
+        if !view.enabled.unwrap_or(true) {
+            continue;
+        }
+        let path = format!("views[{i}]");
+        let pose = &view.pose;
+        check(
+            &format!("{path}.pose.lat"),
+            pose.lat,
+            is_latitude,
+            "within [-90, 90]",
+        )?;
+        check(
+            &format!("{path}.pose.lon"),
+            pose.lon,
+            is_longitude,
+            "within [-180, 180]",
+        )?;
+        for (name, value) in [
+            ("alt", pose.alt),
+            ("yawDeg", pose.yaw_deg),
+            ("pitchDeg", pose.pitch_deg),
+            ("rollDeg", pose.roll_deg),
+        ] {
+            check(&format!("{path}.pose.{name}"), value, |_| true, "")?;
+        }
+        let intr = &view.intrinsics;
+        check(
+            &format!("{path}.intrinsics.focalPx"),
+            intr.focal_px,
+            |v| v > 0.0,
+            "positive",
+        )?;
+        for (name, value) in [
+            ("cx", intr.cx),
+            ("cy", intr.cy),
+            ("k1", intr.k1),
+            ("k2", intr.k2),
+            ("p1", intr.p1),
+            ("p2", intr.p2),
+        ] {
+            check(&format!("{path}.intrinsics.{name}"), value, |_| true, "")?;
+        }
+        check_pixel(&path, &view.pixel)?;
+    }
+    Ok(())
+}
+
+/// The ids of the list at `path` must be distinct.
+fn unique_ids<'a>(path: &str, ids: impl Iterator<Item = &'a str>) -> Result<(), SolveError> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (i, id) in ids.enumerate() {
+        if let Some(first) = seen.insert(id, i) {
+            return Err(SolveError::InvalidInput {
+                field: format!("{path}[{i}].id"),
+                message: format!("Duplicate id \"{id}\" (also {path}[{first}])"),
+            });
+        }
+    }
+    Ok(())
+}
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+/// `value` must be finite and satisfy `in_range`; `requirement` completes
+/// the message "`field` must be …".
+fn check(
+    field: &str,
+    value: f64,
+    in_range: impl Fn(f64) -> bool,
+    requirement: &str,
+) -> Result<(), SolveError> {
+    if !value.is_finite() {
+        return Err(SolveError::NonFiniteInput {
+            field: field.to_string(),
+        });
+    }
+    if !in_range(value) {
+        return Err(SolveError::InvalidInput {
+            field: field.to_string(),
+            message: format!("{field} must be {requirement} (got {value})"),
+        });
+    }
+    Ok(())
+}
+
+/// Like `check`, for a count that must lie within `[min, max]`.
+fn check_count(field: &str, value: usize, min: usize, max: usize) -> Result<(), SolveError> {
+    if !(min..=max).contains(&value) {
+        return Err(SolveError::InvalidInput {
+            field: field.to_string(),
+            message: format!("{field} must be within [{min}, {max}] (got {value})"),
+        });
+    }
+    Ok(())
+}
+
+fn is_latitude(v: f64) -> bool {
+    (-90.0..=90.0).contains(&v)
+}
+
+fn is_longitude(v: f64) -> bool {
+    (-180.0..=180.0).contains(&v)
+}
+
+/// Straight-line distance between the world points of `a` and `b` (m),
+/// missing altitudes taken as 0.  Only meant for nearby points.
+fn world_distance_m(a: &Corr, b: &Corr) -> f64 {
+    let alt_a = a.world.alt.unwrap_or(0.0);
+    let alt_b = b.world.alt.unwrap_or(0.0);
+    let (m_lat, m_lon) = metres_per_degree(a.world.lat, alt_a);
+    let north = (b.world.lat - a.world.lat) * m_lat;
+    let east = (b.world.lon - a.world.lon) * m_lon;
+    (north * north + east * east + (alt_b - alt_a).powi(2)).sqrt()
+}
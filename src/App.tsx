@@ -1,5 +1,6 @@
 import React, { useEffect, useRef, useState } from "react";
 import * as Comlink from "comlink";
+import { SCHEMA_VERSION } from "./types/solver";
 import type { SolveRequest, SolveResponse } from "./types/solver";
 import ImageCanvas from "./components/ImageCanvas";
 import WorldMap from "./components/WorldMap";
@@ -81,6 +82,7 @@ export default function App() {
         enabled: true,
       }));
       const req: SolveRequest = {
+        schemaVersion: SCHEMA_VERSION,
         image: { width: image.width, height: image.height },
         correspondences,
         model: {
@@ -89,7 +91,7 @@ export default function App() {
           estimateDistortion: false,
         },
         ransac: { maxIters: 5000, inlierPx: 2.0, targetProb: 0.999 },
-        refine: { maxIters: 50, robustLoss: "huber", huberDelta: 1.0 },
+        refine: { maxIters: 50, robustLoss: "huber", lossScale: 1.0 },
         uncertainty: { bootstrap: { enabled: false, samples: 0 } },
       };
       const res = await workerApiRef.current.solve(req);
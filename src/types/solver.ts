@@ -3,22 +3,38 @@ export type WorldLLA = { lat: number; lon: number; alt?: number; sigmaM?: number
 export type Corr = { id: string; pixel: Pixel; world: WorldLLA; enabled?: boolean };
 
 export type SolverModel = {
-  estimateFocal: boolean;
+  estimateFocal?: boolean; // default: only when priors.focalPx is absent
   estimatePrincipalPoint: boolean;
   estimateDistortion: boolean; // k1,k2,(p1,p2)
+  uncalibrated?: boolean; // DLT: focal + principal point + pose, ≥6 non-coplanar points
 };
 
+export type GaussianPrior = { mean: number; sigma?: number };
+
 export type Priors = {
-  focalPx?: { mean: number; sigma: number };
-  cameraAlt?: { mean: number; sigma: number };
+  focalPx?: GaussianPrior;
+  cameraAlt?: GaussianPrior;
+  position?: { lat: number; lon: number; sigmaM?: number }; // e.g. GNSS fix
+  yawDeg?: GaussianPrior;   // compass heading, 0 = North
+  pitchDeg?: GaussianPrior; // IMU
+  rollDeg?: GaussianPrior;  // IMU
   bounds?: { latMin: number; latMax: number; lonMin: number; lonMax: number };
+  distortion?: { k1?: number; k2?: number; p1?: number; p2?: number }; // initial guess + weak prior
 };
 
-export type RansacCfg = { maxIters: number; inlierPx: number; targetProb: number };
-export type RefineCfg = { maxIters: number; robustLoss: 'none'|'huber'; huberDelta?: number };
-export type UncertaintyCfg = { bootstrap: { enabled: boolean; samples: number; seed?: number } };
+export type RansacCfg = { maxIters: number; inlierPx: number; targetProb: number; seed?: number };
+export type RobustLoss = 'none'|'huber'|'cauchy'|'tukey';
+// lossScale is the kernel threshold in σ units (huberDelta in unversioned requests).
+export type RefineCfg = { maxIters: number; robustLoss: RobustLoss; lossScale?: number };
+// method: 'cases' resamples correspondences, 'residuals' resamples fit residuals (default 'cases').
+export type BootstrapMethod = 'cases'|'residuals';
+export type UncertaintyCfg = { bootstrap: { enabled: boolean; samples: number; seed?: number; method?: BootstrapMethod } };
+
+// Bumped on incompatible changes; older requests are migrated by the solver.
+export const SCHEMA_VERSION = 1;
 
 export type SolveRequest = {
+  schemaVersion: number; // SCHEMA_VERSION; absent = 0 (pre-versioning)
   image: { width: number; height: number };
   correspondences: Corr[];
   model: SolverModel;
@@ -43,11 +59,30 @@ export type Covariance = {
   matrix: number[]; labels: string[];
 };
 
+export type ErrorEllipse = {
+  semiMajorM: number; semiMinorM: number;
+  azimuthDeg: number; // semi-major axis, clockwise from North, [0, 180)
+  confidence: number; // 0.95
+};
+export type ConfidenceInterval = { sigma: number; lower95: number; upper95: number };
+export type UncertaintySummary = {
+  horizontalEllipse: ErrorEllipse;
+  alt: ConfidenceInterval;
+  yawDeg: ConfidenceInterval; pitchDeg: ConfidenceInterval; rollDeg: ConfidenceInterval;
+  correlation: Covariance; // same labels as covariance
+};
+
+export type TerminationReason =
+  'convergedGradient'|'convergedStep'|'convergedCost'|'maxIterations'|'lambdaOverflow';
+
 export type Diagnostics = {
   rmsePx: number;
   inlierRatio: number;
   residualsPx: number[];
   inlierIds: string[];
+  weights: number[]; // final robust-loss weight per residual (0 = ignored)
+  termination?: TerminationReason; // absent for the single-point fallback
+  iterations: number; // of the final LM run
   warnings: string[];
 };
 
@@ -57,10 +92,130 @@ export type Bootstrap = {
   focalSamples?: number[];
 };
 
+export type PoseHypothesis = {
+  pose: Pose;
+  intrinsics: Intrinsics;
+  cost: number;
+  rmsePx: number;
+  covariance: Covariance;
+};
+
 export type SolveResponse = {
+  schemaVersion: number;
   pose: Pose;
   intrinsics: Intrinsics;
   covariance: Covariance;
+  uncertainty: UncertaintySummary;
   bootstrap?: Bootstrap;
+  hypotheses: PoseHypothesis[]; // hypotheses[0] is pose; alternatives by cost
   diagnostics: Diagnostics;
+  error?: SolveError; // set by the worker when the solve failed
+};
+
+export type SolveErrorCode =
+  | 'invalidJson' | 'unsupportedSchemaVersion' | 'noEnabledPoints' | 'degenerateGeometry' | 'nonFiniteInput'
+  | 'invalidInput' | 'singularCovariance' | 'noIntersection' | 'serialization'
+  | 'internal'; // unexpected failure: a solver panic, or the wasm module did not load
+
+// Thrown by the wasm functions as a JSON string.
+export type SolveError = {
+  code: SolveErrorCode;
+  message: string;
+  details:
+    | { line: number; column: number }
+    | { found: number; supported: number }
+    | { reason: string }
+    | { field: string }
+    | null;
+};
+
+export type ReprojectRequest = {
+  pose: Pose;
+  intrinsics: Intrinsics;
+  image: { width: number; height: number };
+  points: { id: string; world: WorldLLA }[];
+};
+
+export type ProjectedPixel = {
+  id: string;
+  u: number | null; v: number | null;
+  behindCamera: boolean;
+  outsideImage: boolean;
+};
+
+export type ReprojectResponse = {
+  pixels: ProjectedPixel[];
+  warnings: string[];
+};
+
+export type BackProjectRequest = {
+  pose: Pose;
+  intrinsics: Intrinsics;
+  pixel: Pixel;
+  planeAlt: number; // metres above the WGS-84 ellipsoid
+};
+
+export type BackProjectResponse = {
+  lat: number; lon: number; alt: number;
+  distanceM: number;
+  bearingDeg: number;
+  warnings: string[];
+};
+
+// Several images sharing point ids: ids in controlPoints are known, every other
+// id seen in ≥2 images is a tie point estimated along with the camera poses.
+export type Observation = { id: string; pixel: Pixel; enabled?: boolean };
+
+export type BundleImage = {
+  id: string;
+  image: { width: number; height: number };
+  focalPx?: number; // absent = estimated
+  observations: Observation[];
+};
+
+export type BundleRequest = {
+  schemaVersion: number;
+  images: BundleImage[];
+  controlPoints: { id: string; world: WorldLLA }[]; // world.sigmaM = refined under a prior
+  maxIters?: number;
+};
+
+export type BundleCamera = {
+  id: string;
+  pose: Pose;
+  intrinsics: Intrinsics;
+  rmsePx: number;
+  observations: number;
+};
+
+export type BundlePoint = {
+  id: string;
+  lat: number; lon: number; alt: number;
+  control: boolean;
+  observations: number;
+  rmsePx: number;
+};
+
+export type BundleResponse = {
+  schemaVersion: number;
+  cameras: BundleCamera[];
+  points: BundlePoint[];
+  diagnostics: { rmsePx: number; iterations: number; termination: TerminationReason; warnings: string[] };
+};
+
+// One feature clicked in two or more solved images.
+export type TriangulateView = { pose: Pose; intrinsics: Intrinsics; pixel: Pixel; enabled?: boolean };
+
+export type TriangulateRequest = { schemaVersion: number; views: TriangulateView[] };
+
+export type TriangulateResponse = {
+  schemaVersion: number;
+  lat: number; lon: number; alt: number;
+  covariance: Covariance; // m², labels east/north/up at the point
+  horizontalEllipse: ErrorEllipse;
+  triangulationAngleDeg: number; // small angle = depth poorly determined
+  rmsePx: number;
+  residualsPx: (number | null)[]; // per view, null when disabled
+  termination: TerminationReason;
+  warnings: string[];
 };
@@ -1,11 +1,12 @@
 import * as Comlink from 'comlink';
-import type { SolveRequest, SolveResponse } from '../types/solver';
+import { SCHEMA_VERSION } from '../types/solver';
+import type { BackProjectRequest, BackProjectResponse, BundleRequest, BundleResponse, SolveError, SolveRequest, SolveResponse, TriangulateRequest, TriangulateResponse } from '../types/solver';
 
 // Static import of the wasm-pack generated JS glue.
 // Vite handles `new URL('./solver_bg.wasm', import.meta.url)` inside solver.js
 // and emits the WASM as a hashed asset in both dev and production builds.
 // @ts-ignore: TS can't resolve the wasm-pack generated module; types come from solver.d.ts
-import init, { solve as wasmSolve, reproject_points as wasmReproject } from '../../crates/solver/pkg/solver.js';
+import init, { solve as wasmSolve, reproject_points as wasmReproject, back_project as wasmBackProject, bundle_adjust as wasmBundleAdjust, triangulate as wasmTriangulate } from '../../crates/solver/pkg/solver.js';
 
 // Initialise once; re-use the same promise so concurrent callers don't double-init.
 const initPromise = init().catch((err: unknown) => {
@@ -13,6 +14,20 @@ const initPromise = init().catch((err: unknown) => {
   throw err;
 });
 
+// The wasm functions throw a `SolveError` as JSON; anything else (e.g. a
+// failed init) becomes a generic error with its message.
+function toSolveError(err: any): SolveError {
+  if (typeof err === 'string') {
+    try {
+      const parsed = JSON.parse(err);
+      if (parsed && typeof parsed.code === 'string') return parsed as SolveError;
+    } catch {
+      // not JSON: fall through
+    }
+  }
+  return { code: 'internal', message: err?.message ?? String(err), details: null };
+}
+
 const api = {
   async solve(req: SolveRequest): Promise<SolveResponse> {
     try {
@@ -21,17 +36,29 @@ const api = {
       return JSON.parse(out) as SolveResponse;
     } catch (err: any) {
       console.error('[solverWorker] solve error:', err);
+      const error = toSolveError(err);
+      const none = { sigma: 0, lower95: 0, upper95: 0 };
       return {
+        schemaVersion: SCHEMA_VERSION,
         pose: { lat: 0, lon: 0, alt: 0, yawDeg: 0, pitchDeg: 0, rollDeg: 0 },
         intrinsics: { focalPx: 1000, cx: req.image.width / 2, cy: req.image.height / 2 },
         covariance: { matrix: [], labels: [] },
+        uncertainty: {
+          horizontalEllipse: { semiMajorM: 0, semiMinorM: 0, azimuthDeg: 0, confidence: 0.95 },
+          alt: none, yawDeg: none, pitchDeg: none, rollDeg: none,
+          correlation: { matrix: [], labels: [] },
+        },
+        hypotheses: [],
         diagnostics: {
           rmsePx: 0,
           inlierRatio: 0,
           residualsPx: [],
           inlierIds: [],
-          warnings: ['Solver error: ' + (err?.message ?? String(err))],
+          weights: [],
+          iterations: 0,
+          warnings: ['Solver error: ' + error.message],
         },
+        error,
       };
     }
   },
@@ -42,7 +69,38 @@ const api = {
       const out = wasmReproject(JSON.stringify(req));
       return JSON.parse(out);
     } catch (err: any) {
-      return { pixels: [], warnings: ['Solver error: ' + (err?.message ?? String(err))] };
+      return { pixels: [], warnings: ['Solver error: ' + toSolveError(err).message] };
+    }
+  },
+
+  async back_project(req: BackProjectRequest): Promise<BackProjectResponse | null> {
+    try {
+      await initPromise;
+      const out = wasmBackProject(JSON.stringify(req));
+      return JSON.parse(out) as BackProjectResponse;
+    } catch (err: any) {
+      console.error('[solverWorker] back_project error:', err);
+      return null;
+    }
+  },
+
+  async bundle_adjust(req: BundleRequest): Promise<{ ok: true; response: BundleResponse } | { ok: false; error: SolveError }> {
+    try {
+      await initPromise;
+      const out = wasmBundleAdjust(JSON.stringify(req));
+      return { ok: true, response: JSON.parse(out) as BundleResponse };
+    } catch (err: any) {
+      return { ok: false, error: toSolveError(err) };
+    }
+  },
+
+  async triangulate(req: TriangulateRequest): Promise<{ ok: true; response: TriangulateResponse } | { ok: false; error: SolveError }> {
+    try {
+      await initPromise;
+      const out = wasmTriangulate(JSON.stringify(req));
+      return { ok: true, response: JSON.parse(out) as TriangulateResponse };
+    } catch (err: any) {
+      return { ok: false, error: toSolveError(err) };
     }
   },
 };